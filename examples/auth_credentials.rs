@@ -4,25 +4,34 @@
 
 use kraken_api_client::auth::{
     Credentials, CredentialsProvider, EnvCredentials, IncreasingNonce, NonceProvider,
-    StaticCredentials, sign_request,
+    RotatingCredentials, StaticCredentials, sign_request,
 };
 use kraken_api_client::futures::sign_futures_request;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Static credentials (typically used in tests or explicit config).
     let static_creds = StaticCredentials::new("api_key", "YXBpX3NlY3JldA==");
-    println!("Static key: {}", static_creds.get_credentials().api_key);
+    println!(
+        "Static key: {}",
+        static_creds.get_credentials().await?.api_key
+    );
 
     // Environment credentials are convenient for local dev.
     if let Some(env_creds) = EnvCredentials::try_from_env() {
         println!(
             "Loaded env credentials: {}",
-            env_creds.get_credentials().api_key
+            env_creds.get_credentials().await?.api_key
         );
     } else {
         println!("Set KRAKEN_API_KEY and KRAKEN_API_SECRET to load env credentials.");
     }
 
+    // Rotating credentials swap keys without rebuilding clients.
+    let rotating = RotatingCredentials::new(Credentials::new("old_key", "YXBpX3NlY3JldA=="));
+    rotating.rotate(Credentials::new("new_key", "YXBpX3NlY3JldA=="));
+    println!("Rotated key: {}", rotating.get_credentials().await?.api_key);
+
     // Nonce generation for authenticated requests.
     let nonce = IncreasingNonce::new();
     let next_nonce = nonce.next_nonce();
@@ -2,7 +2,7 @@
 //!
 //! Run with: cargo run --example error_handling
 
-use kraken_api_client::error::{error_codes, ApiError};
+use kraken_api_client::error::{error_codes, ApiError, KrakenErrorCode};
 use kraken_api_client::KrakenError;
 
 fn main() {
@@ -12,6 +12,13 @@ fn main() {
     println!("Is rate limit: {}", api_error.is_rate_limit());
 
     let err = KrakenError::Api(api_error.clone());
+    println!("Error class: {:?}", err.class());
+    match err.code() {
+        Some(KrakenErrorCode::RateLimitExceeded) => println!("Matched typed rate limit code"),
+        Some(other) => println!("Other code: {}", other),
+        None => println!("Not an API error"),
+    }
+
     match err {
         KrakenError::Api(inner) => {
             if inner.full_code() == error_codes::RATE_LIMIT_EXCEEDED {
@@ -9,6 +9,7 @@ use kraken_api_client::auth::EnvCredentials;
 use kraken_api_client::futures::rest::{
     BatchOrderRequest, EditOrderRequest, FillsRequest, FuturesRestClient, SendOrderRequest,
 };
+use kraken_api_client::futures::TriggerSignal;
 use kraken_api_client::BuySell;
 use rust_decimal::Decimal;
 
@@ -118,7 +119,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Decimal::from(5),
         Decimal::from(49000),
     )
-    .trigger_signal("mark");
+    .trigger_signal(TriggerSignal::Mark);
     println!("Constructed stop order type: {:?}", stop.order_type);
 
     Ok(())
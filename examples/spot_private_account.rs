@@ -50,6 +50,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .get_open_orders(Some(&OpenOrdersRequest {
             trades: Some(true),
             userref: None,
+            cl_ord_id: None,
         }))
         .await?;
     println!("Open orders: {}", open_orders.open.len());
@@ -59,6 +60,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .get_closed_orders(Some(&ClosedOrdersRequest {
             trades: Some(false),
             userref: None,
+            cl_ord_id: None,
             start: None,
             end: None,
             ofs: Some(0),
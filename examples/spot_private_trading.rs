@@ -9,6 +9,7 @@ use std::sync::Arc;
 use kraken_api_client::auth::EnvCredentials;
 use kraken_api_client::spot::rest::private::CancelOrderRequest;
 use kraken_api_client::spot::rest::SpotRestClient;
+use kraken_api_client::types::TimeInForce;
 use kraken_api_client::{BuySell, OrderType};
 use rust_decimal::Decimal;
 
@@ -32,7 +33,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Decimal::from_str("0.001")?,
     )
     .price(Decimal::from_str("50000")?)
-    .time_in_force("GTC")
+    .time_in_force(TimeInForce::GTC)
     .post_only()
     .validate(true);
 
@@ -1,7 +1,12 @@
 //! Credential management for Kraken API authentication.
 
+use futures_util::future::{self, BoxFuture};
 use secrecy::{ExposeSecret, SecretString};
-use std::sync::Arc;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::error::KrakenError;
 
 /// API credentials containing the key and secret.
 #[derive(Clone)]
@@ -10,6 +15,8 @@ pub struct Credentials {
     pub api_key: String,
     /// The API secret (private, used for signing)
     api_secret: SecretString,
+    /// Two-factor password for keys that require one (spot only)
+    otp: Option<SecretString>,
 }
 
 impl Credentials {
@@ -18,15 +25,32 @@ impl Credentials {
         Self {
             api_key: api_key.into(),
             api_secret: SecretString::from(api_secret.into()),
+            otp: None,
         }
     }
 
+    /// Set the two-factor password sent as `otp` with private spot requests.
+    ///
+    /// Needed only for keys protected by a static 2FA password in the Kraken
+    /// API key settings. Futures requests ignore it.
+    pub fn with_otp(mut self, otp: impl Into<String>) -> Self {
+        self.otp = Some(SecretString::from(otp.into()));
+        self
+    }
+
     /// Get the API secret for signing.
     ///
     /// This method exposes the secret - use carefully.
     pub fn expose_secret(&self) -> &str {
         self.api_secret.expose_secret()
     }
+
+    /// Get the two-factor password, if set.
+    ///
+    /// This method exposes the secret - use carefully.
+    pub fn expose_otp(&self) -> Option<&str> {
+        self.otp.as_ref().map(|otp| otp.expose_secret())
+    }
 }
 
 impl std::fmt::Debug for Credentials {
@@ -34,43 +58,66 @@ impl std::fmt::Debug for Credentials {
         f.debug_struct("Credentials")
             .field("api_key", &self.api_key)
             .field("api_secret", &"[REDACTED]")
+            .field("otp", &self.otp.as_ref().map(|_| "[REDACTED]"))
             .finish()
     }
 }
 
+/// Future returned by [`CredentialsProvider::get_credentials`].
+pub type CredentialsFuture<'a> = BoxFuture<'a, Result<Arc<Credentials>, KrakenError>>;
+
 /// Trait for providing API credentials.
 ///
 /// Implement this trait to customize how credentials are retrieved,
 /// for example from a secrets manager or environment variables.
+///
+/// Clients ask the provider for credentials on every signed request and
+/// every WebSocket authentication, so a provider that rotates keys takes
+/// effect without rebuilding the client. Providers that fetch remotely should
+/// cache and refresh in the background (see [`RefreshingCredentials`]) rather
+/// than fetch on every call.
 pub trait CredentialsProvider: Send + Sync {
-    /// Get the credentials.
-    fn get_credentials(&self) -> &Credentials;
+    /// Get the credentials to sign the next request with.
+    fn get_credentials(&self) -> CredentialsFuture<'_>;
 }
 
 /// Static credentials provider that holds credentials directly.
 #[derive(Clone)]
 pub struct StaticCredentials {
-    credentials: Credentials,
+    credentials: Arc<Credentials>,
 }
 
 impl StaticCredentials {
     /// Create a new static credentials provider.
     pub fn new(api_key: impl Into<String>, api_secret: impl Into<String>) -> Self {
         Self {
-            credentials: Credentials::new(api_key, api_secret),
+            credentials: Arc::new(Credentials::new(api_key, api_secret)),
         }
     }
+
+    /// The held credentials.
+    pub fn credentials(&self) -> &Credentials {
+        &self.credentials
+    }
 }
 
 impl CredentialsProvider for StaticCredentials {
-    fn get_credentials(&self) -> &Credentials {
-        &self.credentials
+    fn get_credentials(&self) -> CredentialsFuture<'_> {
+        Box::pin(future::ready(Ok(self.credentials.clone())))
+    }
+}
+
+impl From<Credentials> for StaticCredentials {
+    fn from(credentials: Credentials) -> Self {
+        Self {
+            credentials: Arc::new(credentials),
+        }
     }
 }
 
 impl CredentialsProvider for Arc<StaticCredentials> {
-    fn get_credentials(&self) -> &Credentials {
-        &self.credentials
+    fn get_credentials(&self) -> CredentialsFuture<'_> {
+        Box::pin(future::ready(Ok(self.credentials.clone())))
     }
 }
 
@@ -78,7 +125,7 @@ impl CredentialsProvider for Arc<StaticCredentials> {
 ///
 /// By default, reads from `KRAKEN_API_KEY` and `KRAKEN_API_SECRET`.
 pub struct EnvCredentials {
-    credentials: Credentials,
+    credentials: Arc<Credentials>,
 }
 
 impl EnvCredentials {
@@ -105,7 +152,7 @@ impl EnvCredentials {
             .unwrap_or_else(|_| panic!("Environment variable {secret_var} not set"));
 
         Self {
-            credentials: Credentials::new(api_key, api_secret),
+            credentials: Arc::new(Credentials::new(api_key, api_secret)),
         }
     }
 
@@ -124,14 +171,123 @@ impl EnvCredentials {
         let api_secret = std::env::var(secret_var).ok()?;
 
         Some(Self {
-            credentials: Credentials::new(api_key, api_secret),
+            credentials: Arc::new(Credentials::new(api_key, api_secret)),
         })
     }
+
+    /// The loaded credentials.
+    pub fn credentials(&self) -> &Credentials {
+        &self.credentials
+    }
 }
 
 impl CredentialsProvider for EnvCredentials {
-    fn get_credentials(&self) -> &Credentials {
-        &self.credentials
+    fn get_credentials(&self) -> CredentialsFuture<'_> {
+        Box::pin(future::ready(Ok(self.credentials.clone())))
+    }
+}
+
+/// Credentials provider whose key can be swapped at runtime.
+///
+/// Requests signed after [`rotate`](Self::rotate) returns use the new key;
+/// requests already in flight keep the key they were signed with.
+pub struct RotatingCredentials {
+    current: RwLock<Arc<Credentials>>,
+}
+
+impl RotatingCredentials {
+    /// Create a provider starting with `credentials`.
+    pub fn new(credentials: Credentials) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(credentials)),
+        }
+    }
+
+    /// Replace the credentials used for subsequent requests.
+    pub fn rotate(&self, credentials: Credentials) {
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(credentials);
+    }
+
+    /// The credentials currently in use.
+    pub fn current(&self) -> Arc<Credentials> {
+        self.current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl CredentialsProvider for RotatingCredentials {
+    fn get_credentials(&self) -> CredentialsFuture<'_> {
+        Box::pin(future::ready(Ok(self.current())))
+    }
+}
+
+/// Credentials provider that re-fetches its key in the background.
+///
+/// The key is fetched once up front, then again every `interval` by a tokio
+/// task. A failed refresh is logged and the previous key stays in use. The
+/// task stops when the provider is dropped.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use std::time::Duration;
+///
+/// use kraken_api_client::auth::{Credentials, RefreshingCredentials};
+///
+/// let credentials = RefreshingCredentials::spawn(Duration::from_secs(300), || async {
+///     let secret = secrets_manager.get("kraken/trading").await?;
+///     Ok(Credentials::new(secret.key, secret.secret))
+/// })
+/// .await?;
+/// let client = SpotRestClient::builder().credentials(credentials).build();
+/// ```
+pub struct RefreshingCredentials {
+    inner: RotatingCredentials,
+}
+
+impl RefreshingCredentials {
+    /// Fetch the credentials and start refreshing them every `interval`.
+    pub async fn spawn<F, Fut>(interval: Duration, fetch: F) -> Result<Arc<Self>, KrakenError>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Credentials, KrakenError>> + Send + 'static,
+    {
+        let provider = Arc::new(Self {
+            inner: RotatingCredentials::new(fetch().await?),
+        });
+
+        let weak = Arc::downgrade(&provider);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if weak.strong_count() == 0 {
+                    break;
+                }
+                let result = fetch().await;
+                let Some(provider) = weak.upgrade() else {
+                    break;
+                };
+                match result {
+                    Ok(credentials) => provider.inner.rotate(credentials),
+                    Err(e) => tracing::warn!("Failed to refresh credentials: {}", e),
+                }
+            }
+        });
+
+        Ok(provider)
+    }
+
+    /// The credentials currently in use.
+    pub fn current(&self) -> Arc<Credentials> {
+        self.inner.current()
+    }
+}
+
+impl CredentialsProvider for RefreshingCredentials {
+    fn get_credentials(&self) -> CredentialsFuture<'_> {
+        self.inner.get_credentials()
     }
 }
 
@@ -148,11 +304,47 @@ mod tests {
         assert!(debug_str.contains("[REDACTED]"));
     }
 
-    #[test]
-    fn test_static_credentials() {
+    #[tokio::test]
+    async fn test_static_credentials() {
         let provider = StaticCredentials::new("key", "secret");
-        let creds = provider.get_credentials();
+        let creds = provider.get_credentials().await.unwrap();
         assert_eq!(creds.api_key, "key");
         assert_eq!(creds.expose_secret(), "secret");
     }
+
+    #[tokio::test]
+    async fn test_rotating_credentials() {
+        let provider = RotatingCredentials::new(Credentials::new("old", "s1"));
+        let before = provider.get_credentials().await.unwrap();
+
+        provider.rotate(Credentials::new("new", "s2"));
+        assert_eq!(provider.get_credentials().await.unwrap().api_key, "new");
+        // Credentials handed out earlier are unaffected.
+        assert_eq!(before.api_key, "old");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_refreshing_credentials() {
+        let fetches = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter = fetches.clone();
+        let provider = RefreshingCredentials::spawn(Duration::from_secs(60), move || {
+            let n = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async move {
+                if n == 2 {
+                    return Err(KrakenError::Auth("vault sealed".into()));
+                }
+                Ok(Credentials::new(format!("key-{}", n), "secret"))
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(provider.current().api_key, "key-0");
+
+        tokio::time::sleep(Duration::from_secs(61)).await;
+        assert_eq!(provider.get_credentials().await.unwrap().api_key, "key-1");
+
+        // A failed refresh keeps the previous key.
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert_eq!(provider.current().api_key, "key-1");
+    }
 }
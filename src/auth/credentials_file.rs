@@ -0,0 +1,322 @@
+//! Credentials loaded from a file of named profiles.
+//!
+//! The file holds one table per profile, each with optional spot and futures
+//! keys, an optional two-factor password for the spot key and a demo flag.
+//! It may be written as TOML:
+//!
+//! ```toml
+//! [trading]
+//! otp = "spot-2fa-password"
+//!
+//! [trading.spot]
+//! api_key = "..."
+//! api_secret = "..."
+//!
+//! [trading.futures]
+//! api_key = "..."
+//! api_secret = "..."
+//!
+//! [testnet]
+//! demo = true
+//!
+//! [testnet.futures]
+//! api_key = "..."
+//! api_secret = "..."
+//! ```
+//!
+//! or as the equivalent JSON object. Files readable by other users are
+//! rejected.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+
+use crate::auth::{Credentials, StaticCredentials};
+use crate::error::KrakenError;
+
+/// Environment variable overriding the default credentials file location.
+pub const CREDENTIALS_FILE_ENV: &str = "KRAKEN_CREDENTIALS_FILE";
+
+/// A parsed credentials file.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+///
+/// use kraken_api_client::auth::CredentialsFile;
+/// use kraken_api_client::spot::rest::SpotRestClient;
+///
+/// # fn main() -> Result<(), kraken_api_client::KrakenError> {
+/// let file = CredentialsFile::load_default()?;
+/// let client = SpotRestClient::builder()
+///     .credentials(Arc::new(file.profile("trading")?.spot()?))
+///     .build();
+///
+/// // Or, equivalently:
+/// let client = SpotRestClient::builder().profile("trading")?.build();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CredentialsFile {
+    path: PathBuf,
+    profiles: HashMap<String, CredentialsProfile>,
+}
+
+/// One named profile from a [`CredentialsFile`].
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CredentialsProfile {
+    #[serde(skip)]
+    name: String,
+    spot: Option<ProfileKey>,
+    futures: Option<ProfileKey>,
+    otp: Option<SecretString>,
+    #[serde(default)]
+    demo: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileKey {
+    api_key: String,
+    api_secret: SecretString,
+}
+
+impl CredentialsFile {
+    /// Default location of the credentials file.
+    ///
+    /// This is `$KRAKEN_CREDENTIALS_FILE` if set, otherwise
+    /// `kraken/credentials` under `$XDG_CONFIG_HOME` or `~/.config`.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os(CREDENTIALS_FILE_ENV).filter(|p| !p.is_empty()) {
+            return Some(PathBuf::from(path));
+        }
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME")
+                    .or_else(|| std::env::var_os("USERPROFILE"))
+                    .map(|home| PathBuf::from(home).join(".config"))
+            })?;
+        Some(config_dir.join("kraken").join("credentials"))
+    }
+
+    /// Load the credentials file from [`default_path`](Self::default_path).
+    pub fn load_default() -> Result<Self, KrakenError> {
+        let path = Self::default_path().ok_or_else(|| {
+            KrakenError::Auth("cannot locate credentials file: HOME is not set".to_string())
+        })?;
+        Self::load(path)
+    }
+
+    /// Load a credentials file.
+    ///
+    /// Fails if the file is readable by other users.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, KrakenError> {
+        let path = path.as_ref();
+        check_permissions(path)?;
+        let contents = std::fs::read_to_string(path)?;
+        Self::parse(path, &contents)
+    }
+
+    /// Parse the contents of a credentials file, as TOML or, if it starts
+    /// with `{`, as JSON.
+    fn parse(path: &Path, contents: &str) -> Result<Self, KrakenError> {
+        let invalid = |e: &dyn std::fmt::Display| {
+            KrakenError::Auth(format!(
+                "invalid credentials file {}: {}",
+                path.display(),
+                e
+            ))
+        };
+        let mut profiles: HashMap<String, CredentialsProfile> =
+            if contents.trim_start().starts_with('{') {
+                serde_json::from_str(contents).map_err(|e| invalid(&e))?
+            } else {
+                toml::from_str(contents).map_err(|e| invalid(&e))?
+            };
+        for (name, profile) in &mut profiles {
+            profile.name.clone_from(name);
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            profiles,
+        })
+    }
+
+    /// Path the file was loaded from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Names of the profiles in the file.
+    pub fn profile_names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// Look up a profile by name.
+    pub fn profile(&self, name: &str) -> Result<&CredentialsProfile, KrakenError> {
+        self.profiles.get(name).ok_or_else(|| {
+            KrakenError::Auth(format!(
+                "profile '{}' not found in {}",
+                name,
+                self.path.display()
+            ))
+        })
+    }
+}
+
+impl CredentialsProfile {
+    /// Name of the profile.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the profile's keys are for the demo environment.
+    pub fn is_demo(&self) -> bool {
+        self.demo
+    }
+
+    /// Credentials provider for the spot key, including the two-factor
+    /// password if one is set.
+    ///
+    /// Fails for demo profiles, since Kraken has no spot demo environment.
+    pub fn spot(&self) -> Result<StaticCredentials, KrakenError> {
+        if self.demo {
+            return Err(KrakenError::Auth(format!(
+                "profile '{}' is a demo profile, but there is no spot demo environment",
+                self.name
+            )));
+        }
+        let mut credentials = self.key(self.spot.as_ref(), "spot")?;
+        if let Some(otp) = &self.otp {
+            credentials = credentials.with_otp(otp.expose_secret());
+        }
+        Ok(credentials.into())
+    }
+
+    /// Credentials provider for the futures key.
+    pub fn futures(&self) -> Result<StaticCredentials, KrakenError> {
+        Ok(self.key(self.futures.as_ref(), "futures")?.into())
+    }
+
+    fn key(&self, key: Option<&ProfileKey>, kind: &str) -> Result<Credentials, KrakenError> {
+        let key = key.ok_or_else(|| {
+            KrakenError::Auth(format!("profile '{}' has no {} key", self.name, kind))
+        })?;
+        Ok(Credentials::new(
+            key.api_key.clone(),
+            key.api_secret.expose_secret(),
+        ))
+    }
+}
+
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<(), KrakenError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path)?.permissions().mode();
+    if mode & 0o004 != 0 {
+        return Err(KrakenError::Auth(format!(
+            "credentials file {} is readable by other users; restrict it with `chmod 600`",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> Result<(), KrakenError> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+[trading]
+otp = "hunter2"
+
+[trading.spot]
+api_key = "spot_key"
+api_secret = "c3BvdA=="
+
+[trading.futures]
+api_key = "futures_key"
+api_secret = "ZnV0dXJlcw=="
+
+[testnet]
+demo = true
+
+[testnet.futures]
+api_key = "demo_key"
+api_secret = "ZGVtbw=="
+"#;
+
+    fn parse(contents: &str) -> Result<CredentialsFile, KrakenError> {
+        CredentialsFile::parse(Path::new("credentials"), contents)
+    }
+
+    #[test]
+    fn test_parse_toml_profiles() {
+        let file = parse(TOML).unwrap();
+
+        let trading = file.profile("trading").unwrap();
+        assert!(!trading.is_demo());
+        let spot = trading.spot().unwrap();
+        assert_eq!(spot.credentials().api_key, "spot_key");
+        assert_eq!(spot.credentials().expose_otp(), Some("hunter2"));
+        let futures = trading.futures().unwrap();
+        assert_eq!(futures.credentials().api_key, "futures_key");
+        assert_eq!(futures.credentials().expose_otp(), None);
+
+        let testnet = file.profile("testnet").unwrap();
+        assert!(testnet.is_demo());
+        assert_eq!(testnet.futures().unwrap().credentials().api_key, "demo_key");
+        assert!(testnet.spot().is_err());
+
+        assert!(file.profile("missing").is_err());
+    }
+
+    #[test]
+    fn test_parse_json_profiles() {
+        let file =
+            parse(r#"{"trading": {"spot": {"api_key": "spot_key", "api_secret": "c3BvdA=="}}}"#)
+                .unwrap();
+
+        let trading = file.profile("trading").unwrap();
+        assert_eq!(trading.spot().unwrap().credentials().api_key, "spot_key");
+        assert!(trading.futures().is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_file() {
+        assert!(parse("[trading]\napi_key = \"misplaced\"").is_err());
+        assert!(parse("{not json").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_load_rejects_world_readable_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("kraken-credentials-{}", std::process::id()));
+        std::fs::write(&path, TOML).unwrap();
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(CredentialsFile::load(&path).is_err());
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        let file = CredentialsFile::load(&path).unwrap();
+        assert_eq!(file.path(), path);
+        assert_eq!(file.profile_names().count(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
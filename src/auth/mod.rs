@@ -2,13 +2,22 @@
 //!
 //! This module provides:
 //! - Credential management with secure secret storage
-//! - Nonce generation for replay attack prevention
+//! - Named credential profiles loaded from a config file
+//! - Nonce generation for replay attack prevention, in memory or persisted to a file
 //! - HMAC-SHA512 signature generation for authenticated requests
 
 mod credentials;
+mod credentials_file;
 mod nonce;
+mod persistent_nonce;
 mod signature;
 
-pub use credentials::{Credentials, CredentialsProvider, EnvCredentials, StaticCredentials};
-pub use nonce::{IncreasingNonce, NonceProvider};
+pub use credentials::{
+    Credentials, CredentialsFuture, CredentialsProvider, EnvCredentials, RefreshingCredentials,
+    RotatingCredentials, StaticCredentials,
+};
+pub use credentials_file::{CREDENTIALS_FILE_ENV, CredentialsFile, CredentialsProfile};
+pub(crate) use nonce::{recovery_floor, report_rejected_nonce};
+pub use nonce::{IncreasingNonce, NONCE_RECOVERY_MARGIN_MICROS, NonceProvider};
+pub use persistent_nonce::PersistentNonce;
 pub use signature::sign_request;
@@ -15,6 +15,45 @@ pub trait NonceProvider: Send + Sync {
     ///
     /// This value must be greater than any previously returned value.
     fn next_nonce(&self) -> u64;
+
+    /// Move later nonces ahead after Kraken rejects `nonce`.
+    ///
+    /// A rejection usually means another client sharing the key has used a
+    /// higher nonce, whose value is unknown. The built-in providers jump
+    /// [`NONCE_RECOVERY_MARGIN_MICROS`] past the later of `nonce` and the
+    /// current time, which overtakes a client whose clock runs up to that far
+    /// ahead; a resend still fails against one further ahead. The default
+    /// does nothing.
+    fn advance_past(&self, nonce: u64) {
+        let _ = nonce;
+    }
+}
+
+/// How far [`NonceProvider::advance_past`] moves the built-in providers
+/// beyond the rejected nonce or the current time, in microseconds.
+pub const NONCE_RECOVERY_MARGIN_MICROS: u64 = 1_000_000;
+
+/// First nonce to issue after Kraken rejects `nonce`.
+pub(crate) fn recovery_floor(nonce: u64, now_micros: u64) -> u64 {
+    nonce
+        .max(now_micros)
+        .saturating_add(NONCE_RECOVERY_MARGIN_MICROS)
+}
+
+/// Record a nonce rejection as a tracing event and a metric.
+///
+/// Repeated rejections usually mean the API key is shared by several clients
+/// or processes, each with its own nonce source.
+pub(crate) fn report_rejected_nonce(endpoint: &str, nonce: u64) {
+    tracing::warn!(
+        endpoint,
+        nonce,
+        "Kraken rejected nonce {} on {}; is the API key shared?",
+        nonce,
+        endpoint
+    );
+    metrics::counter!("kraken_nonce_rejections_total", "endpoint" => endpoint.to_string())
+        .increment(1);
 }
 
 /// A nonce provider that generates strictly increasing nonces based on time.
@@ -68,6 +107,11 @@ impl NonceProvider for IncreasingNonce {
             // If CAS failed, another thread updated the value. Retry.
         }
     }
+
+    fn advance_past(&self, nonce: u64) {
+        let floor = recovery_floor(nonce, Self::current_time_micros());
+        self.last_nonce.fetch_max(floor - 1, Ordering::SeqCst);
+    }
 }
 
 #[cfg(test)]
@@ -88,6 +132,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_nonce_advance_past_overtakes_shared_key() {
+        // Two clients share a key; the other one's clock runs 200ms ahead,
+        // so Kraken has already accepted a nonce above ours.
+        let ours = IncreasingNonce::new();
+        let rejected = ours.next_nonce();
+        let accepted = IncreasingNonce::current_time_micros() + 200_000;
+        assert!(accepted > rejected);
+
+        ours.advance_past(rejected);
+        let retry = ours.next_nonce();
+        assert!(retry > accepted);
+
+        // Nonces keep increasing afterwards rather than falling back to
+        // the clock.
+        assert!(ours.next_nonce() > retry);
+    }
+
     #[test]
     fn test_nonce_unique_across_threads() {
         let provider = std::sync::Arc::new(IncreasingNonce::new());
@@ -0,0 +1,303 @@
+//! File-backed nonce provider shared across processes.
+//!
+//! [`PersistentNonce`] keeps a high-water mark in a file. Each nonce is
+//! reserved under an exclusive advisory lock, above both the mark and the
+//! current time in microseconds, and written back before it is issued. Every
+//! nonce is therefore higher than any nonce issued before it by any process
+//! sharing the file, and nonces never go backwards across restarts, even if
+//! the clock does.
+//!
+//! The mark is written without an fsync. It reaches the page cache before
+//! the lock is released, which is enough for other processes and for a
+//! restart after a process crash; only an OS crash can lose it, and nonces
+//! issued after that still start above the current time.
+//!
+//! A single process can trade that ordering for fewer file writes with
+//! [`PersistentNonce::with_block_size`], which reserves a block of nonces at
+//! a time and issues them from memory. Nonces stay unique, but processes
+//! working through older blocks issue lower nonces than others just used, so
+//! Kraken rejects them with `EAPI:Invalid nonce` unless the key's nonce
+//! window is raised in the account settings.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use fs4::fs_std::FileExt;
+
+use crate::auth::{NonceProvider, recovery_floor};
+use crate::error::KrakenError;
+
+/// A nonce provider that persists its high-water mark to a file.
+///
+/// With the default block size, every [`next_nonce`](NonceProvider::next_nonce)
+/// locks, reads and rewrites the file with blocking calls on the calling
+/// thread, which for an async client is a runtime worker. Uncontended this
+/// takes microseconds, but while another process holds the lock the
+/// worker is blocked until it is released. Processes that sign many requests
+/// concurrently can reserve blocks with [`with_block_size`](Self::with_block_size)
+/// instead, at the cost of ordering across processes.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+///
+/// use kraken_api_client::auth::{PersistentNonce, StaticCredentials};
+/// use kraken_api_client::spot::rest::SpotRestClient;
+///
+/// # fn main() -> Result<(), kraken_api_client::KrakenError> {
+/// let nonce = PersistentNonce::open("/var/lib/trader/kraken.nonce")?;
+/// let client = SpotRestClient::builder()
+///     .credentials(Arc::new(StaticCredentials::new("key", "secret")))
+///     .nonce_provider(Arc::new(nonce))
+///     .build();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct PersistentNonce {
+    path: PathBuf,
+    block_size: u64,
+    block: Mutex<Block>,
+}
+
+/// Nonces reserved by this process: `next..end`.
+#[derive(Debug, Default)]
+struct Block {
+    next: u64,
+    end: u64,
+}
+
+impl PersistentNonce {
+    /// Default number of nonces reserved per file access.
+    ///
+    /// One nonce per access keeps nonces in order across processes.
+    pub const DEFAULT_BLOCK_SIZE: u64 = 1;
+
+    /// Open or create the nonce file, reserving one nonce per request.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, KrakenError> {
+        Self::with_block_size(path, Self::DEFAULT_BLOCK_SIZE)
+    }
+
+    /// Open or create the nonce file, reserving `block_size` nonces at a time.
+    ///
+    /// Blocks larger than 1 write the file less often, but let processes
+    /// sharing it issue nonces out of order. The file is read immediately,
+    /// so an unusable path is reported here rather than on the first request,
+    /// but nothing is reserved until a nonce is needed.
+    pub fn with_block_size(path: impl AsRef<Path>, block_size: u64) -> Result<Self, KrakenError> {
+        let provider = Self {
+            path: path.as_ref().to_path_buf(),
+            block_size: block_size.max(1),
+            block: Mutex::new(Block::default()),
+        };
+        let mut file = provider.open_locked()?;
+        let result = provider.read_high_water(&mut file);
+        FileExt::unlock(&file)?;
+        result?;
+        Ok(provider)
+    }
+
+    /// Path of the nonce file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of nonces reserved per file access.
+    pub fn block_size(&self) -> u64 {
+        self.block_size
+    }
+
+    fn lock_block(&self) -> std::sync::MutexGuard<'_, Block> {
+        self.block.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Reserve a new block starting above the file's high-water mark, the
+    /// local floor and the current time.
+    fn reserve(&self, block: &mut Block, floor: u64) -> Result<(), KrakenError> {
+        let mut file = self.open_locked()?;
+        let result = self.reserve_locked(&mut file, block, floor);
+        FileExt::unlock(&file)?;
+        result
+    }
+
+    /// Open or create the nonce file and take its exclusive lock.
+    fn open_locked(&self) -> Result<File, KrakenError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.path)?;
+        FileExt::lock_exclusive(&file)?;
+        Ok(file)
+    }
+
+    fn read_high_water(&self, file: &mut File) -> Result<u64, KrakenError> {
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        match contents.trim() {
+            "" => Ok(0),
+            value => value.parse::<u64>().map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("corrupt nonce file {}: {:?}", self.path.display(), value),
+                )
+                .into()
+            }),
+        }
+    }
+
+    fn reserve_locked(
+        &self,
+        file: &mut File,
+        block: &mut Block,
+        floor: u64,
+    ) -> Result<(), KrakenError> {
+        let high_water = self.read_high_water(file)?;
+
+        let start = high_water
+            .saturating_add(1)
+            .max(floor)
+            .max(block.next)
+            .max(current_time_micros());
+        let end = start.saturating_add(self.block_size);
+
+        file.seek(SeekFrom::Start(0))?;
+        file.set_len(0)?;
+        write!(file, "{}", end - 1)?;
+
+        block.next = start;
+        block.end = end;
+        Ok(())
+    }
+}
+
+impl NonceProvider for PersistentNonce {
+    fn next_nonce(&self) -> u64 {
+        let mut block = self.lock_block();
+        // Nonces are issued from the block without consulting the clock, so
+        // the file is only touched once per `block_size` requests. A new
+        // block starts at the current time, catching up with the clock.
+        if block.next >= block.end {
+            let floor = block.next;
+            if let Err(e) = self.reserve(&mut block, floor) {
+                // Keep issuing increasing nonces rather than failing the
+                // request; uniqueness across processes is no longer
+                // guaranteed until the file is usable again.
+                tracing::error!("Failed to reserve nonces in {}: {}", self.path.display(), e);
+                let next = floor.max(current_time_micros());
+                block.next = next;
+                block.end = next.saturating_add(1);
+            }
+        }
+        let nonce = block.next;
+        block.next += 1;
+        nonce
+    }
+
+    fn advance_past(&self, nonce: u64) {
+        let mut block = self.lock_block();
+        block.next = block.next.max(recovery_floor(nonce, current_time_micros()));
+    }
+}
+
+fn current_time_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("kraken-nonce-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn high_water(path: &Path) -> u64 {
+        std::fs::read_to_string(path).unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn test_blocks_are_reserved_in_file() {
+        let path = temp_path("blocks");
+        let provider = PersistentNonce::with_block_size(&path, 10).unwrap();
+
+        let first = provider.next_nonce();
+        assert!(high_water(&path) >= first);
+
+        let mut last = first;
+        for _ in 0..25 {
+            let nonce = provider.next_nonce();
+            assert!(nonce > last);
+            assert!(high_water(&path) >= nonce);
+            last = nonce;
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_is_written_once_per_block() {
+        let path = temp_path("amortised");
+        let provider = PersistentNonce::with_block_size(&path, 100).unwrap();
+        let mut last = provider.next_nonce();
+        let reserved = high_water(&path);
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+
+        // Requests spread over time draw from the same block.
+        for _ in 0..5 {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            let nonce = provider.next_nonce();
+            assert!(nonce > last && nonce <= reserved);
+            last = nonce;
+        }
+        assert_eq!(high_water(&path), reserved);
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().modified().unwrap(),
+            modified
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_restart_never_goes_backwards() {
+        let path = temp_path("restart");
+        // A mark far in the future, as if the clock had since moved back.
+        let future = current_time_micros() + 3_600_000_000;
+        std::fs::write(&path, future.to_string()).unwrap();
+
+        let provider = PersistentNonce::open(&path).unwrap();
+        let nonce = provider.next_nonce();
+        assert!(nonce > future);
+        drop(provider);
+
+        let reopened = PersistentNonce::open(&path).unwrap();
+        assert!(reopened.next_nonce() > nonce);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_advance_past_and_corrupt_file() {
+        let path = temp_path("advance");
+        let provider = PersistentNonce::with_block_size(&path, 5).unwrap();
+        let ahead = provider.next_nonce() + 1_000_000_000;
+
+        provider.advance_past(ahead);
+        let nonce = provider.next_nonce();
+        assert!(nonce > ahead);
+        assert!(high_water(&path) >= nonce);
+
+        std::fs::write(&path, "not a number").unwrap();
+        assert!(PersistentNonce::open(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -2,6 +2,8 @@
 
 use thiserror::Error;
 
+use crate::validation::OrderViolation;
+
 /// The main error type for all Kraken client operations.
 #[derive(Error, Debug)]
 pub enum KrakenError {
@@ -29,6 +31,10 @@ pub enum KrakenError {
     #[error("URL parsing error: {0}")]
     Url(#[from] url::ParseError),
 
+    /// Local file I/O error
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
     /// Kraken API returned an error
     #[error("Kraken API error: {0}")]
     Api(ApiError),
@@ -62,6 +68,72 @@ pub enum KrakenError {
     /// Missing required credentials
     #[error("Missing credentials: API key and secret required for private endpoints")]
     MissingCredentials,
+
+    /// Pair or asset name not found in the loaded market metadata
+    #[error("Unknown pair or asset: {0}")]
+    UnknownSymbol(String),
+
+    /// Order breaks the market's price or size constraints
+    #[error("Invalid order: {}", describe_violations(.0))]
+    InvalidOrder(Vec<OrderViolation>),
+}
+
+impl KrakenError {
+    /// Structured API error code, if this is an API error.
+    pub fn code(&self) -> Option<KrakenErrorCode> {
+        match self {
+            KrakenError::Api(error) => Some(error.error_code()),
+            KrakenError::RateLimitExceeded { .. } => Some(KrakenErrorCode::RateLimitExceeded),
+            _ => None,
+        }
+    }
+
+    /// Classify the error for retry decisions.
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            KrakenError::Http(e) => classify_http(e.is_timeout() || e.is_connect(), e.status()),
+            KrakenError::HttpMiddleware(reqwest_middleware::Error::Reqwest(e)) => {
+                classify_http(e.is_timeout() || e.is_connect(), e.status())
+            }
+            KrakenError::HttpMiddleware(_) => ErrorClass::Fatal,
+            KrakenError::WebSocket(_)
+            | KrakenError::WebSocketMsg(_)
+            | KrakenError::ConnectionClosed { .. }
+            | KrakenError::Timeout => ErrorClass::Retryable,
+            KrakenError::Api(error) => error.error_code().class(),
+            KrakenError::RateLimitExceeded { .. } => ErrorClass::BackOff,
+            KrakenError::Json(_)
+            | KrakenError::Url(_)
+            | KrakenError::Io(_)
+            | KrakenError::Auth(_)
+            | KrakenError::InvalidResponse(_)
+            | KrakenError::MissingCredentials => ErrorClass::Fatal,
+            KrakenError::UnknownSymbol(_) | KrakenError::InvalidOrder(_) => ErrorClass::UserError,
+        }
+    }
+
+    /// Whether the request can be retried as-is.
+    pub fn is_retryable(&self) -> bool {
+        self.class() == ErrorClass::Retryable
+    }
+}
+
+fn classify_http(transient: bool, status: Option<reqwest::StatusCode>) -> ErrorClass {
+    match status {
+        Some(status) if status == reqwest::StatusCode::TOO_MANY_REQUESTS => ErrorClass::BackOff,
+        Some(status) if status.is_server_error() => ErrorClass::Retryable,
+        Some(status) if status.is_client_error() => ErrorClass::UserError,
+        _ if transient => ErrorClass::Retryable,
+        _ => ErrorClass::Fatal,
+    }
+}
+
+fn describe_violations(violations: &[OrderViolation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 /// Kraken API error codes and messages.
@@ -110,35 +182,403 @@ impl ApiError {
         format!("{}:{}", self.code, self.message)
     }
 
+    /// Structured error code.
+    ///
+    /// Futures errors (code `EFutures`) are looked up by message.
+    pub fn error_code(&self) -> KrakenErrorCode {
+        if self.code == "EFutures" {
+            KrakenErrorCode::parse(&self.message)
+        } else {
+            KrakenErrorCode::parse(&self.full_code())
+        }
+    }
+
     /// Check if this is a rate limit error.
     pub fn is_rate_limit(&self) -> bool {
-        (self.code == "EAPI" && self.message.contains("Rate limit"))
-            || (self.code == "EOrder" && self.message.contains("Rate limit"))
+        matches!(
+            self.error_code(),
+            KrakenErrorCode::RateLimitExceeded
+                | KrakenErrorCode::OrderRateLimitExceeded
+                | KrakenErrorCode::DomainRateLimitExceeded
+                | KrakenErrorCode::FuturesApiLimitExceeded
+        )
     }
 
     /// Check if this is an invalid nonce error.
     pub fn is_invalid_nonce(&self) -> bool {
-        self.code == "EAPI" && self.message.contains("Invalid nonce")
+        matches!(
+            self.error_code(),
+            KrakenErrorCode::InvalidNonce
+                | KrakenErrorCode::FuturesNonceBelowThreshold
+                | KrakenErrorCode::FuturesNonceDuplicate
+        )
     }
 
     /// Check if this is an invalid key error.
     pub fn is_invalid_key(&self) -> bool {
-        self.code == "EAPI" && self.message.contains("Invalid key")
+        self.error_code() == KrakenErrorCode::InvalidKey
     }
 
     /// Check if this is an invalid signature error.
     pub fn is_invalid_signature(&self) -> bool {
-        self.code == "EAPI" && self.message.contains("Invalid signature")
+        self.error_code() == KrakenErrorCode::InvalidSignature
     }
 
     /// Check if this is a permission denied error.
     pub fn is_permission_denied(&self) -> bool {
-        self.code == "EGeneral" && self.message.contains("Permission denied")
+        self.error_code() == KrakenErrorCode::PermissionDenied
     }
 
     /// Check if this is a service unavailable error.
     pub fn is_service_unavailable(&self) -> bool {
-        self.code == "EService" && (self.message.contains("Unavailable") || self.message.contains("Busy"))
+        matches!(
+            self.error_code(),
+            KrakenErrorCode::ServiceUnavailable | KrakenErrorCode::ServiceBusy
+        )
+    }
+}
+
+/// How a caller should react to an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorClass {
+    /// Transient failure; the same request may succeed if retried
+    Retryable,
+    /// Rate limited or market restricted; retry only after waiting
+    BackOff,
+    /// Credentials, permissions or client bugs; retrying will not help
+    Fatal,
+    /// The request itself is invalid (funds, size, unknown order, ...)
+    UserError,
+}
+
+/// Known Kraken error codes, for exhaustive matching.
+///
+/// Covers the spot `ECategory:Message` strings and the futures error strings.
+/// Anything else parses to [`KrakenErrorCode::Unknown`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KrakenErrorCode {
+    // Spot general errors
+    /// `EGeneral:Invalid arguments`
+    InvalidArguments,
+    /// `EGeneral:Permission denied`
+    PermissionDenied,
+    /// `EGeneral:Unknown method`
+    UnknownMethod,
+    /// `EGeneral:Internal error`
+    InternalError,
+    /// `EGeneral:Temporary lockout`
+    TemporaryLockout,
+    /// `EGeneral:Too many requests`
+    TooManyRequests,
+
+    // Spot API errors
+    /// `EAPI:Invalid key`
+    InvalidKey,
+    /// `EAPI:Invalid signature`
+    InvalidSignature,
+    /// `EAPI:Invalid nonce`
+    InvalidNonce,
+    /// `EAPI:Rate limit exceeded`
+    RateLimitExceeded,
+    /// `EAPI:Feature disabled`
+    FeatureDisabled,
+
+    // Spot service errors
+    /// `EService:Unavailable`
+    ServiceUnavailable,
+    /// `EService:Busy`
+    ServiceBusy,
+    /// `EService:Deadline elapsed`
+    DeadlineElapsed,
+    /// `EService:Market in cancel_only mode`
+    MarketInCancelOnlyMode,
+    /// `EService:Market in post_only mode`
+    MarketInPostOnlyMode,
+    /// `EService:Market in limit_only mode`
+    MarketInLimitOnlyMode,
+
+    // Spot query errors
+    /// `EQuery:Unknown asset pair`
+    UnknownAssetPair,
+    /// `EQuery:Unknown asset`
+    UnknownAsset,
+
+    // Spot order errors
+    /// `EOrder:Rate limit exceeded`
+    OrderRateLimitExceeded,
+    /// `EOrder:Domain rate limit exceeded`
+    DomainRateLimitExceeded,
+    /// `EOrder:Insufficient funds`
+    InsufficientFunds,
+    /// `EOrder:Insufficient margin`
+    InsufficientMargin,
+    /// `EOrder:Invalid order`
+    InvalidOrder,
+    /// `EOrder:Unknown order`
+    UnknownOrder,
+    /// `EOrder:Unknown position`
+    UnknownPosition,
+    /// `EOrder:Orders limit exceeded`
+    OrdersLimitExceeded,
+    /// `EOrder:Positions limit exceeded`
+    PositionsLimitExceeded,
+    /// `EOrder:Margin limit exceeded`
+    MarginLimitExceeded,
+    /// `EOrder:Margin allowance exceeded`
+    MarginAllowanceExceeded,
+    /// `EOrder:Margin level too low`
+    MarginLevelTooLow,
+    /// `EOrder:Cannot open position`
+    CannotOpenPosition,
+    /// `EOrder:Order minimum not met`
+    OrderMinimumNotMet,
+    /// `EOrder:Cost minimum not met`
+    CostMinimumNotMet,
+    /// `EOrder:Tick size check failed`
+    TickSizeCheckFailed,
+    /// `EOrder:Trading agreement required`
+    TradingAgreementRequired,
+
+    // Spot funding errors
+    /// `EFunding:Unknown withdraw key`
+    UnknownWithdrawKey,
+    /// `EFunding:Invalid amount`
+    InvalidAmount,
+    /// `EFunding:Unknown reference id`
+    UnknownReferenceId,
+    /// `EFunding:Max fee exceeded`
+    MaxFeeExceeded,
+
+    // Futures errors
+    /// `apiLimitExceeded`
+    FuturesApiLimitExceeded,
+    /// `authenticationError`
+    FuturesAuthenticationError,
+    /// `accountInactive`
+    FuturesAccountInactive,
+    /// `requiredArgumentMissing`
+    FuturesRequiredArgumentMissing,
+    /// `invalidArgument`
+    FuturesInvalidArgument,
+    /// `nonceBelowThreshold`
+    FuturesNonceBelowThreshold,
+    /// `nonceDuplicate`
+    FuturesNonceDuplicate,
+    /// `insufficientAvailableFunds`
+    FuturesInsufficientAvailableFunds,
+    /// `marketUnavailable`
+    FuturesMarketUnavailable,
+    /// `Server Error`
+    FuturesServerError,
+
+    /// Any other error string, kept verbatim
+    Unknown(String),
+}
+
+impl KrakenErrorCode {
+    /// Parse a single error string.
+    ///
+    /// Spot errors may carry extra detail after the message
+    /// (e.g., `EGeneral:Invalid arguments:volume`); only the
+    /// `ECategory:Message` prefix is matched.
+    pub fn parse(error: &str) -> Self {
+        let error = error.trim();
+        let key = match error.match_indices(':').nth(1) {
+            Some((end, _)) if error.starts_with('E') => &error[..end],
+            _ => error,
+        };
+        match key {
+            "EGeneral:Invalid arguments" => Self::InvalidArguments,
+            "EGeneral:Permission denied" => Self::PermissionDenied,
+            "EGeneral:Unknown method" => Self::UnknownMethod,
+            "EGeneral:Internal error" => Self::InternalError,
+            "EGeneral:Temporary lockout" => Self::TemporaryLockout,
+            "EGeneral:Too many requests" => Self::TooManyRequests,
+            "EAPI:Invalid key" => Self::InvalidKey,
+            "EAPI:Invalid signature" => Self::InvalidSignature,
+            "EAPI:Invalid nonce" => Self::InvalidNonce,
+            "EAPI:Rate limit exceeded" => Self::RateLimitExceeded,
+            "EAPI:Feature disabled" => Self::FeatureDisabled,
+            "EService:Unavailable" => Self::ServiceUnavailable,
+            "EService:Busy" => Self::ServiceBusy,
+            "EService:Deadline elapsed" => Self::DeadlineElapsed,
+            "EService:Market in cancel_only mode" => Self::MarketInCancelOnlyMode,
+            "EService:Market in post_only mode" => Self::MarketInPostOnlyMode,
+            "EService:Market in limit_only mode" => Self::MarketInLimitOnlyMode,
+            "EQuery:Unknown asset pair" => Self::UnknownAssetPair,
+            "EQuery:Unknown asset" => Self::UnknownAsset,
+            "EOrder:Rate limit exceeded" => Self::OrderRateLimitExceeded,
+            "EOrder:Domain rate limit exceeded" => Self::DomainRateLimitExceeded,
+            "EOrder:Insufficient funds" => Self::InsufficientFunds,
+            "EOrder:Insufficient margin" => Self::InsufficientMargin,
+            "EOrder:Invalid order" => Self::InvalidOrder,
+            "EOrder:Unknown order" => Self::UnknownOrder,
+            "EOrder:Unknown position" => Self::UnknownPosition,
+            "EOrder:Orders limit exceeded" => Self::OrdersLimitExceeded,
+            "EOrder:Positions limit exceeded" => Self::PositionsLimitExceeded,
+            "EOrder:Margin limit exceeded" => Self::MarginLimitExceeded,
+            "EOrder:Margin allowance exceeded" => Self::MarginAllowanceExceeded,
+            "EOrder:Margin level too low" => Self::MarginLevelTooLow,
+            "EOrder:Cannot open position" => Self::CannotOpenPosition,
+            "EOrder:Order minimum not met" => Self::OrderMinimumNotMet,
+            "EOrder:Cost minimum not met" => Self::CostMinimumNotMet,
+            "EOrder:Tick size check failed" => Self::TickSizeCheckFailed,
+            "EOrder:Trading agreement required" => Self::TradingAgreementRequired,
+            "EFunding:Unknown withdraw key" => Self::UnknownWithdrawKey,
+            "EFunding:Invalid amount" => Self::InvalidAmount,
+            "EFunding:Unknown reference id" => Self::UnknownReferenceId,
+            "EFunding:Max fee exceeded" => Self::MaxFeeExceeded,
+            "apiLimitExceeded" => Self::FuturesApiLimitExceeded,
+            "authenticationError" => Self::FuturesAuthenticationError,
+            "accountInactive" => Self::FuturesAccountInactive,
+            "requiredArgumentMissing" => Self::FuturesRequiredArgumentMissing,
+            "invalidArgument" => Self::FuturesInvalidArgument,
+            "nonceBelowThreshold" => Self::FuturesNonceBelowThreshold,
+            "nonceDuplicate" => Self::FuturesNonceDuplicate,
+            "insufficientAvailableFunds" => Self::FuturesInsufficientAvailableFunds,
+            "marketUnavailable" => Self::FuturesMarketUnavailable,
+            "Server Error" => Self::FuturesServerError,
+            _ => Self::Unknown(error.to_string()),
+        }
+    }
+
+    /// Parse every entry of Kraken's error array.
+    pub fn from_error_array(errors: &[String]) -> Vec<Self> {
+        errors.iter().map(|e| Self::parse(e)).collect()
+    }
+
+    /// The error string as Kraken sends it.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::InvalidArguments => "EGeneral:Invalid arguments",
+            Self::PermissionDenied => "EGeneral:Permission denied",
+            Self::UnknownMethod => "EGeneral:Unknown method",
+            Self::InternalError => "EGeneral:Internal error",
+            Self::TemporaryLockout => "EGeneral:Temporary lockout",
+            Self::TooManyRequests => "EGeneral:Too many requests",
+            Self::InvalidKey => "EAPI:Invalid key",
+            Self::InvalidSignature => "EAPI:Invalid signature",
+            Self::InvalidNonce => "EAPI:Invalid nonce",
+            Self::RateLimitExceeded => "EAPI:Rate limit exceeded",
+            Self::FeatureDisabled => "EAPI:Feature disabled",
+            Self::ServiceUnavailable => "EService:Unavailable",
+            Self::ServiceBusy => "EService:Busy",
+            Self::DeadlineElapsed => "EService:Deadline elapsed",
+            Self::MarketInCancelOnlyMode => "EService:Market in cancel_only mode",
+            Self::MarketInPostOnlyMode => "EService:Market in post_only mode",
+            Self::MarketInLimitOnlyMode => "EService:Market in limit_only mode",
+            Self::UnknownAssetPair => "EQuery:Unknown asset pair",
+            Self::UnknownAsset => "EQuery:Unknown asset",
+            Self::OrderRateLimitExceeded => "EOrder:Rate limit exceeded",
+            Self::DomainRateLimitExceeded => "EOrder:Domain rate limit exceeded",
+            Self::InsufficientFunds => "EOrder:Insufficient funds",
+            Self::InsufficientMargin => "EOrder:Insufficient margin",
+            Self::InvalidOrder => "EOrder:Invalid order",
+            Self::UnknownOrder => "EOrder:Unknown order",
+            Self::UnknownPosition => "EOrder:Unknown position",
+            Self::OrdersLimitExceeded => "EOrder:Orders limit exceeded",
+            Self::PositionsLimitExceeded => "EOrder:Positions limit exceeded",
+            Self::MarginLimitExceeded => "EOrder:Margin limit exceeded",
+            Self::MarginAllowanceExceeded => "EOrder:Margin allowance exceeded",
+            Self::MarginLevelTooLow => "EOrder:Margin level too low",
+            Self::CannotOpenPosition => "EOrder:Cannot open position",
+            Self::OrderMinimumNotMet => "EOrder:Order minimum not met",
+            Self::CostMinimumNotMet => "EOrder:Cost minimum not met",
+            Self::TickSizeCheckFailed => "EOrder:Tick size check failed",
+            Self::TradingAgreementRequired => "EOrder:Trading agreement required",
+            Self::UnknownWithdrawKey => "EFunding:Unknown withdraw key",
+            Self::InvalidAmount => "EFunding:Invalid amount",
+            Self::UnknownReferenceId => "EFunding:Unknown reference id",
+            Self::MaxFeeExceeded => "EFunding:Max fee exceeded",
+            Self::FuturesApiLimitExceeded => "apiLimitExceeded",
+            Self::FuturesAuthenticationError => "authenticationError",
+            Self::FuturesAccountInactive => "accountInactive",
+            Self::FuturesRequiredArgumentMissing => "requiredArgumentMissing",
+            Self::FuturesInvalidArgument => "invalidArgument",
+            Self::FuturesNonceBelowThreshold => "nonceBelowThreshold",
+            Self::FuturesNonceDuplicate => "nonceDuplicate",
+            Self::FuturesInsufficientAvailableFunds => "insufficientAvailableFunds",
+            Self::FuturesMarketUnavailable => "marketUnavailable",
+            Self::FuturesServerError => "Server Error",
+            Self::Unknown(error) => error,
+        }
+    }
+
+    /// How callers should react to this error.
+    ///
+    /// Invalid nonces are retryable because a retry signs with a fresh nonce.
+    /// Unknown codes are treated as fatal.
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            Self::InternalError
+            | Self::InvalidNonce
+            | Self::ServiceUnavailable
+            | Self::ServiceBusy
+            | Self::DeadlineElapsed
+            | Self::FuturesNonceBelowThreshold
+            | Self::FuturesNonceDuplicate
+            | Self::FuturesServerError => ErrorClass::Retryable,
+
+            Self::TemporaryLockout
+            | Self::TooManyRequests
+            | Self::RateLimitExceeded
+            | Self::OrderRateLimitExceeded
+            | Self::DomainRateLimitExceeded
+            | Self::MarketInCancelOnlyMode
+            | Self::MarketInPostOnlyMode
+            | Self::MarketInLimitOnlyMode
+            | Self::FuturesApiLimitExceeded
+            | Self::FuturesMarketUnavailable => ErrorClass::BackOff,
+
+            Self::PermissionDenied
+            | Self::UnknownMethod
+            | Self::InvalidKey
+            | Self::InvalidSignature
+            | Self::FeatureDisabled
+            | Self::TradingAgreementRequired
+            | Self::FuturesAuthenticationError
+            | Self::FuturesAccountInactive
+            | Self::Unknown(_) => ErrorClass::Fatal,
+
+            Self::InvalidArguments
+            | Self::UnknownAssetPair
+            | Self::UnknownAsset
+            | Self::InsufficientFunds
+            | Self::InsufficientMargin
+            | Self::InvalidOrder
+            | Self::UnknownOrder
+            | Self::UnknownPosition
+            | Self::OrdersLimitExceeded
+            | Self::PositionsLimitExceeded
+            | Self::MarginLimitExceeded
+            | Self::MarginAllowanceExceeded
+            | Self::MarginLevelTooLow
+            | Self::CannotOpenPosition
+            | Self::OrderMinimumNotMet
+            | Self::CostMinimumNotMet
+            | Self::TickSizeCheckFailed
+            | Self::UnknownWithdrawKey
+            | Self::InvalidAmount
+            | Self::UnknownReferenceId
+            | Self::MaxFeeExceeded
+            | Self::FuturesRequiredArgumentMissing
+            | Self::FuturesInvalidArgument
+            | Self::FuturesInsufficientAvailableFunds => ErrorClass::UserError,
+        }
+    }
+}
+
+impl std::fmt::Display for KrakenErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for KrakenErrorCode {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::parse(s))
     }
 }
 
@@ -188,6 +628,64 @@ mod tests {
         assert!(error.is_invalid_key());
     }
 
+    #[test]
+    fn test_error_code_parsing() {
+        let errors = vec![
+            "EOrder:Insufficient funds".to_string(),
+            "EGeneral:Invalid arguments:volume".to_string(),
+            "EService:Market in cancel_only mode".to_string(),
+            "EFoo:Something new".to_string(),
+        ];
+        let codes = KrakenErrorCode::from_error_array(&errors);
+        assert_eq!(codes[0], KrakenErrorCode::InsufficientFunds);
+        assert_eq!(codes[1], KrakenErrorCode::InvalidArguments);
+        assert_eq!(codes[2], KrakenErrorCode::MarketInCancelOnlyMode);
+        assert_eq!(
+            codes[3],
+            KrakenErrorCode::Unknown("EFoo:Something new".to_string())
+        );
+        assert_eq!(codes[3].to_string(), "EFoo:Something new");
+
+        let error = ApiError::from_error_array(&errors[1..]).unwrap();
+        assert_eq!(error.error_code(), KrakenErrorCode::InvalidArguments);
+
+        let futures = ApiError::new("EFutures", "nonceBelowThreshold");
+        assert_eq!(
+            futures.error_code(),
+            KrakenErrorCode::FuturesNonceBelowThreshold
+        );
+    }
+
+    #[test]
+    fn test_error_classification() {
+        assert_eq!(
+            KrakenErrorCode::UnknownWithdrawKey.class(),
+            ErrorClass::UserError
+        );
+        assert_eq!(KrakenErrorCode::ServiceBusy.class(), ErrorClass::Retryable);
+        assert_eq!(
+            KrakenErrorCode::MarketInCancelOnlyMode.class(),
+            ErrorClass::BackOff
+        );
+        assert_eq!(KrakenErrorCode::InvalidKey.class(), ErrorClass::Fatal);
+
+        let err = KrakenError::Api(ApiError::new("EOrder", "Orders limit exceeded"));
+        assert_eq!(err.code(), Some(KrakenErrorCode::OrdersLimitExceeded));
+        assert_eq!(err.class(), ErrorClass::UserError);
+        assert!(!err.is_retryable());
+
+        let err = KrakenError::Api(ApiError::new("EFutures", "apiLimitExceeded"));
+        assert_eq!(err.class(), ErrorClass::BackOff);
+        assert!(KrakenError::Timeout.is_retryable());
+        assert_eq!(
+            KrakenError::RateLimitExceeded {
+                retry_after_ms: None
+            }
+            .class(),
+            ErrorClass::BackOff
+        );
+    }
+
     #[test]
     fn test_api_error_display() {
         let error = ApiError::new("EOrder", "Insufficient funds");
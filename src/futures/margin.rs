@@ -0,0 +1,657 @@
+//! Margin and liquidation estimates from instrument margin tiers.
+//!
+//! [`MarginCalculator`] applies an instrument's [`MarginLevel`] schedule to a
+//! hypothetical position and the account's collateral, so the effect of an
+//! order on margin and liquidation price can be checked before it is sent.
+//!
+//! Tiers are applied incrementally: each portion of the position is charged
+//! the rate of the tier it falls in. Liquidation prices are estimates; they
+//! assume the maintenance rate at the current size and ignore fees and
+//! funding.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kraken_api_client::futures::margin::{MarginCalculator, MarginSchedule};
+//!
+//! let schedule = MarginSchedule::from_instrument(&instrument).expect("margin levels");
+//! let calculator = MarginCalculator::from_account(schedule, &account).expect("balances");
+//!
+//! let impact = calculator.with_order(current.as_ref(), &order, mark_price);
+//! if !impact.is_affordable() {
+//!     return Err("insufficient margin");
+//! }
+//! println!("liquidation: {:?}", impact.after.liquidation_price);
+//! client.send_order(&order).await?;
+//! ```
+
+use rust_decimal::Decimal;
+
+use crate::futures::rest::SendOrderRequest;
+use crate::futures::types::{FuturesAccount, FuturesInstrument, FuturesPosition, MarginLevel};
+use crate::types::common::BuySell;
+
+/// How an instrument's margin and profit and loss are denominated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Settlement {
+    /// Margined in the quote currency (e.g., "PF_XBTUSD")
+    Linear,
+    /// Margined in the base currency (e.g., "PI_XBTUSD")
+    Inverse,
+}
+
+impl Settlement {
+    /// Infer settlement from a symbol prefix ("PI_" and "FI_" are inverse).
+    pub fn from_symbol(symbol: &str) -> Self {
+        let prefix = symbol.get(..3).unwrap_or_default().to_ascii_uppercase();
+        match prefix.as_str() {
+            "PI_" | "FI_" => Settlement::Inverse,
+            _ => Settlement::Linear,
+        }
+    }
+}
+
+/// Tiered margin schedule of an instrument.
+#[derive(Debug, Clone)]
+pub struct MarginSchedule {
+    levels: Vec<MarginLevel>,
+    contract_size: Decimal,
+    settlement: Settlement,
+}
+
+impl MarginSchedule {
+    /// Create a schedule from margin tiers, in any order.
+    pub fn new(
+        mut levels: Vec<MarginLevel>,
+        contract_size: Decimal,
+        settlement: Settlement,
+    ) -> Self {
+        levels.sort_by_key(threshold);
+        Self {
+            levels,
+            contract_size,
+            settlement,
+        }
+    }
+
+    /// Create a schedule from an instrument's margin levels.
+    ///
+    /// Returns `None` if the instrument has no margin levels.
+    pub fn from_instrument(instrument: &FuturesInstrument) -> Option<Self> {
+        let levels = instrument.margin_levels.clone().filter(|l| !l.is_empty())?;
+        Some(Self::new(
+            levels,
+            instrument.contract_size.unwrap_or(Decimal::ONE),
+            Settlement::from_symbol(&instrument.symbol),
+        ))
+    }
+
+    /// Settlement of the instrument.
+    pub fn settlement(&self) -> Settlement {
+        self.settlement
+    }
+
+    /// Margin tiers, ordered by threshold.
+    pub fn levels(&self) -> &[MarginLevel] {
+        &self.levels
+    }
+
+    /// Position value in the margin currency.
+    pub fn notional(&self, size: Decimal, price: Decimal) -> Decimal {
+        let contracts = size.abs() * self.contract_size;
+        match self.settlement {
+            Settlement::Linear => contracts * price,
+            Settlement::Inverse => contracts.checked_div(price).unwrap_or_default(),
+        }
+    }
+
+    /// Initial margin for a position of `size` contracts valued at `price`.
+    pub fn initial_margin(&self, size: Decimal, price: Decimal) -> Decimal {
+        self.margin(size, price, |level| level.initial_margin)
+    }
+
+    /// Maintenance margin for a position of `size` contracts valued at `price`.
+    pub fn maintenance_margin(&self, size: Decimal, price: Decimal) -> Decimal {
+        self.margin(size, price, |level| level.maintenance_margin)
+    }
+
+    /// Blended maintenance margin rate across the tiers the position spans.
+    pub fn maintenance_rate(&self, size: Decimal, price: Decimal) -> Decimal {
+        let exposure = self.exposure(size, price);
+        if exposure.is_zero() {
+            return self
+                .levels
+                .first()
+                .map(|level| level.maintenance_margin)
+                .unwrap_or_default();
+        }
+        self.tiered(exposure, |level| level.maintenance_margin) / exposure
+    }
+
+    fn margin(
+        &self,
+        size: Decimal,
+        price: Decimal,
+        rate: impl Fn(&MarginLevel) -> Decimal,
+    ) -> Decimal {
+        let exposure = self.exposure(size, price);
+        if exposure.is_zero() {
+            return Decimal::ZERO;
+        }
+        self.tiered(exposure, rate) * self.notional(size, price) / exposure
+    }
+
+    /// Sum of each tier's rate over the part of `exposure` that falls in it.
+    fn tiered(&self, exposure: Decimal, rate: impl Fn(&MarginLevel) -> Decimal) -> Decimal {
+        let mut charged = Decimal::ZERO;
+        for (i, level) in self.levels.iter().enumerate() {
+            let lower = if i == 0 {
+                Decimal::ZERO
+            } else {
+                threshold(level)
+            };
+            let upper = self.levels.get(i + 1).map(threshold).unwrap_or(exposure);
+            let portion = upper.min(exposure) - lower;
+            if portion > Decimal::ZERO {
+                charged += portion * rate(level);
+            }
+        }
+        charged
+    }
+
+    /// Position size in the units the tier thresholds are expressed in.
+    fn exposure(&self, size: Decimal, price: Decimal) -> Decimal {
+        let by_value = self
+            .levels
+            .iter()
+            .any(|level| level.num_non_contract_units.is_some());
+        if !by_value {
+            return size.abs();
+        }
+        let contracts = size.abs() * self.contract_size;
+        match self.settlement {
+            Settlement::Linear => contracts * price,
+            Settlement::Inverse => contracts,
+        }
+    }
+
+    /// Unrealized profit and loss of a position at `price`.
+    fn pnl(&self, position: &HypotheticalPosition, price: Decimal) -> Decimal {
+        let contracts = position.signed_size() * self.contract_size;
+        match self.settlement {
+            Settlement::Linear => contracts * (price - position.entry_price),
+            Settlement::Inverse if price.is_zero() || position.entry_price.is_zero() => {
+                Decimal::ZERO
+            }
+            Settlement::Inverse => {
+                contracts * (Decimal::ONE / position.entry_price - Decimal::ONE / price)
+            }
+        }
+    }
+
+    /// Average entry price after adding `added` contracts at `price`.
+    ///
+    /// Keeps the current entry price when a zero price leaves the average
+    /// undefined.
+    fn average_entry(
+        &self,
+        position: &HypotheticalPosition,
+        added: Decimal,
+        price: Decimal,
+    ) -> Decimal {
+        let total = position.size + added;
+        let average = match self.settlement {
+            Settlement::Linear => {
+                (position.size * position.entry_price + added * price).checked_div(total)
+            }
+            // Inverse contracts have a fixed quote value, so entries average
+            // harmonically.
+            Settlement::Inverse => position
+                .size
+                .checked_div(position.entry_price)
+                .zip(added.checked_div(price))
+                .and_then(|(held, bought)| total.checked_div(held + bought)),
+        };
+        average.unwrap_or(position.entry_price)
+    }
+}
+
+/// Tier threshold of a margin level, in contracts or value.
+fn threshold(level: &MarginLevel) -> Decimal {
+    level.num_non_contract_units.unwrap_or(level.contracts)
+}
+
+/// A position to evaluate, held or proposed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HypotheticalPosition {
+    /// Position side
+    pub side: BuySell,
+    /// Position size in contracts (positive)
+    pub size: Decimal,
+    /// Average entry price
+    pub entry_price: Decimal,
+}
+
+impl HypotheticalPosition {
+    /// Create a position.
+    pub fn new(side: BuySell, size: Decimal, entry_price: Decimal) -> Self {
+        Self {
+            side,
+            size: size.abs(),
+            entry_price,
+        }
+    }
+
+    /// Take the side, size and entry price of an open position.
+    pub fn from_position(position: &FuturesPosition) -> Self {
+        Self::new(position.side, position.size, position.entry_price)
+    }
+
+    /// Size with sign: positive for long, negative for short.
+    pub fn signed_size(&self) -> Decimal {
+        match self.side {
+            BuySell::Buy => self.size,
+            BuySell::Sell => -self.size,
+        }
+    }
+}
+
+/// Margin state of a position at a mark price.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarginEstimate {
+    /// Evaluated position (`None` when flat)
+    pub position: Option<HypotheticalPosition>,
+    /// Collateral excluding unrealized profit and loss
+    pub collateral: Decimal,
+    /// Unrealized profit and loss at the mark price
+    pub unrealized_pnl: Decimal,
+    /// Initial margin requirement
+    pub initial_margin: Decimal,
+    /// Maintenance margin requirement
+    pub maintenance_margin: Decimal,
+    /// Margin left for new positions (equity minus initial margin)
+    pub available_margin: Decimal,
+    /// Estimated liquidation price (`None` when flat or out of reach)
+    pub liquidation_price: Option<Decimal>,
+}
+
+impl MarginEstimate {
+    /// Collateral plus unrealized profit and loss.
+    pub fn equity(&self) -> Decimal {
+        self.collateral + self.unrealized_pnl
+    }
+}
+
+/// Margin state before and after a proposed order fills.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderImpact {
+    /// State with the current position
+    pub before: MarginEstimate,
+    /// State once the order has filled
+    pub after: MarginEstimate,
+}
+
+impl OrderImpact {
+    /// Change in initial margin caused by the order.
+    pub fn initial_margin_change(&self) -> Decimal {
+        self.after.initial_margin - self.before.initial_margin
+    }
+
+    /// Whether the account can carry the position after the order.
+    ///
+    /// Orders that do not increase the initial margin are always affordable.
+    pub fn is_affordable(&self) -> bool {
+        self.after.available_margin >= Decimal::ZERO
+            || self.after.initial_margin <= self.before.initial_margin
+    }
+}
+
+/// Computes margin requirements and liquidation prices for one instrument.
+#[derive(Debug, Clone)]
+pub struct MarginCalculator {
+    schedule: MarginSchedule,
+    collateral: Decimal,
+}
+
+impl MarginCalculator {
+    /// Create a calculator for an account holding `collateral` (in the margin
+    /// currency, excluding unrealized profit and loss).
+    pub fn new(schedule: MarginSchedule, collateral: Decimal) -> Self {
+        Self {
+            schedule,
+            collateral,
+        }
+    }
+
+    /// Create a calculator from a single-collateral margin account.
+    ///
+    /// Collateral is the portfolio value less unrealized profit and loss.
+    /// Returns `None` if the account has no auxiliary balances.
+    pub fn from_account(schedule: MarginSchedule, account: &FuturesAccount) -> Option<Self> {
+        let auxiliary = account.auxiliary.as_ref()?;
+        Some(Self::new(schedule, auxiliary.pv - auxiliary.pnl))
+    }
+
+    /// The margin schedule in use.
+    pub fn schedule(&self) -> &MarginSchedule {
+        &self.schedule
+    }
+
+    /// Collateral excluding unrealized profit and loss.
+    pub fn collateral(&self) -> Decimal {
+        self.collateral
+    }
+
+    /// Evaluate a position at `mark_price`.
+    pub fn estimate(
+        &self,
+        position: Option<&HypotheticalPosition>,
+        mark_price: Decimal,
+    ) -> MarginEstimate {
+        self.estimate_with(self.collateral, position.copied(), mark_price)
+    }
+
+    /// Evaluate the effect of a proposed order on the current position.
+    ///
+    /// The order is assumed to fill in full at its limit price (or stop price,
+    /// or `mark_price` for market orders). Reduce-only orders are capped at
+    /// the current position size.
+    pub fn with_order(
+        &self,
+        position: Option<&HypotheticalPosition>,
+        order: &SendOrderRequest,
+        mark_price: Decimal,
+    ) -> OrderImpact {
+        let price = order.limit_price.or(order.stop_price).unwrap_or(mark_price);
+        let mut size = order.size;
+        if order.reduce_only == Some(true) {
+            size = match position {
+                Some(current) if current.side != order.side => size.min(current.size),
+                _ => Decimal::ZERO,
+            };
+        }
+        self.with_fill(position, order.side, size, price, mark_price)
+    }
+
+    /// Evaluate the effect of filling `size` contracts on `side` at `price`.
+    pub fn with_fill(
+        &self,
+        position: Option<&HypotheticalPosition>,
+        side: BuySell,
+        size: Decimal,
+        price: Decimal,
+        mark_price: Decimal,
+    ) -> OrderImpact {
+        let before = self.estimate(position, mark_price);
+        let (after_position, realized) = self.fill(position, side, size.abs(), price);
+        let after = self.estimate_with(self.collateral + realized, after_position, mark_price);
+        OrderImpact { before, after }
+    }
+
+    /// Position after a fill, and the profit or loss it realizes.
+    fn fill(
+        &self,
+        position: Option<&HypotheticalPosition>,
+        side: BuySell,
+        size: Decimal,
+        price: Decimal,
+    ) -> (Option<HypotheticalPosition>, Decimal) {
+        let Some(current) = position.filter(|p| !p.size.is_zero()) else {
+            let opened = (!size.is_zero()).then(|| HypotheticalPosition::new(side, size, price));
+            return (opened, Decimal::ZERO);
+        };
+
+        if current.side == side {
+            let entry = self.schedule.average_entry(current, size, price);
+            let grown = HypotheticalPosition::new(side, current.size + size, entry);
+            return (Some(grown), Decimal::ZERO);
+        }
+
+        let closed = size.min(current.size);
+        let closed_part = HypotheticalPosition::new(current.side, closed, current.entry_price);
+        let realized = self.schedule.pnl(&closed_part, price);
+        let remaining = if size < current.size {
+            Some(HypotheticalPosition::new(
+                current.side,
+                current.size - size,
+                current.entry_price,
+            ))
+        } else if size > current.size {
+            Some(HypotheticalPosition::new(side, size - current.size, price))
+        } else {
+            None
+        };
+        (remaining, realized)
+    }
+
+    fn estimate_with(
+        &self,
+        collateral: Decimal,
+        position: Option<HypotheticalPosition>,
+        mark_price: Decimal,
+    ) -> MarginEstimate {
+        let Some(position) = position.filter(|p| !p.size.is_zero()) else {
+            return MarginEstimate {
+                position: None,
+                collateral,
+                unrealized_pnl: Decimal::ZERO,
+                initial_margin: Decimal::ZERO,
+                maintenance_margin: Decimal::ZERO,
+                available_margin: collateral,
+                liquidation_price: None,
+            };
+        };
+
+        let unrealized_pnl = self.schedule.pnl(&position, mark_price);
+        let initial_margin = self.schedule.initial_margin(position.size, mark_price);
+        let maintenance_margin = self.schedule.maintenance_margin(position.size, mark_price);
+        MarginEstimate {
+            position: Some(position),
+            collateral,
+            unrealized_pnl,
+            initial_margin,
+            maintenance_margin,
+            available_margin: collateral + unrealized_pnl - initial_margin,
+            liquidation_price: self.liquidation_price(collateral, &position, mark_price),
+        }
+    }
+
+    /// Price at which equity falls to the maintenance margin.
+    fn liquidation_price(
+        &self,
+        collateral: Decimal,
+        position: &HypotheticalPosition,
+        mark_price: Decimal,
+    ) -> Option<Decimal> {
+        let rate = self.schedule.maintenance_rate(position.size, mark_price);
+        let contracts = position.size * self.schedule.contract_size;
+        let entry = position.entry_price;
+        if entry.is_zero() {
+            return None;
+        }
+
+        let price =
+            match (self.schedule.settlement, position.side) {
+                // collateral + q(P - E) = qPm
+                (Settlement::Linear, BuySell::Buy) => (contracts * entry - collateral)
+                    .checked_div(contracts * (Decimal::ONE - rate))?,
+                // collateral + q(E - P) = qPm
+                (Settlement::Linear, BuySell::Sell) => (collateral + contracts * entry)
+                    .checked_div(contracts * (Decimal::ONE + rate))?,
+                // collateral + q/E - q/P = qm/P
+                (Settlement::Inverse, BuySell::Buy) => (contracts * (Decimal::ONE + rate))
+                    .checked_div(collateral + contracts / entry)?,
+                // collateral + q/P - q/E = qm/P
+                (Settlement::Inverse, BuySell::Sell) => {
+                    let denominator = contracts / entry - collateral;
+                    if denominator <= Decimal::ZERO {
+                        return None;
+                    }
+                    (contracts * (Decimal::ONE - rate)).checked_div(denominator)?
+                }
+            };
+        (price > Decimal::ZERO).then_some(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn level(contracts: &str, initial: &str, maintenance: &str) -> MarginLevel {
+        MarginLevel {
+            contracts: d(contracts),
+            num_non_contract_units: None,
+            initial_margin: d(initial),
+            maintenance_margin: d(maintenance),
+        }
+    }
+
+    fn linear_schedule() -> MarginSchedule {
+        MarginSchedule::new(
+            vec![level("10", "0.04", "0.02"), level("0", "0.02", "0.01")],
+            Decimal::ONE,
+            Settlement::Linear,
+        )
+    }
+
+    #[test]
+    fn test_settlement_from_symbol() {
+        assert_eq!(Settlement::from_symbol("PI_XBTUSD"), Settlement::Inverse);
+        assert_eq!(
+            Settlement::from_symbol("fi_xbtusd_240628"),
+            Settlement::Inverse
+        );
+        assert_eq!(Settlement::from_symbol("PF_XBTUSD"), Settlement::Linear);
+    }
+
+    #[test]
+    fn test_tiers_apply_incrementally() {
+        let schedule = linear_schedule();
+
+        // 10 contracts at 2%, 5 at 4%, on a notional of 15 * 100.
+        assert_eq!(schedule.initial_margin(d("15"), d("100")), d("40"));
+        assert_eq!(schedule.maintenance_margin(d("15"), d("100")), d("20"));
+        assert_eq!(schedule.initial_margin(d("5"), d("100")), d("10"));
+    }
+
+    #[test]
+    fn test_value_tiers_use_notional() {
+        let mut upper = level("0", "0.1", "0.05");
+        upper.num_non_contract_units = Some(d("1000"));
+        let mut lower = level("0", "0.02", "0.01");
+        lower.num_non_contract_units = Some(d("0"));
+        let schedule = MarginSchedule::new(vec![lower, upper], Decimal::ONE, Settlement::Linear);
+
+        // 1000 of notional at 2% and 500 at 10%.
+        assert_eq!(schedule.initial_margin(d("15"), d("100")), d("70"));
+    }
+
+    #[test]
+    fn test_schedule_from_instrument() {
+        let instrument: FuturesInstrument = serde_json::from_value(serde_json::json!({
+            "symbol": "PF_XBTUSD",
+            "contractSize": 1,
+            "marginLevels": [
+                { "numNonContractUnits": 500000, "initialMargin": 0.04, "maintenanceMargin": 0.02 },
+                { "numNonContractUnits": 0, "initialMargin": 0.02, "maintenanceMargin": 0.01 }
+            ]
+        }))
+        .unwrap();
+
+        let schedule = MarginSchedule::from_instrument(&instrument).unwrap();
+        assert_eq!(schedule.settlement(), Settlement::Linear);
+        assert_eq!(schedule.levels()[0].num_non_contract_units, Some(d("0")));
+        assert_eq!(schedule.initial_margin(d("10"), d("60000")), d("14000"));
+    }
+
+    #[test]
+    fn test_linear_liquidation_price() {
+        let calculator = MarginCalculator::new(linear_schedule(), d("100"));
+        let long = HypotheticalPosition::new(BuySell::Buy, d("5"), d("100"));
+        let estimate = calculator.estimate(Some(&long), d("100"));
+
+        assert_eq!(estimate.initial_margin, d("10"));
+        assert_eq!(estimate.available_margin, d("90"));
+        // 100 + 5(P - 100) = 0.05P  =>  P = 400 / 4.95
+        let liquidation = estimate.liquidation_price.unwrap();
+        assert_eq!(liquidation.round_dp(4), d("80.8081"));
+
+        let short = HypotheticalPosition::new(BuySell::Sell, d("5"), d("100"));
+        let liquidation = calculator
+            .estimate(Some(&short), d("100"))
+            .liquidation_price
+            .unwrap();
+        // 100 + 5(100 - P) = 0.05P  =>  P = 600 / 5.05
+        assert_eq!(liquidation.round_dp(4), d("118.8119"));
+    }
+
+    #[test]
+    fn test_inverse_liquidation_price() {
+        let schedule = MarginSchedule::new(
+            vec![level("0", "0.02", "0.01")],
+            Decimal::ONE,
+            Settlement::Inverse,
+        );
+        let calculator = MarginCalculator::new(schedule, d("0.01"));
+        let long = HypotheticalPosition::new(BuySell::Buy, d("1000"), d("50000"));
+        let liquidation = calculator
+            .estimate(Some(&long), d("50000"))
+            .liquidation_price
+            .unwrap();
+        // 0.01 + 1000/50000 - 1000/P = 10/P  =>  P = 1010 / 0.03
+        assert_eq!(liquidation.round_dp(2), d("33666.67"));
+    }
+
+    #[test]
+    fn test_order_impact() {
+        let calculator = MarginCalculator::new(linear_schedule(), d("100"));
+        let current = HypotheticalPosition::new(BuySell::Buy, d("5"), d("100"));
+
+        let order = SendOrderRequest::limit("PF_XBTUSD", BuySell::Buy, d("10"), d("100"));
+        let impact = calculator.with_order(Some(&current), &order, d("100"));
+        let after = impact.after.position.unwrap();
+        assert_eq!(after.size, d("15"));
+        assert_eq!(after.entry_price, d("100"));
+        assert_eq!(impact.initial_margin_change(), d("30"));
+        assert!(impact.is_affordable());
+        assert!(impact.after.liquidation_price > impact.before.liquidation_price);
+
+        // A reduce-only sell larger than the position only closes it.
+        let close =
+            SendOrderRequest::limit("PF_XBTUSD", BuySell::Sell, d("8"), d("120")).reduce_only(true);
+        let impact = calculator.with_order(Some(&current), &close, d("100"));
+        assert!(impact.after.position.is_none());
+        assert_eq!(impact.after.collateral, d("200"));
+        assert_eq!(impact.after.liquidation_price, None);
+
+        let oversized = SendOrderRequest::market("PF_XBTUSD", BuySell::Buy, d("100"));
+        assert!(
+            !calculator
+                .with_order(Some(&current), &oversized, d("100"))
+                .is_affordable()
+        );
+    }
+
+    #[test]
+    fn test_zero_prices_do_not_panic() {
+        let schedule = MarginSchedule::new(
+            vec![level("0", "0.02", "0.01")],
+            Decimal::ONE,
+            Settlement::Inverse,
+        );
+        let calculator = MarginCalculator::new(schedule, d("0.01"));
+        let current = HypotheticalPosition::new(BuySell::Buy, d("1000"), d("50000"));
+        let order = SendOrderRequest::market("PI_XBTUSD", BuySell::Buy, d("10"));
+        let impact = calculator.with_order(Some(&current), &order, Decimal::ZERO);
+        assert_eq!(impact.after.position.unwrap().entry_price, d("50000"));
+
+        let unpriced = HypotheticalPosition::new(BuySell::Buy, d("1000"), Decimal::ZERO);
+        let impact = calculator.with_order(Some(&unpriced), &order, d("50000"));
+        assert_eq!(impact.after.position.unwrap().size, d("1010"));
+    }
+}
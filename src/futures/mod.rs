@@ -45,7 +45,9 @@
 //! - WebSocket: <https://docs.kraken.com/api/docs/futures-api/websocket>
 
 mod auth;
+pub mod margin;
 pub mod rest;
+pub mod transfer;
 pub mod types;
 pub mod ws;
 
@@ -0,0 +1,533 @@
+//! Futures account history API: executions, orders, triggers and account log.
+//!
+//! These endpoints reach back further than [`get_fills`](FuturesRestClient::get_fills)
+//! and are paginated. Each has a page method and a `*_stream` method that
+//! follows the pagination until the history is exhausted:
+//!
+//! ```rust,ignore
+//! use futures_util::TryStreamExt;
+//! use kraken_api_client::futures::rest::HistoryRequest;
+//!
+//! let request = HistoryRequest {
+//!     since: Some(1_700_000_000_000),
+//!     ..Default::default()
+//! };
+//! let executions: Vec<_> = client.executions_stream(request).try_collect().await?;
+//! ```
+
+use std::future::Future;
+use std::marker::PhantomData;
+
+use futures_util::{Stream, TryStreamExt, stream};
+use rust_decimal::Decimal;
+use serde::de::{DeserializeOwned, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::error::KrakenError;
+use crate::futures::rest::{FuturesClient, FuturesRestClient};
+use crate::futures::rest::endpoints::history;
+use crate::futures::ws::AccountLogEntry;
+
+/// Sort order for history queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistorySort {
+    /// Oldest first
+    Asc,
+    /// Newest first
+    Desc,
+}
+
+/// Query for the executions, orders and triggers history endpoints.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HistoryRequest {
+    /// Only events at or after this time (Unix milliseconds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<i64>,
+    /// Only events before this time (Unix milliseconds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<i64>,
+    /// Sort order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<HistorySort>,
+    /// Symbol filter (e.g., "PF_XBTUSD")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tradeable: Option<String>,
+    /// Maximum number of events per page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    /// Continuation token from a previous page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+}
+
+/// One page of history events.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(bound(deserialize = "E: Deserialize<'de>"))]
+pub struct HistoryPage<E> {
+    /// Account UID
+    #[serde(default)]
+    pub account_uid: Option<String>,
+    /// Token for the next page (absent on the last page)
+    #[serde(default)]
+    pub continuation_token: Option<String>,
+    /// Events on this page
+    #[serde(default)]
+    pub elements: Vec<HistoryElement<E>>,
+    /// Number of events on this page
+    #[serde(default)]
+    pub len: Option<u64>,
+}
+
+/// A single history event.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HistoryElement<E> {
+    /// Event UID
+    pub uid: String,
+    /// Event time (Unix milliseconds)
+    pub timestamp: i64,
+    /// Event details
+    pub event: E,
+}
+
+/// An order as recorded in the history API.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryOrder {
+    /// Order ID
+    pub uid: String,
+    /// Account UID
+    #[serde(default, alias = "accountId")]
+    pub account_uid: Option<String>,
+    /// Symbol
+    pub tradeable: String,
+    /// Direction ("Buy" or "Sell")
+    pub direction: String,
+    /// Order quantity
+    pub quantity: Decimal,
+    /// Filled quantity
+    #[serde(default)]
+    pub filled: Option<Decimal>,
+    /// Creation time (Unix milliseconds)
+    pub timestamp: i64,
+    /// Limit price
+    #[serde(default)]
+    pub limit_price: Option<Decimal>,
+    /// Order type (e.g., "Limit", "IoC", "Post", "Market")
+    pub order_type: String,
+    /// Client order ID
+    #[serde(default)]
+    pub client_id: Option<String>,
+    /// Reduce-only flag
+    #[serde(default)]
+    pub reduce_only: bool,
+    /// Last update time (Unix milliseconds)
+    #[serde(default)]
+    pub last_update_timestamp: Option<i64>,
+    /// Trigger price (trigger orders only)
+    #[serde(default)]
+    pub trigger_price: Option<Decimal>,
+    /// Trigger side (trigger orders only)
+    #[serde(default)]
+    pub trigger_side: Option<String>,
+    /// Trigger signal (trigger orders only)
+    #[serde(default)]
+    pub trigger_signal: Option<String>,
+}
+
+/// A fill between a maker and a taker order.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryExecution {
+    /// Execution ID
+    pub uid: String,
+    /// Maker side of the fill
+    pub maker_order: HistoryOrder,
+    /// Taker side of the fill
+    pub taker_order: HistoryOrder,
+    /// Execution time (Unix milliseconds)
+    pub timestamp: i64,
+    /// Executed quantity
+    pub quantity: Decimal,
+    /// Execution price
+    pub price: Decimal,
+    /// Mark price at execution
+    #[serde(default)]
+    pub mark_price: Option<Decimal>,
+    /// Whether the limit order was fully filled
+    #[serde(default)]
+    pub limit_filled: Option<bool>,
+    /// USD value of the execution
+    #[serde(default)]
+    pub usd_value: Option<Decimal>,
+}
+
+/// An event from the executions history.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "Tagged<ExecutionBody>")]
+pub struct ExecutionEvent {
+    /// Event kind (normally "Execution")
+    pub kind: String,
+    /// The execution, if the event carries one
+    pub execution: Option<HistoryExecution>,
+}
+
+/// An event from the order or trigger history.
+///
+/// `kind` is the event name reported by Kraken, e.g. "OrderPlaced",
+/// "OrderUpdated", "OrderCancelled", "OrderRejected", "OrderEditRejected",
+/// or "OrderTriggerPlaced", "OrderTriggerCancelled", "OrderTriggerActivated"
+/// for triggers.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "Tagged<OrderBody>")]
+pub struct OrderEvent {
+    /// Event kind
+    pub kind: String,
+    /// The order after the event
+    pub order: Option<HistoryOrder>,
+    /// The order before an update or edit
+    pub old_order: Option<HistoryOrder>,
+    /// Reason given for the event
+    pub reason: Option<String>,
+}
+
+/// Query for the account log endpoints.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AccountLogRequest {
+    /// Only entries at or after this time (Unix milliseconds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<i64>,
+    /// Only entries before this time (Unix milliseconds)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<i64>,
+    /// Lowest entry ID to include
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<u64>,
+    /// Highest entry ID to include
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<u64>,
+    /// Entry type filter (e.g., "futures trade", "funding rate change")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub info: Option<String>,
+    /// Maximum number of entries per page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+}
+
+/// Response for the account log endpoint.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountLogResponse {
+    /// Account UID
+    #[serde(default)]
+    pub account_uid: Option<String>,
+    /// Log entries, newest first
+    #[serde(default)]
+    pub logs: Vec<AccountLogEntry>,
+}
+
+impl FuturesRestClient {
+    /// Get one page of execution history.
+    pub async fn get_executions(
+        &self,
+        request: &HistoryRequest,
+    ) -> Result<HistoryPage<ExecutionEvent>, KrakenError> {
+        self.history_get(history::EXECUTIONS, request).await
+    }
+
+    /// Get one page of order event history.
+    pub async fn get_order_history(
+        &self,
+        request: &HistoryRequest,
+    ) -> Result<HistoryPage<OrderEvent>, KrakenError> {
+        self.history_get(history::ORDERS, request).await
+    }
+
+    /// Get one page of trigger order event history.
+    pub async fn get_trigger_history(
+        &self,
+        request: &HistoryRequest,
+    ) -> Result<HistoryPage<OrderEvent>, KrakenError> {
+        self.history_get(history::TRIGGERS, request).await
+    }
+
+    /// Get one page of the account log.
+    pub async fn get_account_log(
+        &self,
+        request: &AccountLogRequest,
+    ) -> Result<AccountLogResponse, KrakenError> {
+        self.history_get(history::ACCOUNT_LOG, request).await
+    }
+
+    /// Export the account log as CSV.
+    ///
+    /// The export is a single document covering the requested range; it is
+    /// not paginated.
+    pub async fn get_account_log_csv(
+        &self,
+        request: &AccountLogRequest,
+    ) -> Result<String, KrakenError> {
+        let query = serde_urlencoded::to_string(request)
+            .map_err(|e| KrakenError::InvalidResponse(e.to_string()))?;
+        let response = self
+            .signed_get(self.host_root(), history::ACCOUNT_LOG_CSV, &query)
+            .await?;
+
+        if !response.status().is_success() {
+            return self.parse_futures_response(response).await;
+        }
+        Ok(response.text().await?)
+    }
+
+    /// Stream all executions matching `request`, following continuation tokens.
+    ///
+    /// See [`FuturesClient::executions_stream`].
+    pub fn executions_stream(
+        &self,
+        request: HistoryRequest,
+    ) -> impl Stream<Item = Result<HistoryElement<ExecutionEvent>, KrakenError>> + Send + '_ {
+        FuturesClient::executions_stream(self, request)
+    }
+
+    /// Stream all order events matching `request`, following continuation tokens.
+    ///
+    /// See [`FuturesClient::order_history_stream`].
+    pub fn order_history_stream(
+        &self,
+        request: HistoryRequest,
+    ) -> impl Stream<Item = Result<HistoryElement<OrderEvent>, KrakenError>> + Send + '_ {
+        FuturesClient::order_history_stream(self, request)
+    }
+
+    /// Stream all trigger events matching `request`, following continuation tokens.
+    ///
+    /// See [`FuturesClient::trigger_history_stream`].
+    pub fn trigger_history_stream(
+        &self,
+        request: HistoryRequest,
+    ) -> impl Stream<Item = Result<HistoryElement<OrderEvent>, KrakenError>> + Send + '_ {
+        FuturesClient::trigger_history_stream(self, request)
+    }
+
+    /// Stream all account log entries matching `request`, newest first.
+    ///
+    /// See [`FuturesClient::account_log_stream`].
+    pub fn account_log_stream(
+        &self,
+        request: AccountLogRequest,
+    ) -> impl Stream<Item = Result<AccountLogEntry, KrakenError>> + Send + '_ {
+        FuturesClient::account_log_stream(self, request)
+    }
+
+    async fn history_get<T, Q>(&self, endpoint: &str, request: &Q) -> Result<T, KrakenError>
+    where
+        T: DeserializeOwned,
+        Q: Serialize,
+    {
+        let query = serde_urlencoded::to_string(request)
+            .map_err(|e| KrakenError::InvalidResponse(e.to_string()))?;
+        let response = self.signed_get(self.host_root(), endpoint, &query).await?;
+        self.parse_futures_response(response).await
+    }
+}
+
+/// Follow the continuation tokens of a history endpoint, fetching each page
+/// with `fetch`.
+pub(crate) fn paginate_history<'a, E, F, Fut>(
+    request: HistoryRequest,
+    fetch: F,
+) -> impl Stream<Item = Result<HistoryElement<E>, KrakenError>> + Send + 'a
+where
+    E: Send + 'a,
+    F: Fn(HistoryRequest) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<HistoryPage<E>, KrakenError>> + Send + 'a,
+{
+    stream::try_unfold(Some(request), move |state| {
+        let page = state.clone().map(&fetch);
+        async move {
+            let (Some(mut request), Some(page)) = (state, page) else {
+                return Ok::<_, KrakenError>(None);
+            };
+            let page = page.await?;
+
+            let next = match page.continuation_token {
+                Some(token)
+                    if !token.is_empty()
+                        && !page.elements.is_empty()
+                        && request.continuation_token.as_ref() != Some(&token) =>
+                {
+                    request.continuation_token = Some(token);
+                    Some(request)
+                }
+                _ => None,
+            };
+
+            Ok(Some((
+                stream::iter(page.elements.into_iter().map(Ok)),
+                next,
+            )))
+        }
+    })
+    .try_flatten()
+}
+
+/// Page through the account log newest first, fetching each page with
+/// `fetch`.
+///
+/// The account log has no continuation token; pages are chained by lowering
+/// the `to` entry ID below the oldest entry seen so far.
+pub(crate) fn paginate_account_log<'a, F, Fut>(
+    request: AccountLogRequest,
+    fetch: F,
+) -> impl Stream<Item = Result<AccountLogEntry, KrakenError>> + Send + 'a
+where
+    F: Fn(AccountLogRequest) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<AccountLogResponse, KrakenError>> + Send + 'a,
+{
+    stream::try_unfold(Some(request), move |state| {
+        let response = state.clone().map(&fetch);
+        async move {
+            let (Some(mut request), Some(response)) = (state, response) else {
+                return Ok::<_, KrakenError>(None);
+            };
+            let response = response.await?;
+
+            let bound = request.to;
+            let logs: Vec<_> = response
+                .logs
+                .into_iter()
+                .filter(|entry| bound.is_none_or(|to| entry.id <= to))
+                .collect();
+            let next = match logs.iter().map(|entry| entry.id).min() {
+                Some(oldest) if oldest > 0 => {
+                    request.to = Some(oldest - 1);
+                    Some(request)
+                }
+                _ => None,
+            };
+
+            Ok(Some((stream::iter(logs.into_iter().map(Ok)), next)))
+        }
+    })
+    .try_flatten()
+}
+
+// History events are encoded as a single-key object: `{"<Kind>": {...}}`.
+
+struct Tagged<B> {
+    kind: String,
+    body: Option<B>,
+}
+
+impl<'de, B: Deserialize<'de>> Deserialize<'de> for Tagged<B> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TaggedVisitor<B>(PhantomData<B>);
+
+        impl<'de, B: Deserialize<'de>> Visitor<'de> for TaggedVisitor<B> {
+            type Value = Tagged<B>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a single-key event object")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let kind: String = map
+                    .next_key()?
+                    .ok_or_else(|| serde::de::Error::custom("empty event object"))?;
+                let body = map.next_value()?;
+                Ok(Tagged { kind, body })
+            }
+        }
+
+        deserializer.deserialize_map(TaggedVisitor(PhantomData))
+    }
+}
+
+#[derive(Deserialize)]
+struct ExecutionBody {
+    #[serde(default)]
+    execution: Option<HistoryExecution>,
+}
+
+impl From<Tagged<ExecutionBody>> for ExecutionEvent {
+    fn from(tagged: Tagged<ExecutionBody>) -> Self {
+        Self {
+            kind: tagged.kind,
+            execution: tagged.body.and_then(|b| b.execution),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OrderBody {
+    #[serde(default, alias = "orderTrigger")]
+    order: Option<HistoryOrder>,
+    #[serde(default, alias = "newOrderTrigger")]
+    new_order: Option<HistoryOrder>,
+    #[serde(default, alias = "oldOrderTrigger")]
+    old_order: Option<HistoryOrder>,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+impl From<Tagged<OrderBody>> for OrderEvent {
+    fn from(tagged: Tagged<OrderBody>) -> Self {
+        let body = tagged.body.unwrap_or(OrderBody {
+            order: None,
+            new_order: None,
+            old_order: None,
+            reason: None,
+        });
+        Self {
+            kind: tagged.kind,
+            order: body.new_order.or(body.order),
+            old_order: body.old_order,
+            reason: body.reason,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_event_kinds() {
+        let json = r#"{
+            "uid": "e1",
+            "timestamp": 1700000000000,
+            "event": {
+                "OrderUpdated": {
+                    "oldOrder": {
+                        "uid": "o1", "tradeable": "PF_XBTUSD", "direction": "Buy",
+                        "quantity": "2", "timestamp": 1700000000000,
+                        "limitPrice": "37000", "orderType": "Limit"
+                    },
+                    "newOrder": {
+                        "uid": "o1", "tradeable": "PF_XBTUSD", "direction": "Buy",
+                        "quantity": "2", "filled": "1", "timestamp": 1700000000000,
+                        "limitPrice": "37000", "orderType": "Limit"
+                    },
+                    "reason": "partial_fill"
+                }
+            }
+        }"#;
+        let element: HistoryElement<OrderEvent> = serde_json::from_str(json).unwrap();
+        assert_eq!(element.event.kind, "OrderUpdated");
+        assert_eq!(element.event.order.unwrap().filled, Some(Decimal::ONE));
+        assert!(element.event.old_order.unwrap().filled.is_none());
+
+        let json = r#"{"uid": "e2", "timestamp": 1, "event": {"OrderNotFound": {"orderId": "x"}}}"#;
+        let element: HistoryElement<OrderEvent> = serde_json::from_str(json).unwrap();
+        assert_eq!(element.event.kind, "OrderNotFound");
+        assert!(element.event.order.is_none());
+    }
+}
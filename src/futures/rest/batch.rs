@@ -0,0 +1,326 @@
+//! Typed batch order builder.
+//!
+//! [`BatchBuilder`] collects place, edit and cancel intents, tags each place
+//! element with a unique `order_tag`, and maps the batch status back to one
+//! [`BatchItemResult`] per intent, in input order.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kraken_api_client::futures::rest::{BatchBuilder, FuturesClient, SendOrderRequest};
+//!
+//! // Rebuild a two-level ladder in one request.
+//! let batch = BatchBuilder::new()
+//!     .cancel("old-bid")
+//!     .cancel("old-ask")
+//!     .place(SendOrderRequest::limit("PF_XBTUSD", BuySell::Buy, size, bid))
+//!     .place(SendOrderRequest::limit("PF_XBTUSD", BuySell::Sell, size, ask));
+//!
+//! for result in client.execute_batch(&batch).await? {
+//!     match result {
+//!         Ok(success) => println!("{:?} ok: {:?}", success.action, success.order_id),
+//!         Err(failure) => println!("{:?} failed: {}", failure.action, failure.status),
+//!     }
+//! }
+//! ```
+
+use crate::futures::rest::{
+    BatchElement, BatchElementStatus, BatchOrderRequest, CancelBatchElement, EditBatchElement,
+    EditOrderRequest, PlaceBatchElement, SendOrderRequest,
+};
+
+/// Status reported for elements with no matching entry in the batch response.
+const UNKNOWN_STATUS: &str = "unknown";
+
+/// Kind of batch instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BatchAction {
+    /// Place a new order
+    Place,
+    /// Edit an existing order
+    Edit,
+    /// Cancel an existing order
+    Cancel,
+}
+
+impl BatchAction {
+    /// Status the API reports when this action succeeds.
+    fn success_status(&self) -> &'static str {
+        match self {
+            BatchAction::Place => "placed",
+            BatchAction::Edit => "edited",
+            BatchAction::Cancel => "cancelled",
+        }
+    }
+}
+
+/// A batch instruction that was accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchSuccess {
+    /// Kind of instruction
+    pub action: BatchAction,
+    /// Order the instruction applied to (the new order for places)
+    pub order_id: Option<String>,
+    /// Status reported by the API
+    pub status: String,
+}
+
+/// A batch instruction that was rejected or not reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchFailure {
+    /// Kind of instruction
+    pub action: BatchAction,
+    /// Order the instruction referred to, if known
+    pub order_id: Option<String>,
+    /// Status reported by the API (e.g., "insufficientAvailableFunds"), or
+    /// "unknown" if no entry in the response could be matched to the
+    /// instruction
+    pub status: String,
+    /// Error message, if any
+    pub error_message: Option<String>,
+}
+
+/// Outcome of one batch instruction.
+pub type BatchItemResult = Result<BatchSuccess, BatchFailure>;
+
+/// Builder for batch orders whose results map back to each intent.
+#[derive(Debug, Clone, Default)]
+pub struct BatchBuilder {
+    elements: Vec<BatchElement>,
+    next_tag: u32,
+}
+
+impl BatchBuilder {
+    /// Create an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Place a new order, tagged so its status can be matched.
+    pub fn place(mut self, order: SendOrderRequest) -> Self {
+        let tag = self.next_tag.to_string();
+        self.next_tag += 1;
+        let element = PlaceBatchElement::new(order, Some(tag));
+        self.elements.push(BatchElement::Place(element));
+        self
+    }
+
+    /// Edit an existing order.
+    pub fn edit(mut self, request: EditOrderRequest) -> Self {
+        self.elements
+            .push(BatchElement::Edit(EditBatchElement::from(request)));
+        self
+    }
+
+    /// Cancel an order by order ID.
+    pub fn cancel(mut self, order_id: impl Into<String>) -> Self {
+        self.elements.push(BatchElement::Cancel(CancelBatchElement {
+            order_id: Some(order_id.into()),
+            cli_ord_id: None,
+        }));
+        self
+    }
+
+    /// Cancel an order by client order ID.
+    pub fn cancel_by_cli_ord_id(mut self, cli_ord_id: impl Into<String>) -> Self {
+        self.elements.push(BatchElement::Cancel(CancelBatchElement {
+            order_id: None,
+            cli_ord_id: Some(cli_ord_id.into()),
+        }));
+        self
+    }
+
+    /// Number of instructions in the batch.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Whether the batch is empty.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Build the wire request.
+    pub fn request(&self) -> BatchOrderRequest {
+        BatchOrderRequest {
+            batch_order: self.elements.clone(),
+        }
+    }
+
+    /// Map a batch response to one result per instruction, in input order.
+    ///
+    /// Place statuses are matched by `order_tag`; edit and cancel statuses by
+    /// order ID or client order ID. Instructions without a matching status
+    /// are reported as failed with status "unknown": the outcome of such an
+    /// instruction should be checked with an order status query rather than
+    /// guessed from position.
+    pub fn results(&self, statuses: Vec<BatchElementStatus>) -> Vec<BatchItemResult> {
+        let mut slots: Vec<Option<BatchElementStatus>> = vec![None; self.elements.len()];
+
+        for status in statuses {
+            let index = match &status.order_tag {
+                Some(tag) => self
+                    .elements
+                    .iter()
+                    .position(|element| has_tag(element, tag)),
+                None => self
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .position(|(i, element)| slots[i].is_none() && refers_to(element, &status)),
+            };
+            match index {
+                Some(index) if slots[index].is_none() => slots[index] = Some(status),
+                _ => tracing::debug!("Unmatched batch status: {:?}", status),
+            }
+        }
+
+        slots
+            .into_iter()
+            .zip(&self.elements)
+            .map(|(status, element)| outcome(element, status))
+            .collect()
+    }
+}
+
+fn action(element: &BatchElement) -> BatchAction {
+    match element {
+        BatchElement::Place(_) => BatchAction::Place,
+        BatchElement::Edit(_) => BatchAction::Edit,
+        BatchElement::Cancel(_) => BatchAction::Cancel,
+    }
+}
+
+/// Order ID and client order ID an edit or cancel element refers to.
+fn target(element: &BatchElement) -> (Option<&String>, Option<&String>) {
+    match element {
+        BatchElement::Place(place) => (None, place.cli_ord_id.as_ref()),
+        BatchElement::Edit(edit) => (edit.order_id.as_ref(), edit.cli_ord_id.as_ref()),
+        BatchElement::Cancel(cancel) => (cancel.order_id.as_ref(), cancel.cli_ord_id.as_ref()),
+    }
+}
+
+fn has_tag(element: &BatchElement, tag: &str) -> bool {
+    matches!(element, BatchElement::Place(place) if place.order_tag.as_deref() == Some(tag))
+}
+
+fn refers_to(element: &BatchElement, status: &BatchElementStatus) -> bool {
+    if matches!(element, BatchElement::Place(_)) {
+        return false;
+    }
+    let (order_id, cli_ord_id) = target(element);
+    (order_id.is_some() && order_id == status.order_id.as_ref())
+        || (cli_ord_id.is_some() && cli_ord_id == status.cli_ord_id.as_ref())
+}
+
+fn outcome(element: &BatchElement, status: Option<BatchElementStatus>) -> BatchItemResult {
+    let action = action(element);
+    let fallback_id = target(element).0.cloned();
+    match status {
+        Some(status) if status.status == action.success_status() => Ok(BatchSuccess {
+            action,
+            order_id: status.order_id.or(fallback_id),
+            status: status.status,
+        }),
+        Some(status) => Err(BatchFailure {
+            action,
+            order_id: status.order_id.or(fallback_id),
+            status: status.status,
+            error_message: status.error_message,
+        }),
+        None => Err(BatchFailure {
+            action,
+            order_id: fallback_id,
+            status: UNKNOWN_STATUS.to_string(),
+            error_message: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::futures::TrailingStopDeviationUnit;
+    use crate::types::common::BuySell;
+
+    fn status(json: serde_json::Value) -> BatchElementStatus {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_results_follow_input_order() {
+        let batch = BatchBuilder::new()
+            .cancel("old-bid")
+            .place(SendOrderRequest::limit(
+                "PF_XBTUSD",
+                BuySell::Buy,
+                Decimal::ONE,
+                Decimal::from(49_000),
+            ))
+            .edit(EditOrderRequest::by_order_id("resting").size(Decimal::from(2)))
+            .place(SendOrderRequest::limit(
+                "PF_XBTUSD",
+                BuySell::Sell,
+                Decimal::ONE,
+                Decimal::from(51_000),
+            ));
+
+        let json = serde_json::to_value(batch.request()).unwrap();
+        assert_eq!(json["batchOrder"][1]["order"], "send");
+        assert_eq!(json["batchOrder"][1]["order_tag"], "0");
+        assert_eq!(json["batchOrder"][3]["order_tag"], "1");
+        assert_eq!(json["batchOrder"][2]["order"], "edit");
+
+        // Statuses arrive out of order.
+        let results = batch.results(vec![
+            status(serde_json::json!({ "order_tag": "1", "status": "insufficientAvailableFunds" })),
+            status(serde_json::json!({ "order_id": "resting", "status": "edited" })),
+            status(
+                serde_json::json!({ "order_tag": "0", "order_id": "new-bid", "status": "placed" }),
+            ),
+            status(serde_json::json!({ "order_id": "old-bid", "status": "cancelled" })),
+        ]);
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap().action, BatchAction::Cancel);
+        assert_eq!(
+            results[1].as_ref().unwrap().order_id.as_deref(),
+            Some("new-bid")
+        );
+        assert_eq!(results[2].as_ref().unwrap().action, BatchAction::Edit);
+        let failure = results[3].as_ref().unwrap_err();
+        assert_eq!(failure.action, BatchAction::Place);
+        assert_eq!(failure.status, "insufficientAvailableFunds");
+    }
+
+    #[test]
+    fn test_unreported_elements_fail() {
+        let batch = BatchBuilder::new().cancel("a").cancel_by_cli_ord_id("b");
+        let results = batch.results(vec![status(
+            serde_json::json!({ "order_id": "x", "status": "notFound" }),
+        )]);
+
+        // A status for another order is not attributed to either cancel.
+        assert_eq!(results[0].as_ref().unwrap_err().status, UNKNOWN_STATUS);
+        assert_eq!(results[1].as_ref().unwrap_err().status, UNKNOWN_STATUS);
+    }
+
+    #[test]
+    fn test_place_keeps_trailing_stop_fields() {
+        let batch = BatchBuilder::new().place(SendOrderRequest::trailing_stop(
+            "PF_XBTUSD",
+            BuySell::Sell,
+            Decimal::ONE,
+            Decimal::from(5),
+            TrailingStopDeviationUnit::Percent,
+        ));
+
+        let json = serde_json::to_value(batch.request()).unwrap();
+        let element = &json["batchOrder"][0];
+        assert_eq!(element["orderType"], "trailing_stop");
+        assert_eq!(element["trailingStopMaxDeviation"], "5");
+        assert_eq!(element["trailingStopDeviationUnit"], "PERCENT");
+    }
+}
@@ -7,10 +7,13 @@ use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
 use reqwest_tracing::TracingMiddleware;
 
-use crate::auth::{CredentialsProvider, IncreasingNonce, NonceProvider};
+use crate::auth::{Credentials, CredentialsFile, CredentialsProvider, IncreasingNonce, NonceProvider};
 use crate::error::KrakenError;
 use crate::futures::auth::sign_futures_request;
-use crate::futures::rest::endpoints::{FUTURES_BASE_URL, private, public};
+use crate::futures::rest::FuturesClient;
+use crate::futures::rest::batch::{BatchBuilder, BatchItemResult};
+use crate::futures::rest::account_history::*;
+use crate::futures::rest::endpoints::{FUTURES_BASE_URL, charts, private, public};
 use crate::futures::rest::types::*;
 use crate::futures::types::*;
 
@@ -62,6 +65,9 @@ use crate::futures::types::*;
 #[derive(Clone)]
 pub struct FuturesRestClient {
     http_client: ClientWithMiddleware,
+    /// Same connection pool without the retry middleware, for requests that
+    /// move funds and so must not be resent blindly.
+    single_shot_client: ClientWithMiddleware,
     base_url: String,
     credentials: Option<Arc<dyn CredentialsProvider>>,
     nonce_provider: Arc<dyn NonceProvider>,
@@ -119,18 +125,41 @@ impl FuturesRestClient {
     where
         T: serde::de::DeserializeOwned,
     {
-        let credentials = self
+        let response = self.signed_get(&self.base_url, endpoint, "").await?;
+        self.parse_futures_response(response).await
+    }
+
+    /// Current credentials from the provider.
+    async fn current_credentials(&self) -> Result<Arc<Credentials>, KrakenError> {
+        let provider = self
             .credentials
             .as_ref()
             .ok_or(KrakenError::MissingCredentials)?;
+        provider.get_credentials().await
+    }
 
+    /// Send an authenticated GET request to `root` + `endpoint`.
+    ///
+    /// The query string is signed as the request's post data.
+    pub(crate) async fn signed_get(
+        &self,
+        root: &str,
+        endpoint: &str,
+        query: &str,
+    ) -> Result<reqwest::Response, KrakenError> {
+        // Fetch credentials before taking a nonce, so a slow provider cannot
+        // reorder nonces between concurrent requests.
+        let creds = self.current_credentials().await?;
         let nonce = self.nonce_provider.next_nonce();
-        let creds = credentials.get_credentials();
 
-        // Sign the request (empty post_data for GET).
-        let signature = sign_futures_request(creds, endpoint, nonce, "")?;
+        // Sign the request (the query string stands in for post data on GET).
+        let signature = sign_futures_request(&creds, endpoint, nonce, query)?;
 
-        let url = format!("{}{}", self.base_url, endpoint);
+        let url = if query.is_empty() {
+            format!("{}{}", root, endpoint)
+        } else {
+            format!("{}{}?{}", root, endpoint, query)
+        };
         let response = self
             .http_client
             .get(&url)
@@ -140,7 +169,7 @@ impl FuturesRestClient {
             .send()
             .await?;
 
-        self.parse_futures_response(response).await
+        Ok(response)
     }
 
     /// Make an authenticated POST request.
@@ -153,24 +182,48 @@ impl FuturesRestClient {
         T: serde::de::DeserializeOwned,
         P: serde::Serialize,
     {
-        let credentials = self
-            .credentials
-            .as_ref()
-            .ok_or(KrakenError::MissingCredentials)?;
+        self.signed_post(&self.http_client, endpoint, params).await
+    }
+
+    /// Make an authenticated POST request that is never resent.
+    ///
+    /// Used for transfers: a transient failure does not rule out that the
+    /// server executed the request, so a resend could move the funds twice.
+    pub(crate) async fn private_post_once<T, P>(
+        &self,
+        endpoint: &str,
+        params: &P,
+    ) -> Result<T, KrakenError>
+    where
+        T: serde::de::DeserializeOwned,
+        P: serde::Serialize,
+    {
+        self.signed_post(&self.single_shot_client, endpoint, params)
+            .await
+    }
 
+    async fn signed_post<T, P>(
+        &self,
+        http_client: &ClientWithMiddleware,
+        endpoint: &str,
+        params: &P,
+    ) -> Result<T, KrakenError>
+    where
+        T: serde::de::DeserializeOwned,
+        P: serde::Serialize,
+    {
+        let creds = self.current_credentials().await?;
         let nonce = self.nonce_provider.next_nonce();
-        let creds = credentials.get_credentials();
 
         // Build the POST body.
         let form_data = serde_urlencoded::to_string(params)
             .map_err(|e| KrakenError::InvalidResponse(e.to_string()))?;
 
         // Sign the request using the Futures algorithm.
-        let signature = sign_futures_request(creds, endpoint, nonce, &form_data)?;
+        let signature = sign_futures_request(&creds, endpoint, nonce, &form_data)?;
 
         let url = format!("{}{}", self.base_url, endpoint);
-        let response = self
-            .http_client
+        let response = http_client
             .post(&url)
             .header("APIKey", &creds.api_key)
             .header("Authent", signature)
@@ -183,12 +236,67 @@ impl FuturesRestClient {
         self.parse_futures_response(response).await
     }
 
+    /// Make an authenticated PUT request.
+    ///
+    /// Parameters are sent in the query string, which is signed as post data.
+    pub(crate) async fn private_put<T, P>(
+        &self,
+        endpoint: &str,
+        params: &P,
+    ) -> Result<T, KrakenError>
+    where
+        T: serde::de::DeserializeOwned,
+        P: serde::Serialize,
+    {
+        let creds = self.current_credentials().await?;
+        let nonce = self.nonce_provider.next_nonce();
+
+        let query = serde_urlencoded::to_string(params)
+            .map_err(|e| KrakenError::InvalidResponse(e.to_string()))?;
+        let signature = sign_futures_request(&creds, endpoint, nonce, &query)?;
+
+        let url = format!("{}{}?{}", self.base_url, endpoint, query);
+        let response = self
+            .http_client
+            .put(&url)
+            .header("APIKey", &creds.api_key)
+            .header("Authent", signature)
+            .header("Nonce", nonce.to_string())
+            .send()
+            .await?;
+
+        self.parse_futures_response(response).await
+    }
+
+    /// Make a GET request against the charts API.
+    ///
+    /// The charts API lives at the host root rather than under `/derivatives`.
+    async fn charts_get<T>(&self, path: &str) -> Result<T, KrakenError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let url = format!("{}{}{}", self.host_root(), charts::CHARTS, path);
+        let response = self.http_client.get(&url).send().await?;
+        self.parse_futures_response(response).await
+    }
+
+    /// Base URL without the `/derivatives` suffix.
+    ///
+    /// The charts and history APIs are served from the host root.
+    pub(crate) fn host_root(&self) -> &str {
+        let root = self.base_url.trim_end_matches('/');
+        root.strip_suffix("/derivatives").unwrap_or(root)
+    }
+
     /// Parse a response from the Kraken Futures API.
     ///
     /// Futures API has a different response format than Spot:
     /// - Success: `{ "result": "success", ... }`
     /// - Error: `{ "result": "error", "error": "...", "serverTime": "..." }`
-    async fn parse_futures_response<T>(&self, response: reqwest::Response) -> Result<T, KrakenError>
+    pub(crate) async fn parse_futures_response<T>(
+        &self,
+        response: reqwest::Response,
+    ) -> Result<T, KrakenError>
     where
         T: serde::de::DeserializeOwned,
     {
@@ -234,8 +342,9 @@ impl FuturesRestClient {
     ///
     /// Returns ticker data for the specified symbol, or None if not found.
     pub async fn get_ticker(&self, symbol: &str) -> Result<Option<FuturesTicker>, KrakenError> {
-        let tickers = self.get_tickers().await?;
-        Ok(tickers.into_iter().find(|t| t.symbol == symbol))
+        let endpoint = format!("{}/{}", public::TICKERS, symbol);
+        let response: TickerResponse = self.public_get(&endpoint).await?;
+        Ok(response.ticker)
     }
 
     /// Get order book for a symbol.
@@ -286,6 +395,98 @@ impl FuturesRestClient {
         Ok(response.instruments)
     }
 
+    /// Get historical funding rates for a perpetual contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The perpetual symbol (e.g., "PF_XBTUSD")
+    pub async fn get_historical_funding_rates(
+        &self,
+        symbol: &str,
+    ) -> Result<FundingRateSeries, KrakenError> {
+        #[derive(serde::Serialize)]
+        struct Params<'a> {
+            symbol: &'a str,
+        }
+        let response: HistoricalFundingRatesResponse = self
+            .public_get_with_params(public::HISTORICAL_FUNDING_RATES, &Params { symbol })
+            .await?;
+        Ok(FundingRateSeries::new(symbol, response.rates))
+    }
+
+    // Charts endpoints.
+
+    /// List the tick types available in the charts API.
+    pub async fn get_chart_tick_types(&self) -> Result<Vec<String>, KrakenError> {
+        self.charts_get("").await
+    }
+
+    /// List the symbols charted for a tick type.
+    pub async fn get_chart_symbols(&self, tick_type: TickType) -> Result<Vec<String>, KrakenError> {
+        self.charts_get(&format!("/{}", tick_type)).await
+    }
+
+    /// List the resolutions available for a tick type and symbol.
+    pub async fn get_chart_resolutions(
+        &self,
+        tick_type: TickType,
+        symbol: &str,
+    ) -> Result<Vec<String>, KrakenError> {
+        self.charts_get(&format!("/{}/{}", tick_type, symbol)).await
+    }
+
+    /// Get one page of OHLC candles.
+    ///
+    /// # Arguments
+    ///
+    /// * `tick_type` - Price series to chart
+    /// * `symbol` - The futures symbol (e.g., "PF_XBTUSD")
+    /// * `resolution` - Candle resolution
+    /// * `from` - Optional start time (Unix seconds, inclusive)
+    /// * `to` - Optional end time (Unix seconds)
+    ///
+    /// `more_candles` in the response is set when the range holds more candles
+    /// than one page; see [`get_candle_range`](Self::get_candle_range).
+    pub async fn get_candles(
+        &self,
+        tick_type: TickType,
+        symbol: &str,
+        resolution: ChartResolution,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> Result<CandlesResponse, KrakenError> {
+        #[derive(serde::Serialize)]
+        struct Params {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            from: Option<i64>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            to: Option<i64>,
+        }
+        let query = serde_urlencoded::to_string(Params { from, to })
+            .map_err(|e| KrakenError::InvalidResponse(e.to_string()))?;
+        let mut path = format!("/{}/{}/{}", tick_type, symbol, resolution);
+        if !query.is_empty() {
+            path.push('?');
+            path.push_str(&query);
+        }
+        self.charts_get(&path).await
+    }
+
+    /// Get all OHLC candles between `from` and `to` (Unix seconds).
+    ///
+    /// Requests successive pages, starting each one after the last candle
+    /// received, until the API reports no more candles.
+    pub async fn get_candle_range(
+        &self,
+        tick_type: TickType,
+        symbol: &str,
+        resolution: ChartResolution,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<FuturesCandle>, KrakenError> {
+        FuturesClient::get_candle_range(self, tick_type, symbol, resolution, from, to).await
+    }
+
     // Private endpoints: account.
 
     /// Get account information.
@@ -343,6 +544,18 @@ impl FuturesRestClient {
         self.private_post(private::SEND_ORDER, request).await
     }
 
+    /// Get the status of specific orders, including closed ones.
+    ///
+    /// Orders that are not found are omitted from the result.
+    pub async fn get_order_status(
+        &self,
+        request: &OrderStatusRequest,
+    ) -> Result<Vec<OrderStatusInfo>, KrakenError> {
+        let response: OrderStatusResponse =
+            self.private_post(private::ORDER_STATUS, request).await?;
+        Ok(response.orders)
+    }
+
     /// Edit an existing order.
     ///
     /// # Arguments
@@ -436,6 +649,96 @@ impl FuturesRestClient {
         .await
     }
 
+    // Private endpoints: account preferences.
+
+    /// Get leverage preferences.
+    ///
+    /// Only symbols with an isolated-margin preference are listed; all other
+    /// symbols use cross margin.
+    pub async fn get_leverage_preferences(&self) -> Result<Vec<LeveragePreference>, KrakenError> {
+        let response: LeveragePreferencesResponse =
+            self.private_get(private::LEVERAGE_PREFERENCES).await?;
+        Ok(response.leverage_preferences)
+    }
+
+    /// Set the margin mode for a symbol.
+    ///
+    /// [`MarginMode::Isolated`] fixes the maximum leverage; [`MarginMode::Cross`]
+    /// clears it.
+    pub async fn set_leverage_preference(
+        &self,
+        symbol: &str,
+        mode: MarginMode,
+    ) -> Result<SetPreferenceResponse, KrakenError> {
+        #[derive(serde::Serialize)]
+        struct Params<'a> {
+            symbol: &'a str,
+            #[serde(rename = "maxLeverage", skip_serializing_if = "Option::is_none")]
+            max_leverage: Option<rust_decimal::Decimal>,
+        }
+        let max_leverage = match mode {
+            MarginMode::Cross => None,
+            MarginMode::Isolated { max_leverage } => Some(max_leverage),
+        };
+        self.private_put(
+            private::LEVERAGE_PREFERENCES,
+            &Params {
+                symbol,
+                max_leverage,
+            },
+        )
+        .await
+    }
+
+    /// Get PnL currency preferences.
+    pub async fn get_pnl_preferences(&self) -> Result<Vec<PnlPreference>, KrakenError> {
+        let response: PnlPreferencesResponse = self.private_get(private::PNL_PREFERENCES).await?;
+        Ok(response.preferences)
+    }
+
+    /// Set the PnL currency for a symbol.
+    pub async fn set_pnl_preference(
+        &self,
+        symbol: &str,
+        currency: PnlCurrency,
+    ) -> Result<SetPreferenceResponse, KrakenError> {
+        #[derive(serde::Serialize)]
+        struct Params<'a> {
+            symbol: &'a str,
+            #[serde(rename = "pnlPreference")]
+            pnl_preference: &'a str,
+        }
+        self.private_put(
+            private::PNL_PREFERENCES,
+            &Params {
+                symbol,
+                pnl_preference: currency.as_str(),
+            },
+        )
+        .await
+    }
+
+    // Private endpoints: transfers.
+
+    /// Transfer funds between two futures wallets.
+    pub async fn transfer(
+        &self,
+        request: &TransferRequest,
+    ) -> Result<TransferResponse, KrakenError> {
+        self.private_post_once(private::TRANSFER, request).await
+    }
+
+    /// Withdraw funds from a futures wallet to the spot account.
+    ///
+    /// Deposits into futures are initiated from the spot side with
+    /// [`SpotRestClient::wallet_transfer`](crate::spot::rest::SpotRestClient::wallet_transfer).
+    pub async fn withdraw_to_spot(
+        &self,
+        request: &WithdrawalRequest,
+    ) -> Result<WithdrawalResponse, KrakenError> {
+        self.private_post_once(private::WITHDRAWAL, request).await
+    }
+
     /// Send batch orders.
     ///
     /// Allows placing, editing, and cancelling multiple orders in a single request.
@@ -447,7 +750,232 @@ impl FuturesRestClient {
         &self,
         request: &BatchOrderRequest,
     ) -> Result<BatchOrderResponse, KrakenError> {
-        self.private_post(private::BATCH_ORDER, request).await
+        // The batch is sent as a JSON document in the `json` form field.
+        #[derive(serde::Serialize)]
+        struct Params {
+            json: String,
+        }
+        let json = serde_json::to_string(request)?;
+        self.private_post(private::BATCH_ORDER, &Params { json })
+            .await
+    }
+
+    /// Send a batch built with [`BatchBuilder`] and map its results.
+    ///
+    /// Returns one result per instruction, in the order they were added.
+    pub async fn execute_batch(
+        &self,
+        batch: &BatchBuilder,
+    ) -> Result<Vec<BatchItemResult>, KrakenError> {
+        FuturesClient::execute_batch(self, batch).await
+    }
+}
+
+// FuturesClient trait implementation.
+
+impl FuturesClient for FuturesRestClient {
+    async fn get_tickers(&self) -> Result<Vec<FuturesTicker>, KrakenError> {
+        FuturesRestClient::get_tickers(self).await
+    }
+
+    async fn get_ticker(&self, symbol: &str) -> Result<Option<FuturesTicker>, KrakenError> {
+        FuturesRestClient::get_ticker(self, symbol).await
+    }
+
+    async fn get_orderbook(&self, symbol: &str) -> Result<FuturesOrderBook, KrakenError> {
+        FuturesRestClient::get_orderbook(self, symbol).await
+    }
+
+    async fn get_trade_history(
+        &self,
+        symbol: &str,
+        last_time: Option<&str>,
+    ) -> Result<Vec<FuturesTrade>, KrakenError> {
+        FuturesRestClient::get_trade_history(self, symbol, last_time).await
+    }
+
+    async fn get_instruments(&self) -> Result<Vec<FuturesInstrument>, KrakenError> {
+        FuturesRestClient::get_instruments(self).await
+    }
+
+    async fn get_historical_funding_rates(
+        &self,
+        symbol: &str,
+    ) -> Result<FundingRateSeries, KrakenError> {
+        FuturesRestClient::get_historical_funding_rates(self, symbol).await
+    }
+
+    async fn get_chart_tick_types(&self) -> Result<Vec<String>, KrakenError> {
+        FuturesRestClient::get_chart_tick_types(self).await
+    }
+
+    async fn get_chart_symbols(&self, tick_type: TickType) -> Result<Vec<String>, KrakenError> {
+        FuturesRestClient::get_chart_symbols(self, tick_type).await
+    }
+
+    async fn get_chart_resolutions(
+        &self,
+        tick_type: TickType,
+        symbol: &str,
+    ) -> Result<Vec<String>, KrakenError> {
+        FuturesRestClient::get_chart_resolutions(self, tick_type, symbol).await
+    }
+
+    async fn get_candles(
+        &self,
+        tick_type: TickType,
+        symbol: &str,
+        resolution: ChartResolution,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> Result<CandlesResponse, KrakenError> {
+        FuturesRestClient::get_candles(self, tick_type, symbol, resolution, from, to).await
+    }
+
+    async fn get_accounts(&self) -> Result<AccountsResponse, KrakenError> {
+        FuturesRestClient::get_accounts(self).await
+    }
+
+    async fn get_open_positions(&self) -> Result<Vec<FuturesPosition>, KrakenError> {
+        FuturesRestClient::get_open_positions(self).await
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<FuturesOrder>, KrakenError> {
+        FuturesRestClient::get_open_orders(self).await
+    }
+
+    async fn get_fills(
+        &self,
+        request: Option<&FillsRequest>,
+    ) -> Result<Vec<FuturesFill>, KrakenError> {
+        FuturesRestClient::get_fills(self, request).await
+    }
+
+    async fn send_order(
+        &self,
+        request: &SendOrderRequest,
+    ) -> Result<SendOrderResponse, KrakenError> {
+        FuturesRestClient::send_order(self, request).await
+    }
+
+    async fn get_order_status(
+        &self,
+        request: &OrderStatusRequest,
+    ) -> Result<Vec<OrderStatusInfo>, KrakenError> {
+        FuturesRestClient::get_order_status(self, request).await
+    }
+
+    async fn edit_order(
+        &self,
+        request: &EditOrderRequest,
+    ) -> Result<EditOrderResponse, KrakenError> {
+        FuturesRestClient::edit_order(self, request).await
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<CancelOrderResponse, KrakenError> {
+        FuturesRestClient::cancel_order(self, order_id).await
+    }
+
+    async fn cancel_order_by_cli_ord_id(
+        &self,
+        cli_ord_id: &str,
+    ) -> Result<CancelOrderResponse, KrakenError> {
+        FuturesRestClient::cancel_order_by_cli_ord_id(self, cli_ord_id).await
+    }
+
+    async fn cancel_all_orders(&self) -> Result<CancelAllOrdersResponse, KrakenError> {
+        FuturesRestClient::cancel_all_orders(self).await
+    }
+
+    async fn cancel_all_orders_for_symbol(
+        &self,
+        symbol: &str,
+    ) -> Result<CancelAllOrdersResponse, KrakenError> {
+        FuturesRestClient::cancel_all_orders_for_symbol(self, symbol).await
+    }
+
+    async fn cancel_all_orders_after(
+        &self,
+        timeout_seconds: u32,
+    ) -> Result<CancelAllOrdersAfterResponse, KrakenError> {
+        FuturesRestClient::cancel_all_orders_after(self, timeout_seconds).await
+    }
+
+    async fn batch_order(
+        &self,
+        request: &BatchOrderRequest,
+    ) -> Result<BatchOrderResponse, KrakenError> {
+        FuturesRestClient::batch_order(self, request).await
+    }
+
+    async fn get_leverage_preferences(&self) -> Result<Vec<LeveragePreference>, KrakenError> {
+        FuturesRestClient::get_leverage_preferences(self).await
+    }
+
+    async fn set_leverage_preference(
+        &self,
+        symbol: &str,
+        mode: MarginMode,
+    ) -> Result<SetPreferenceResponse, KrakenError> {
+        FuturesRestClient::set_leverage_preference(self, symbol, mode).await
+    }
+
+    async fn get_pnl_preferences(&self) -> Result<Vec<PnlPreference>, KrakenError> {
+        FuturesRestClient::get_pnl_preferences(self).await
+    }
+
+    async fn set_pnl_preference(
+        &self,
+        symbol: &str,
+        currency: PnlCurrency,
+    ) -> Result<SetPreferenceResponse, KrakenError> {
+        FuturesRestClient::set_pnl_preference(self, symbol, currency).await
+    }
+
+    async fn transfer(&self, request: &TransferRequest) -> Result<TransferResponse, KrakenError> {
+        FuturesRestClient::transfer(self, request).await
+    }
+
+    async fn withdraw_to_spot(
+        &self,
+        request: &WithdrawalRequest,
+    ) -> Result<WithdrawalResponse, KrakenError> {
+        FuturesRestClient::withdraw_to_spot(self, request).await
+    }
+
+    async fn get_executions(
+        &self,
+        request: &HistoryRequest,
+    ) -> Result<HistoryPage<ExecutionEvent>, KrakenError> {
+        FuturesRestClient::get_executions(self, request).await
+    }
+
+    async fn get_order_history(
+        &self,
+        request: &HistoryRequest,
+    ) -> Result<HistoryPage<OrderEvent>, KrakenError> {
+        FuturesRestClient::get_order_history(self, request).await
+    }
+
+    async fn get_trigger_history(
+        &self,
+        request: &HistoryRequest,
+    ) -> Result<HistoryPage<OrderEvent>, KrakenError> {
+        FuturesRestClient::get_trigger_history(self, request).await
+    }
+
+    async fn get_account_log(
+        &self,
+        request: &AccountLogRequest,
+    ) -> Result<AccountLogResponse, KrakenError> {
+        FuturesRestClient::get_account_log(self, request).await
+    }
+
+    async fn get_account_log_csv(
+        &self,
+        request: &AccountLogRequest,
+    ) -> Result<String, KrakenError> {
+        FuturesRestClient::get_account_log_csv(self, request).await
     }
 }
 
@@ -505,6 +1033,21 @@ impl FuturesRestClientBuilder {
         self
     }
 
+    /// Use the futures key of a profile from the default credentials file,
+    /// switching to the demo environment if the profile is marked `demo`.
+    ///
+    /// See [`CredentialsFile`] for the file format and location.
+    pub fn profile(self, name: &str) -> Result<Self, KrakenError> {
+        let file = CredentialsFile::load_default()?;
+        let profile = file.profile(name)?;
+        let builder = self.credentials(Arc::new(profile.futures()?));
+        Ok(if profile.is_demo() {
+            builder.use_demo()
+        } else {
+            builder
+        })
+    }
+
     /// Set a custom nonce provider.
     pub fn nonce_provider(mut self, provider: Arc<dyn NonceProvider>) -> Self {
         self.nonce_provider = Some(provider);
@@ -518,6 +1061,8 @@ impl FuturesRestClientBuilder {
     }
 
     /// Set the maximum number of retries for transient failures.
+    ///
+    /// Transfers and withdrawals to spot are never retried.
     pub fn max_retries(mut self, retries: u32) -> Self {
         self.max_retries = retries;
         self
@@ -542,10 +1087,13 @@ impl FuturesRestClientBuilder {
 
         let retry_policy = ExponentialBackoff::builder().build_with_max_retries(self.max_retries);
 
-        let client = ClientBuilder::new(reqwest_client)
+        let client = ClientBuilder::new(reqwest_client.clone())
             .with(TracingMiddleware::default())
             .with(RetryTransientMiddleware::new_with_policy(retry_policy))
             .build();
+        let single_shot_client = ClientBuilder::new(reqwest_client)
+            .with(TracingMiddleware::default())
+            .build();
 
         let nonce_provider = self
             .nonce_provider
@@ -553,6 +1101,7 @@ impl FuturesRestClientBuilder {
 
         FuturesRestClient {
             http_client: client,
+            single_shot_client,
             base_url: self.base_url,
             credentials: self.credentials,
             nonce_provider,
@@ -8,7 +8,7 @@ pub const FUTURES_DEMO_URL: &str = "https://demo-futures.kraken.com/derivatives"
 
 /// Public endpoints (no authentication required).
 pub mod public {
-    /// Get all tickers.
+    /// Get all tickers; append `/{symbol}` for a single ticker.
     pub const TICKERS: &str = "/api/v3/tickers";
 
     /// Get order book for a symbol.
@@ -19,6 +19,33 @@ pub mod public {
 
     /// Get available instruments.
     pub const INSTRUMENTS: &str = "/api/v3/instruments";
+
+    /// Get historical funding rates for a perpetual.
+    pub const HISTORICAL_FUNDING_RATES: &str = "/api/v3/historical-funding-rates";
+}
+
+/// Charts endpoints, served next to (not under) the `/derivatives` base path.
+pub mod charts {
+    /// Charts API root: `/{tick_type}/{symbol}/{resolution}`.
+    pub const CHARTS: &str = "/api/charts/v1";
+}
+
+/// Account history endpoints (authentication required), served from the host root.
+pub mod history {
+    /// Execution history.
+    pub const EXECUTIONS: &str = "/api/history/v3/executions";
+
+    /// Order event history.
+    pub const ORDERS: &str = "/api/history/v3/orders";
+
+    /// Trigger order event history.
+    pub const TRIGGERS: &str = "/api/history/v3/triggers";
+
+    /// Account log.
+    pub const ACCOUNT_LOG: &str = "/api/history/v2/account-log";
+
+    /// Account log as CSV.
+    pub const ACCOUNT_LOG_CSV: &str = "/api/history/v2/accountlogcsv";
 }
 
 /// Private endpoints (authentication required).
@@ -52,4 +79,19 @@ pub mod private {
 
     /// Batch order operations.
     pub const BATCH_ORDER: &str = "/api/v3/batchorder";
+
+    /// Look up order status by order ID or client order ID.
+    pub const ORDER_STATUS: &str = "/api/v3/orders/status";
+
+    /// Get or set leverage preferences (margin mode per symbol).
+    pub const LEVERAGE_PREFERENCES: &str = "/api/v3/leveragepreferences";
+
+    /// Get or set PnL currency preferences.
+    pub const PNL_PREFERENCES: &str = "/api/v3/pnlpreferences";
+
+    /// Transfer funds between futures wallets.
+    pub const TRANSFER: &str = "/api/v3/transfer";
+
+    /// Withdraw funds from a futures wallet to the spot account.
+    pub const WITHDRAWAL: &str = "/api/v3/withdrawal";
 }
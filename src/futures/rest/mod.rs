@@ -2,10 +2,16 @@
 //!
 //! This module provides the REST API client for Kraken Futures trading.
 
+mod account_history;
+mod batch;
 mod client;
 mod endpoints;
+mod traits;
 mod types;
 
+pub use account_history::*;
+pub use batch::*;
 pub use client::{FuturesRestClient, FuturesRestClientBuilder};
 pub use endpoints::*;
+pub use traits::FuturesClient;
 pub use types::*;
@@ -0,0 +1,371 @@
+//! Trait definition for the Kraken Futures REST API client.
+//!
+//! This module provides the `FuturesClient` trait, the futures counterpart of
+//! [`KrakenClient`](crate::spot::rest::KrakenClient). It enables:
+//! - Mock implementations for testing
+//! - Decorator pattern (e.g., rate limiting wrapper)
+//! - Alternative implementations
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kraken_api_client::futures::rest::{FuturesClient, FuturesRestClient};
+//!
+//! async fn print_positions<C: FuturesClient>(client: &C) -> Result<(), kraken_api_client::KrakenError> {
+//!     for position in client.get_open_positions().await? {
+//!         println!("{} {}", position.symbol, position.size);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::future::Future;
+
+use futures_util::Stream;
+
+use crate::error::KrakenError;
+use crate::futures::rest::account_history::{paginate_account_log, paginate_history};
+use crate::futures::rest::{
+    AccountLogRequest, AccountLogResponse, AccountsResponse, BatchBuilder, BatchItemResult,
+    BatchOrderRequest, BatchOrderResponse, CancelAllOrdersAfterResponse, CancelAllOrdersResponse,
+    CancelOrderResponse, CandlesResponse, EditOrderRequest, EditOrderResponse, ExecutionEvent,
+    FillsRequest, HistoryElement, HistoryPage, HistoryRequest, OrderEvent, OrderStatusInfo,
+    OrderStatusRequest, SendOrderRequest, SendOrderResponse, SetPreferenceResponse,
+    TransferRequest, TransferResponse, WithdrawalRequest, WithdrawalResponse,
+};
+use crate::futures::types::{
+    ChartResolution, FundingRateSeries, FuturesCandle, FuturesFill, FuturesInstrument,
+    FuturesOrder, FuturesOrderBook, FuturesPosition, FuturesTicker, FuturesTrade,
+    LeveragePreference, MarginMode, PnlCurrency, PnlPreference, TickType,
+};
+use crate::futures::ws::AccountLogEntry;
+
+/// Trait defining all Kraken Futures REST API operations.
+///
+/// This trait enables dependency injection and allows for:
+/// - Testing with mock implementations
+/// - Wrapping with decorators (e.g., rate limiting)
+/// - Alternative implementations
+///
+/// All methods are async and return `Result<T, KrakenError>`. Multi-page
+/// helpers such as [`get_candle_range`](Self::get_candle_range) and the
+/// history streams are provided on top of the single-page methods, so
+/// decorators see every request.
+pub trait FuturesClient: Send + Sync {
+    // ========== Public Endpoints ==========
+
+    /// Get all tickers.
+    fn get_tickers(&self) -> impl Future<Output = Result<Vec<FuturesTicker>, KrakenError>> + Send;
+
+    /// Get ticker for a specific symbol.
+    fn get_ticker(
+        &self,
+        symbol: &str,
+    ) -> impl Future<Output = Result<Option<FuturesTicker>, KrakenError>> + Send;
+
+    /// Get order book for a symbol.
+    fn get_orderbook(
+        &self,
+        symbol: &str,
+    ) -> impl Future<Output = Result<FuturesOrderBook, KrakenError>> + Send;
+
+    /// Get recent trade history for a symbol.
+    fn get_trade_history(
+        &self,
+        symbol: &str,
+        last_time: Option<&str>,
+    ) -> impl Future<Output = Result<Vec<FuturesTrade>, KrakenError>> + Send;
+
+    /// Get available instruments.
+    fn get_instruments(
+        &self,
+    ) -> impl Future<Output = Result<Vec<FuturesInstrument>, KrakenError>> + Send;
+
+    /// Get historical funding rates for a perpetual contract.
+    fn get_historical_funding_rates(
+        &self,
+        symbol: &str,
+    ) -> impl Future<Output = Result<FundingRateSeries, KrakenError>> + Send;
+
+    // ========== Charts Endpoints ==========
+
+    /// List the tick types available in the charts API.
+    fn get_chart_tick_types(&self)
+    -> impl Future<Output = Result<Vec<String>, KrakenError>> + Send;
+
+    /// List the symbols charted for a tick type.
+    fn get_chart_symbols(
+        &self,
+        tick_type: TickType,
+    ) -> impl Future<Output = Result<Vec<String>, KrakenError>> + Send;
+
+    /// List the resolutions available for a tick type and symbol.
+    fn get_chart_resolutions(
+        &self,
+        tick_type: TickType,
+        symbol: &str,
+    ) -> impl Future<Output = Result<Vec<String>, KrakenError>> + Send;
+
+    /// Get one page of OHLC candles.
+    fn get_candles(
+        &self,
+        tick_type: TickType,
+        symbol: &str,
+        resolution: ChartResolution,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> impl Future<Output = Result<CandlesResponse, KrakenError>> + Send;
+
+    /// Get all OHLC candles between `from` and `to` (Unix seconds).
+    ///
+    /// Requests successive pages with [`get_candles`](Self::get_candles),
+    /// starting each one after the last candle received, until the API
+    /// reports no more candles.
+    fn get_candle_range(
+        &self,
+        tick_type: TickType,
+        symbol: &str,
+        resolution: ChartResolution,
+        from: i64,
+        to: i64,
+    ) -> impl Future<Output = Result<Vec<FuturesCandle>, KrakenError>> + Send {
+        async move {
+            let mut candles: Vec<FuturesCandle> = Vec::new();
+            let mut next_from = from;
+
+            while next_from < to {
+                let page = self
+                    .get_candles(tick_type, symbol, resolution, Some(next_from), Some(to))
+                    .await?;
+                let Some(last) = page.candles.last() else {
+                    break;
+                };
+                let last_time = last.time;
+                let seen = candles.last().map(|c| c.time);
+                candles.extend(
+                    page.candles
+                        .into_iter()
+                        .filter(|c| seen.is_none_or(|seen| c.time > seen)),
+                );
+
+                if !page.more_candles || last_time < next_from {
+                    break;
+                }
+                next_from = last_time + 1;
+            }
+
+            Ok(candles)
+        }
+    }
+
+    // ========== Private Endpoints - Account ==========
+
+    /// Get account information.
+    fn get_accounts(&self) -> impl Future<Output = Result<AccountsResponse, KrakenError>> + Send;
+
+    /// Get open positions.
+    fn get_open_positions(
+        &self,
+    ) -> impl Future<Output = Result<Vec<FuturesPosition>, KrakenError>> + Send;
+
+    /// Get open orders.
+    fn get_open_orders(
+        &self,
+    ) -> impl Future<Output = Result<Vec<FuturesOrder>, KrakenError>> + Send;
+
+    /// Get fills (trade history).
+    fn get_fills(
+        &self,
+        request: Option<&FillsRequest>,
+    ) -> impl Future<Output = Result<Vec<FuturesFill>, KrakenError>> + Send;
+
+    // ========== Private Endpoints - Trading ==========
+
+    /// Send a new order.
+    fn send_order(
+        &self,
+        request: &SendOrderRequest,
+    ) -> impl Future<Output = Result<SendOrderResponse, KrakenError>> + Send;
+
+    /// Get the status of specific orders.
+    fn get_order_status(
+        &self,
+        request: &OrderStatusRequest,
+    ) -> impl Future<Output = Result<Vec<OrderStatusInfo>, KrakenError>> + Send;
+
+    /// Edit an existing order.
+    fn edit_order(
+        &self,
+        request: &EditOrderRequest,
+    ) -> impl Future<Output = Result<EditOrderResponse, KrakenError>> + Send;
+
+    /// Cancel an order.
+    fn cancel_order(
+        &self,
+        order_id: &str,
+    ) -> impl Future<Output = Result<CancelOrderResponse, KrakenError>> + Send;
+
+    /// Cancel an order by client order ID.
+    fn cancel_order_by_cli_ord_id(
+        &self,
+        cli_ord_id: &str,
+    ) -> impl Future<Output = Result<CancelOrderResponse, KrakenError>> + Send;
+
+    /// Cancel all orders.
+    fn cancel_all_orders(
+        &self,
+    ) -> impl Future<Output = Result<CancelAllOrdersResponse, KrakenError>> + Send;
+
+    /// Cancel all orders for a specific symbol.
+    fn cancel_all_orders_for_symbol(
+        &self,
+        symbol: &str,
+    ) -> impl Future<Output = Result<CancelAllOrdersResponse, KrakenError>> + Send;
+
+    /// Set dead man's switch (cancel all orders after timeout).
+    fn cancel_all_orders_after(
+        &self,
+        timeout_seconds: u32,
+    ) -> impl Future<Output = Result<CancelAllOrdersAfterResponse, KrakenError>> + Send;
+
+    /// Send batch orders.
+    fn batch_order(
+        &self,
+        request: &BatchOrderRequest,
+    ) -> impl Future<Output = Result<BatchOrderResponse, KrakenError>> + Send;
+
+    /// Send a batch built with [`BatchBuilder`] and map its results.
+    ///
+    /// Returns one result per instruction, in the order they were added.
+    fn execute_batch(
+        &self,
+        batch: &BatchBuilder,
+    ) -> impl Future<Output = Result<Vec<BatchItemResult>, KrakenError>> + Send {
+        async move {
+            let response = self.batch_order(&batch.request()).await?;
+            Ok(batch.results(response.batch_status))
+        }
+    }
+
+    // ========== Private Endpoints - Preferences ==========
+
+    /// Get leverage preferences.
+    fn get_leverage_preferences(
+        &self,
+    ) -> impl Future<Output = Result<Vec<LeveragePreference>, KrakenError>> + Send;
+
+    /// Set the margin mode for a symbol.
+    fn set_leverage_preference(
+        &self,
+        symbol: &str,
+        mode: MarginMode,
+    ) -> impl Future<Output = Result<SetPreferenceResponse, KrakenError>> + Send;
+
+    /// Get PnL currency preferences.
+    fn get_pnl_preferences(
+        &self,
+    ) -> impl Future<Output = Result<Vec<PnlPreference>, KrakenError>> + Send;
+
+    /// Set the PnL currency for a symbol.
+    fn set_pnl_preference(
+        &self,
+        symbol: &str,
+        currency: PnlCurrency,
+    ) -> impl Future<Output = Result<SetPreferenceResponse, KrakenError>> + Send;
+
+    // ========== Private Endpoints - Transfers ==========
+
+    /// Transfer funds between two futures wallets.
+    fn transfer(
+        &self,
+        request: &TransferRequest,
+    ) -> impl Future<Output = Result<TransferResponse, KrakenError>> + Send;
+
+    /// Withdraw funds from a futures wallet to the spot account.
+    fn withdraw_to_spot(
+        &self,
+        request: &WithdrawalRequest,
+    ) -> impl Future<Output = Result<WithdrawalResponse, KrakenError>> + Send;
+
+    // ========== Private Endpoints - History ==========
+
+    /// Get one page of execution history.
+    fn get_executions(
+        &self,
+        request: &HistoryRequest,
+    ) -> impl Future<Output = Result<HistoryPage<ExecutionEvent>, KrakenError>> + Send;
+
+    /// Get one page of order event history.
+    fn get_order_history(
+        &self,
+        request: &HistoryRequest,
+    ) -> impl Future<Output = Result<HistoryPage<OrderEvent>, KrakenError>> + Send;
+
+    /// Get one page of trigger order event history.
+    fn get_trigger_history(
+        &self,
+        request: &HistoryRequest,
+    ) -> impl Future<Output = Result<HistoryPage<OrderEvent>, KrakenError>> + Send;
+
+    /// Get one page of the account log.
+    fn get_account_log(
+        &self,
+        request: &AccountLogRequest,
+    ) -> impl Future<Output = Result<AccountLogResponse, KrakenError>> + Send;
+
+    /// Export the account log as CSV.
+    fn get_account_log_csv(
+        &self,
+        request: &AccountLogRequest,
+    ) -> impl Future<Output = Result<String, KrakenError>> + Send;
+
+    /// Stream all executions matching `request`, following continuation tokens.
+    ///
+    /// Each page is fetched with [`get_executions`](Self::get_executions).
+    fn executions_stream(
+        &self,
+        request: HistoryRequest,
+    ) -> impl Stream<Item = Result<HistoryElement<ExecutionEvent>, KrakenError>> + Send + '_ {
+        paginate_history(request, move |request| async move {
+            self.get_executions(&request).await
+        })
+    }
+
+    /// Stream all order events matching `request`, following continuation tokens.
+    ///
+    /// Each page is fetched with [`get_order_history`](Self::get_order_history).
+    fn order_history_stream(
+        &self,
+        request: HistoryRequest,
+    ) -> impl Stream<Item = Result<HistoryElement<OrderEvent>, KrakenError>> + Send + '_ {
+        paginate_history(request, move |request| async move {
+            self.get_order_history(&request).await
+        })
+    }
+
+    /// Stream all trigger events matching `request`, following continuation tokens.
+    ///
+    /// Each page is fetched with [`get_trigger_history`](Self::get_trigger_history).
+    fn trigger_history_stream(
+        &self,
+        request: HistoryRequest,
+    ) -> impl Stream<Item = Result<HistoryElement<OrderEvent>, KrakenError>> + Send + '_ {
+        paginate_history(request, move |request| async move {
+            self.get_trigger_history(&request).await
+        })
+    }
+
+    /// Stream all account log entries matching `request`, newest first.
+    ///
+    /// Each page is fetched with [`get_account_log`](Self::get_account_log).
+    /// The account log has no continuation token; pages are chained by
+    /// lowering the `to` entry ID below the oldest entry seen so far.
+    fn account_log_stream(
+        &self,
+        request: AccountLogRequest,
+    ) -> impl Stream<Item = Result<AccountLogEntry, KrakenError>> + Send + '_ {
+        paginate_account_log(request, move |request| async move {
+            self.get_account_log(&request).await
+        })
+    }
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::futures::types::*;
+use crate::pairs::IntoFuturesSymbol;
 use crate::types::common::BuySell;
 
 
@@ -23,6 +24,41 @@ pub struct TickersResponse {
     pub server_time: Option<String>,
 }
 
+/// Response for the single-symbol ticker endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TickerResponse {
+    /// Result status
+    pub result: String,
+    /// Ticker data
+    #[serde(default)]
+    pub ticker: Option<FuturesTicker>,
+    /// Server time
+    #[serde(rename = "serverTime")]
+    pub server_time: Option<String>,
+}
+
+/// Response for historical funding rates endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HistoricalFundingRatesResponse {
+    /// Result status
+    pub result: String,
+    /// Funding rates, oldest first
+    pub rates: Vec<FundingRate>,
+    /// Server time
+    #[serde(rename = "serverTime")]
+    pub server_time: Option<String>,
+}
+
+/// Response for the charts candles endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CandlesResponse {
+    /// Candles, oldest first
+    pub candles: Vec<FuturesCandle>,
+    /// Whether more candles exist in the requested range
+    #[serde(default)]
+    pub more_candles: bool,
+}
+
 /// Response for order book endpoint.
 #[derive(Debug, Clone, Deserialize)]
 pub struct OrderBookResponse {
@@ -129,7 +165,7 @@ pub struct FillsResponse {
 /// Request to send a new order.
 #[derive(Debug, Clone, Serialize)]
 pub struct SendOrderRequest {
-    /// The order type (lmt, mkt, stp, take_profit, ioc)
+    /// The order type (lmt, mkt, stp, take_profit, trailing_stop, ioc)
     #[serde(rename = "orderType")]
     pub order_type: FuturesOrderType,
     /// The symbol (e.g., "PI_XBTUSD")
@@ -138,18 +174,26 @@ pub struct SendOrderRequest {
     pub side: BuySell,
     /// Order size (number of contracts)
     pub size: Decimal,
-    /// Limit price (required for limit orders)
+    /// Limit price (required for limit orders, optional for stop and take-profit orders)
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "limitPrice")]
     pub limit_price: Option<Decimal>,
-    /// Stop price (required for stop orders)
+    /// Trigger price (required for stop and take-profit orders)
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "stopPrice")]
     pub stop_price: Option<Decimal>,
-    /// Trigger signal for stop orders (mark or last)
+    /// Trigger signal for stop, take-profit and trailing-stop orders
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "triggerSignal")]
-    pub trigger_signal: Option<String>,
+    pub trigger_signal: Option<TriggerSignal>,
+    /// Maximum deviation from the best price (required for trailing-stop orders)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "trailingStopMaxDeviation")]
+    pub trailing_stop_max_deviation: Option<Decimal>,
+    /// Unit of the trailing-stop deviation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "trailingStopDeviationUnit")]
+    pub trailing_stop_deviation_unit: Option<TrailingStopDeviationUnit>,
     /// Reduce-only order
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "reduceOnly")]
@@ -161,56 +205,98 @@ pub struct SendOrderRequest {
 }
 
 impl SendOrderRequest {
-    /// Create a new limit order request.
-    pub fn limit(symbol: impl Into<String>, side: BuySell, size: Decimal, price: Decimal) -> Self {
+    fn new(
+        order_type: FuturesOrderType,
+        symbol: impl IntoFuturesSymbol,
+        side: BuySell,
+        size: Decimal,
+    ) -> Self {
         Self {
-            order_type: FuturesOrderType::Limit,
-            symbol: symbol.into(),
+            order_type,
+            symbol: symbol.into_futures_symbol(),
             side,
             size,
-            limit_price: Some(price),
+            limit_price: None,
             stop_price: None,
             trigger_signal: None,
+            trailing_stop_max_deviation: None,
+            trailing_stop_deviation_unit: None,
             reduce_only: None,
             cli_ord_id: None,
         }
     }
 
-    /// Create a new market order request.
-    pub fn market(symbol: impl Into<String>, side: BuySell, size: Decimal) -> Self {
+    /// Create a new limit order request.
+    pub fn limit(
+        symbol: impl IntoFuturesSymbol,
+        side: BuySell,
+        size: Decimal,
+        price: Decimal,
+    ) -> Self {
         Self {
-            order_type: FuturesOrderType::Market,
-            symbol: symbol.into(),
-            side,
-            size,
-            limit_price: None,
-            stop_price: None,
-            trigger_signal: None,
-            reduce_only: None,
-            cli_ord_id: None,
+            limit_price: Some(price),
+            ..Self::new(FuturesOrderType::Limit, symbol, side, size)
         }
     }
 
+    /// Create a new market order request.
+    pub fn market(symbol: impl IntoFuturesSymbol, side: BuySell, size: Decimal) -> Self {
+        Self::new(FuturesOrderType::Market, symbol, side, size)
+    }
+
     /// Create a new stop order request.
     pub fn stop(
-        symbol: impl Into<String>,
+        symbol: impl IntoFuturesSymbol,
         side: BuySell,
         size: Decimal,
         stop_price: Decimal,
     ) -> Self {
         Self {
-            order_type: FuturesOrderType::Stop,
-            symbol: symbol.into(),
-            side,
-            size,
-            limit_price: None,
             stop_price: Some(stop_price),
-            trigger_signal: None,
-            reduce_only: None,
-            cli_ord_id: None,
+            ..Self::new(FuturesOrderType::Stop, symbol, side, size)
+        }
+    }
+
+    /// Create a new take-profit order request.
+    ///
+    /// Executes at market once `trigger_price` is reached; add
+    /// [`limit_price`](Self::limit_price) for a take-profit limit order.
+    pub fn take_profit(
+        symbol: impl IntoFuturesSymbol,
+        side: BuySell,
+        size: Decimal,
+        trigger_price: Decimal,
+    ) -> Self {
+        Self {
+            stop_price: Some(trigger_price),
+            ..Self::new(FuturesOrderType::TakeProfit, symbol, side, size)
+        }
+    }
+
+    /// Create a new trailing-stop order request.
+    ///
+    /// The trigger trails the best price by at most `max_deviation`, expressed
+    /// in `unit`.
+    pub fn trailing_stop(
+        symbol: impl IntoFuturesSymbol,
+        side: BuySell,
+        size: Decimal,
+        max_deviation: Decimal,
+        unit: TrailingStopDeviationUnit,
+    ) -> Self {
+        Self {
+            trailing_stop_max_deviation: Some(max_deviation),
+            trailing_stop_deviation_unit: Some(unit),
+            ..Self::new(FuturesOrderType::TrailingStop, symbol, side, size)
         }
     }
 
+    /// Set the limit price (turns a stop or take-profit into a stop-limit order).
+    pub fn limit_price(mut self, price: Decimal) -> Self {
+        self.limit_price = Some(price);
+        self
+    }
+
     /// Set the reduce-only flag.
     pub fn reduce_only(mut self, reduce_only: bool) -> Self {
         self.reduce_only = Some(reduce_only);
@@ -223,9 +309,9 @@ impl SendOrderRequest {
         self
     }
 
-    /// Set the trigger signal for stop orders.
-    pub fn trigger_signal(mut self, signal: impl Into<String>) -> Self {
-        self.trigger_signal = Some(signal.into());
+    /// Set the trigger signal for stop, take-profit and trailing-stop orders.
+    pub fn trigger_signal(mut self, signal: TriggerSignal) -> Self {
+        self.trigger_signal = Some(signal);
         self
     }
 }
@@ -432,6 +518,327 @@ pub struct CancelAllOrdersAfterResponse {
     pub server_time: Option<String>,
 }
 
+/// Request for the order status endpoint.
+///
+/// Orders can be looked up by exchange ID, client order ID, or both.
+#[derive(Debug, Clone, Default)]
+pub struct OrderStatusRequest {
+    /// Exchange order IDs
+    pub order_ids: Vec<String>,
+    /// Client order IDs
+    pub cli_ord_ids: Vec<String>,
+}
+
+impl OrderStatusRequest {
+    /// Look up a single order by exchange ID.
+    pub fn by_order_id(order_id: impl Into<String>) -> Self {
+        Self::default().order_id(order_id)
+    }
+
+    /// Look up a single order by client order ID.
+    pub fn by_cli_ord_id(cli_ord_id: impl Into<String>) -> Self {
+        Self::default().cli_ord_id(cli_ord_id)
+    }
+
+    /// Add an exchange order ID.
+    pub fn order_id(mut self, order_id: impl Into<String>) -> Self {
+        self.order_ids.push(order_id.into());
+        self
+    }
+
+    /// Add a client order ID.
+    pub fn cli_ord_id(mut self, cli_ord_id: impl Into<String>) -> Self {
+        self.cli_ord_ids.push(cli_ord_id.into());
+        self
+    }
+}
+
+impl Serialize for OrderStatusRequest {
+    // Each ID is sent as a repeated form key.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let order_ids = self.order_ids.iter().map(|id| ("orderIds", id));
+        let cli_ord_ids = self.cli_ord_ids.iter().map(|id| ("cliOrdIds", id));
+        serializer.collect_seq(order_ids.chain(cli_ord_ids))
+    }
+}
+
+/// Response for the order status endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderStatusResponse {
+    /// Result status
+    pub result: String,
+    /// Status of each requested order that was found
+    #[serde(default)]
+    pub orders: Vec<OrderStatusInfo>,
+    /// Server time
+    #[serde(rename = "serverTime")]
+    pub server_time: Option<String>,
+}
+
+/// Status of one order, as returned by the order status endpoint.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "RawOrderStatus")]
+pub struct OrderStatusInfo {
+    /// The order, with `status` derived from the lifecycle state
+    pub order: FuturesOrder,
+    /// Lifecycle state
+    pub lifecycle: OrderLifecycle,
+    /// Trigger price (trigger orders only)
+    pub trigger_price: Option<Decimal>,
+    /// Trigger signal (trigger orders only)
+    pub trigger_signal: Option<TriggerSignal>,
+    /// Reason for the last update (e.g., "LIMIT_FILLED")
+    pub update_reason: Option<String>,
+    /// Error reported for the order, if any
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawOrderStatus {
+    order: RawCachedOrder,
+    status: OrderLifecycle,
+    #[serde(default)]
+    update_reason: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawCachedOrder {
+    #[serde(rename = "type", default)]
+    kind: Option<String>,
+    #[serde(default)]
+    order_type: Option<FuturesOrderType>,
+    order_id: String,
+    #[serde(default)]
+    cli_ord_id: Option<String>,
+    symbol: String,
+    side: BuySell,
+    quantity: Decimal,
+    #[serde(default)]
+    filled: Decimal,
+    #[serde(default)]
+    limit_price: Option<Decimal>,
+    #[serde(default)]
+    reduce_only: bool,
+    #[serde(default)]
+    timestamp: Option<String>,
+    #[serde(default)]
+    last_update_timestamp: Option<String>,
+    #[serde(default)]
+    price_trigger_options: Option<RawTriggerOptions>,
+    #[serde(default)]
+    trailing_stop_options: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTriggerOptions {
+    #[serde(default)]
+    trigger_price: Option<Decimal>,
+    #[serde(default)]
+    trigger_side: Option<String>,
+    #[serde(default)]
+    trigger_signal: Option<TriggerSignal>,
+}
+
+impl RawCachedOrder {
+    /// Order type when the response omits `orderType`.
+    ///
+    /// A trigger that fires as the price moves against the position is a
+    /// stop; one that fires as it moves in favor is a take-profit.
+    fn derived_order_type(&self) -> FuturesOrderType {
+        if self.kind.as_deref() != Some("TRIGGER_ORDER") {
+            return if self.limit_price.is_some() {
+                FuturesOrderType::Limit
+            } else {
+                FuturesOrderType::Market
+            };
+        }
+        if self.trailing_stop_options.is_some() {
+            return FuturesOrderType::TrailingStop;
+        }
+        let trigger_side = self
+            .price_trigger_options
+            .as_ref()
+            .and_then(|t| t.trigger_side.as_deref());
+        match (self.side, trigger_side) {
+            (BuySell::Buy, Some("TRIGGER_BELOW")) | (BuySell::Sell, Some("TRIGGER_ABOVE")) => {
+                FuturesOrderType::TakeProfit
+            }
+            _ => FuturesOrderType::Stop,
+        }
+    }
+}
+
+impl From<RawOrderStatus> for OrderStatusInfo {
+    fn from(raw: RawOrderStatus) -> Self {
+        let RawOrderStatus {
+            order,
+            status,
+            update_reason,
+            error,
+        } = raw;
+        let order_type = order
+            .order_type
+            .unwrap_or_else(|| order.derived_order_type());
+        let (trigger_price, trigger_signal) = order
+            .price_trigger_options
+            .map(|t| (t.trigger_price, t.trigger_signal))
+            .unwrap_or_default();
+        let filled = order.filled;
+        Self {
+            order: FuturesOrder {
+                order_id: order.order_id,
+                cli_ord_id: order.cli_ord_id,
+                symbol: order.symbol,
+                side: order.side,
+                order_type,
+                status: status.order_status(filled),
+                size: order.quantity,
+                filled_size: filled,
+                unfilled_size: order.quantity - filled,
+                limit_price: order.limit_price,
+                stop_price: trigger_price,
+                reduce_only: order.reduce_only,
+                received_time: order.timestamp,
+                last_update_time: order.last_update_timestamp,
+            },
+            lifecycle: status,
+            trigger_price,
+            trigger_signal,
+            update_reason,
+            error,
+        }
+    }
+}
+
+/// Response for leverage preferences endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LeveragePreferencesResponse {
+    /// Result status
+    pub result: String,
+    /// Leverage preferences by symbol (symbols without one use cross margin)
+    #[serde(rename = "leveragePreferences", default)]
+    pub leverage_preferences: Vec<LeveragePreference>,
+    /// Server time
+    #[serde(rename = "serverTime")]
+    pub server_time: Option<String>,
+}
+
+/// Response for PnL preferences endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PnlPreferencesResponse {
+    /// Result status
+    pub result: String,
+    /// PnL currency preferences by symbol
+    #[serde(default)]
+    pub preferences: Vec<PnlPreference>,
+    /// Server time
+    #[serde(rename = "serverTime")]
+    pub server_time: Option<String>,
+}
+
+/// Response for setting an account preference.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetPreferenceResponse {
+    /// Result status
+    pub result: String,
+    /// Server time
+    #[serde(rename = "serverTime")]
+    pub server_time: Option<String>,
+}
+
+
+// Transfer Types
+
+
+/// Request to move funds between two futures wallets.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferRequest {
+    /// Source wallet
+    #[serde(rename = "fromAccount")]
+    pub from_account: FuturesWallet,
+    /// Destination wallet
+    #[serde(rename = "toAccount")]
+    pub to_account: FuturesWallet,
+    /// Currency unit (e.g., "xbt", "usd")
+    pub unit: String,
+    /// Amount to transfer
+    pub amount: Decimal,
+}
+
+impl TransferRequest {
+    /// Create a new transfer request.
+    pub fn new(
+        from_account: FuturesWallet,
+        to_account: FuturesWallet,
+        unit: impl Into<String>,
+        amount: Decimal,
+    ) -> Self {
+        Self {
+            from_account,
+            to_account,
+            unit: unit.into(),
+            amount,
+        }
+    }
+}
+
+/// Response for a transfer between futures wallets.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransferResponse {
+    /// Result status
+    pub result: String,
+    /// Server time
+    #[serde(rename = "serverTime")]
+    pub server_time: Option<String>,
+}
+
+/// Request to withdraw funds from a futures wallet to the spot account.
+#[derive(Debug, Clone, Serialize)]
+pub struct WithdrawalRequest {
+    /// Currency to withdraw (e.g., "xbt", "usd")
+    pub currency: String,
+    /// Amount to withdraw
+    pub amount: Decimal,
+    /// Futures wallet to withdraw from (defaults to the cash account)
+    #[serde(rename = "sourceWallet", skip_serializing_if = "Option::is_none")]
+    pub source_wallet: Option<FuturesWallet>,
+}
+
+impl WithdrawalRequest {
+    /// Create a withdrawal request from the cash account.
+    pub fn new(currency: impl Into<String>, amount: Decimal) -> Self {
+        Self {
+            currency: currency.into(),
+            amount,
+            source_wallet: None,
+        }
+    }
+
+    /// Withdraw from a specific futures wallet.
+    pub fn source_wallet(mut self, wallet: FuturesWallet) -> Self {
+        self.source_wallet = Some(wallet);
+        self
+    }
+}
+
+/// Response for a withdrawal to the spot account.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WithdrawalResponse {
+    /// Result status
+    pub result: String,
+    /// Withdrawal UID
+    #[serde(default)]
+    pub uid: Option<String>,
+    /// Server time
+    #[serde(rename = "serverTime")]
+    pub server_time: Option<String>,
+}
+
 
 // Batch Order Types
 
@@ -454,16 +861,15 @@ impl BatchOrderRequest {
 
     /// Add a place order element.
     pub fn place(mut self, order: SendOrderRequest) -> Self {
-        self.batch_order.push(BatchElement::Place(PlaceBatchElement {
-            order_type: order.order_type,
-            symbol: order.symbol,
-            side: order.side,
-            size: order.size,
-            limit_price: order.limit_price,
-            stop_price: order.stop_price,
-            reduce_only: order.reduce_only,
-            cli_ord_id: order.cli_ord_id,
-        }));
+        self.batch_order
+            .push(BatchElement::Place(PlaceBatchElement::new(order, None)));
+        self
+    }
+
+    /// Add an edit order element.
+    pub fn edit(mut self, request: EditOrderRequest) -> Self {
+        self.batch_order
+            .push(BatchElement::Edit(EditBatchElement::from(request)));
         self
     }
 
@@ -497,7 +903,10 @@ impl Default for BatchOrderRequest {
 #[serde(tag = "order", rename_all = "lowercase")]
 pub enum BatchElement {
     /// Place a new order
+    #[serde(rename = "send")]
     Place(PlaceBatchElement),
+    /// Edit an existing order
+    Edit(EditBatchElement),
     /// Cancel an existing order
     Cancel(CancelBatchElement),
 }
@@ -505,6 +914,9 @@ pub enum BatchElement {
 /// Element for placing an order in a batch.
 #[derive(Debug, Clone, Serialize)]
 pub struct PlaceBatchElement {
+    /// Tag echoed back in the element's status, to match results to requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_tag: Option<String>,
     #[serde(rename = "orderType")]
     pub order_type: FuturesOrderType,
     pub symbol: String,
@@ -516,6 +928,18 @@ pub struct PlaceBatchElement {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "stopPrice")]
     pub stop_price: Option<Decimal>,
+    /// Trigger signal for stop and take-profit orders
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "triggerSignal")]
+    pub trigger_signal: Option<TriggerSignal>,
+    /// Maximum deviation from the best price (trailing-stop orders)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "trailingStopMaxDeviation")]
+    pub trailing_stop_max_deviation: Option<Decimal>,
+    /// Unit of the trailing-stop deviation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "trailingStopDeviationUnit")]
+    pub trailing_stop_deviation_unit: Option<TrailingStopDeviationUnit>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "reduceOnly")]
     pub reduce_only: Option<bool>,
@@ -524,6 +948,62 @@ pub struct PlaceBatchElement {
     pub cli_ord_id: Option<String>,
 }
 
+impl PlaceBatchElement {
+    /// Create a place element from an order request.
+    pub fn new(order: SendOrderRequest, order_tag: Option<String>) -> Self {
+        Self {
+            order_tag,
+            order_type: order.order_type,
+            symbol: order.symbol,
+            side: order.side,
+            size: order.size,
+            limit_price: order.limit_price,
+            stop_price: order.stop_price,
+            trigger_signal: order.trigger_signal,
+            trailing_stop_max_deviation: order.trailing_stop_max_deviation,
+            trailing_stop_deviation_unit: order.trailing_stop_deviation_unit,
+            reduce_only: order.reduce_only,
+            cli_ord_id: order.cli_ord_id,
+        }
+    }
+}
+
+/// Element for editing an order in a batch.
+#[derive(Debug, Clone, Serialize)]
+pub struct EditBatchElement {
+    /// Order ID to edit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "order_id")]
+    pub order_id: Option<String>,
+    /// Client order ID to edit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "cliOrdId")]
+    pub cli_ord_id: Option<String>,
+    /// New size
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<Decimal>,
+    /// New limit price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "limitPrice")]
+    pub limit_price: Option<Decimal>,
+    /// New stop price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "stopPrice")]
+    pub stop_price: Option<Decimal>,
+}
+
+impl From<EditOrderRequest> for EditBatchElement {
+    fn from(request: EditOrderRequest) -> Self {
+        Self {
+            order_id: request.order_id,
+            cli_ord_id: request.cli_ord_id,
+            size: request.size,
+            limit_price: request.limit_price,
+            stop_price: request.stop_price,
+        }
+    }
+}
+
 /// Element for cancelling an order in a batch.
 #[derive(Debug, Clone, Serialize)]
 pub struct CancelBatchElement {
@@ -551,9 +1031,15 @@ pub struct BatchOrderResponse {
 /// Status of a single element in a batch.
 #[derive(Debug, Clone, Deserialize)]
 pub struct BatchElementStatus {
-    /// Order ID (for place operations)
+    /// Order ID
     #[serde(rename = "order_id")]
     pub order_id: Option<String>,
+    /// Client order ID
+    #[serde(default, rename = "cliOrdId")]
+    pub cli_ord_id: Option<String>,
+    /// Tag of the place element this status belongs to
+    #[serde(default)]
+    pub order_tag: Option<String>,
     /// Status message
     pub status: String,
     /// Error message (if failed)
@@ -586,6 +1072,101 @@ mod tests {
         assert!(json.contains("PI_ETHUSD"));
     }
 
+    #[test]
+    fn test_send_order_request_trigger_orders() {
+        let request = SendOrderRequest::take_profit("PF_XBTUSD", BuySell::Sell, Decimal::from(1), Decimal::from(60000))
+            .trigger_signal(TriggerSignal::Mark);
+        let form = serde_urlencoded::to_string(&request).unwrap();
+        assert!(form.contains("orderType=take_profit"));
+        assert!(form.contains("stopPrice=60000"));
+        assert!(form.contains("triggerSignal=mark"));
+
+        let request = SendOrderRequest::trailing_stop(
+            "PF_XBTUSD",
+            BuySell::Sell,
+            Decimal::from(1),
+            Decimal::from(5),
+            TrailingStopDeviationUnit::Percent,
+        );
+        let form = serde_urlencoded::to_string(&request).unwrap();
+        assert!(form.contains("orderType=trailing_stop"));
+        assert!(form.contains("trailingStopMaxDeviation=5"));
+        assert!(form.contains("trailingStopDeviationUnit=PERCENT"));
+    }
+
+    #[test]
+    fn test_order_status_request_and_response() {
+        let request = OrderStatusRequest::by_order_id("a").order_id("b").cli_ord_id("c");
+        assert_eq!(
+            serde_urlencoded::to_string(&request).unwrap(),
+            "orderIds=a&orderIds=b&cliOrdIds=c"
+        );
+
+        let json = r#"{
+            "result": "success",
+            "orders": [{
+                "order": {
+                    "type": "TRIGGER_ORDER",
+                    "orderId": "abc",
+                    "cliOrdId": null,
+                    "symbol": "PF_XBTUSD",
+                    "side": "sell",
+                    "quantity": 2,
+                    "filled": 0,
+                    "limitPrice": null,
+                    "reduceOnly": true,
+                    "timestamp": "2024-01-15T10:00:00Z",
+                    "lastUpdateTimestamp": "2024-01-15T10:00:00Z",
+                    "priceTriggerOptions": { "triggerPrice": 40000, "triggerSide": "TRIGGER_BELOW", "triggerSignal": "MARK_PRICE" }
+                },
+                "status": "TRIGGER_PLACED",
+                "updateReason": null,
+                "error": null
+            }]
+        }"#;
+        let response: OrderStatusResponse = serde_json::from_str(json).unwrap();
+        let info = &response.orders[0];
+        assert_eq!(info.lifecycle, OrderLifecycle::TriggerPlaced);
+        assert!(!info.lifecycle.is_final());
+        assert_eq!(info.order.order_type, FuturesOrderType::Stop);
+        assert_eq!(info.order.status, FuturesOrderStatus::Open);
+        assert_eq!(info.order.unfilled_size, Decimal::from(2));
+        assert_eq!(info.trigger_signal, Some(TriggerSignal::Mark));
+        assert_eq!(info.order.stop_price, Some(Decimal::from(40000)));
+    }
+
+    #[test]
+    fn test_order_status_trigger_types() {
+        let status = |side: &str, trigger_side: &str, trailing: &str| -> FuturesOrderType {
+            let json = format!(
+                r#"{{
+                    "order": {{
+                        "type": "TRIGGER_ORDER",
+                        "orderId": "abc",
+                        "symbol": "PF_XBTUSD",
+                        "side": "{side}",
+                        "quantity": 1,
+                        "priceTriggerOptions": {{ "triggerPrice": 40000, "triggerSide": "{trigger_side}", "triggerSignal": "LAST_PRICE" }},
+                        "trailingStopOptions": {trailing}
+                    }},
+                    "status": "TRIGGER_PLACED"
+                }}"#
+            );
+            serde_json::from_str::<OrderStatusInfo>(&json)
+                .unwrap()
+                .order
+                .order_type
+        };
+
+        assert_eq!(status("sell", "TRIGGER_ABOVE", "null"), FuturesOrderType::TakeProfit);
+        assert_eq!(status("buy", "TRIGGER_BELOW", "null"), FuturesOrderType::TakeProfit);
+        assert_eq!(status("buy", "TRIGGER_ABOVE", "null"), FuturesOrderType::Stop);
+        assert_eq!(
+            status("sell", "TRIGGER_BELOW", r#"{ "maxDeviation": 5, "unit": "PERCENT" }"#),
+            FuturesOrderType::TrailingStop
+        );
+    }
+
     #[test]
     fn test_edit_order_request() {
         let request = EditOrderRequest::by_order_id("abc123")
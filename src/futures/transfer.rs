@@ -0,0 +1,414 @@
+//! Moving collateral between the spot and futures accounts.
+//!
+//! Each direction is initiated on one venue: spot-to-futures with the spot
+//! `WalletTransfer` endpoint and futures-to-spot with the futures withdrawal
+//! endpoint. [`CollateralMover`] starts the transfer and then polls the spot
+//! ledger and the futures account log until both sides have booked it.
+
+use std::pin::pin;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures_util::TryStreamExt;
+use rust_decimal::Decimal;
+
+use crate::error::KrakenError;
+use crate::futures::rest::{AccountLogRequest, FuturesClient, WithdrawalRequest};
+use crate::futures::ws::AccountLogEntry;
+use crate::spot::rest::KrakenClient;
+use crate::spot::rest::private::{LedgerEntry, LedgersRequest, WalletTransferRequest};
+use crate::types::common::{asset_ws_name, LedgerType};
+
+/// Spot wallet name used by `WalletTransfer`.
+const SPOT_WALLET: &str = "Spot Wallet";
+
+/// Futures wallet name used by `WalletTransfer`.
+const FUTURES_WALLET: &str = "Futures Wallet";
+
+/// Number of account log entries fetched per page.
+const LOG_PAGE_SIZE: u32 = 50;
+
+/// Direction of a collateral transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferDirection {
+    /// From the spot account into the futures account
+    SpotToFutures,
+    /// From the futures account back to the spot account
+    FuturesToSpot,
+}
+
+/// A collateral transfer and its completion state on both venues.
+#[derive(Debug, Clone)]
+pub struct CollateralTransfer {
+    /// Transfer direction
+    pub direction: TransferDirection,
+    /// Asset being moved
+    pub asset: String,
+    /// Amount being moved
+    pub amount: Decimal,
+    /// Spot reference ID (spot-to-futures only)
+    pub spot_refid: Option<String>,
+    /// Futures withdrawal UID (futures-to-spot only)
+    pub futures_uid: Option<String>,
+    /// Matching spot ledger entry, once booked
+    pub spot_entry: Option<LedgerEntry>,
+    /// Matching futures account log entry, once booked
+    pub futures_entry: Option<AccountLogEntry>,
+    started_at: u64,
+    log_baseline: Option<u64>,
+}
+
+impl CollateralTransfer {
+    /// Whether the spot side has booked the transfer.
+    pub fn spot_settled(&self) -> bool {
+        self.spot_entry.is_some()
+    }
+
+    /// Whether the futures side has booked the transfer.
+    pub fn futures_settled(&self) -> bool {
+        self.futures_entry.is_some()
+    }
+
+    /// Whether both sides have booked the transfer.
+    pub fn is_complete(&self) -> bool {
+        self.spot_settled() && self.futures_settled()
+    }
+
+    /// Signed amount expected on the spot ledger.
+    fn spot_delta(&self) -> Decimal {
+        match self.direction {
+            TransferDirection::SpotToFutures => -self.amount,
+            TransferDirection::FuturesToSpot => self.amount,
+        }
+    }
+
+    /// Whether a spot ledger entry belongs to this transfer.
+    fn matches_spot(&self, entry: &LedgerEntry) -> bool {
+        if let Some(refid) = &self.spot_refid {
+            if entry.refid == *refid {
+                return true;
+            }
+        }
+        entry.ledger_type == LedgerType::Transfer
+            && entry.time >= self.started_at as f64
+            && same_asset(&entry.asset, &self.asset)
+            && entry.amount == self.spot_delta()
+    }
+
+    /// Whether a futures account log entry belongs to this transfer.
+    ///
+    /// Entries are matched by withdrawal UID when the log carries it, and
+    /// otherwise by asset and balance change. Entries without balances are
+    /// never matched, since another transfer of the same asset could be
+    /// mistaken for this one.
+    fn matches_futures(&self, entry: &AccountLogEntry) -> bool {
+        if self
+            .log_baseline
+            .is_some_and(|baseline| entry.id <= baseline)
+        {
+            return false;
+        }
+        if self.futures_uid.is_some() && self.futures_uid == entry.booking_uid {
+            return true;
+        }
+        if !same_asset(&entry.asset, &self.asset)
+            || !entry.info.to_ascii_lowercase().contains("transfer")
+        {
+            return false;
+        }
+        match (entry.old_balance, entry.new_balance) {
+            (Some(old), Some(new)) => (new - old) == -self.spot_delta(),
+            _ => false,
+        }
+    }
+}
+
+/// Moves collateral between the spot and futures accounts and tracks completion.
+///
+/// Works with any [`KrakenClient`] and [`FuturesClient`], so either side can
+/// be mocked or wrapped in a [`RateLimitedClient`](crate::rate_limit::RateLimitedClient).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kraken_api_client::futures::transfer::CollateralMover;
+///
+/// let mover = CollateralMover::new(&spot, &futures);
+/// let transfer = mover.move_to_futures("USD", amount).await?;
+/// assert!(transfer.is_complete());
+/// ```
+#[derive(Clone)]
+pub struct CollateralMover<'a, S, F> {
+    spot: &'a S,
+    futures: &'a F,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl<'a, S: KrakenClient, F: FuturesClient> CollateralMover<'a, S, F> {
+    /// Create a mover with a 5 second poll interval and a 5 minute timeout.
+    pub fn new(spot: &'a S, futures: &'a F) -> Self {
+        Self {
+            spot,
+            futures,
+            poll_interval: Duration::from_secs(5),
+            timeout: Duration::from_secs(300),
+        }
+    }
+
+    /// Set how often both venues are polled while waiting.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Set how long [`wait`](Self::wait) polls before giving up.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Move collateral from spot to futures and wait for both sides to book it.
+    pub async fn move_to_futures(
+        &self,
+        asset: &str,
+        amount: Decimal,
+    ) -> Result<CollateralTransfer, KrakenError> {
+        let mut transfer = self.start_spot_to_futures(asset, amount).await?;
+        self.wait(&mut transfer).await?;
+        Ok(transfer)
+    }
+
+    /// Move collateral from futures to spot and wait for both sides to book it.
+    pub async fn move_to_spot(
+        &self,
+        asset: &str,
+        amount: Decimal,
+    ) -> Result<CollateralTransfer, KrakenError> {
+        let mut transfer = self.start_futures_to_spot(asset, amount).await?;
+        self.wait(&mut transfer).await?;
+        Ok(transfer)
+    }
+
+    /// Initiate a spot-to-futures transfer without waiting for it.
+    pub async fn start_spot_to_futures(
+        &self,
+        asset: &str,
+        amount: Decimal,
+    ) -> Result<CollateralTransfer, KrakenError> {
+        let mut transfer = self
+            .pending(TransferDirection::SpotToFutures, asset, amount)
+            .await?;
+        let request = WalletTransferRequest::new(asset, SPOT_WALLET, FUTURES_WALLET, amount);
+        let confirmation = self.spot.wallet_transfer(&request).await?;
+        transfer.spot_refid = Some(confirmation.ref_id);
+        Ok(transfer)
+    }
+
+    /// Initiate a futures-to-spot withdrawal without waiting for it.
+    pub async fn start_futures_to_spot(
+        &self,
+        asset: &str,
+        amount: Decimal,
+    ) -> Result<CollateralTransfer, KrakenError> {
+        let mut transfer = self
+            .pending(TransferDirection::FuturesToSpot, asset, amount)
+            .await?;
+        let response = self
+            .futures
+            .withdraw_to_spot(&WithdrawalRequest::new(asset, amount))
+            .await?;
+        transfer.futures_uid = response.uid;
+        Ok(transfer)
+    }
+
+    /// Poll both venues until the transfer is complete or the timeout elapses.
+    ///
+    /// On [`KrakenError::Timeout`] the transfer keeps whatever progress was
+    /// observed, so it can be inspected or waited on again.
+    pub async fn wait(&self, transfer: &mut CollateralTransfer) -> Result<(), KrakenError> {
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        loop {
+            if self.poll(transfer).await? {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() + self.poll_interval > deadline {
+                return Err(KrakenError::Timeout);
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Check both venues once, recording any newly booked entries.
+    ///
+    /// The spot ledger is paged from the transfer's start time and the
+    /// futures account log back to the entry seen before it was initiated, so
+    /// unrelated activity on either account cannot push the transfer out of
+    /// view.
+    ///
+    /// Returns `true` once the transfer is complete.
+    pub async fn poll(&self, transfer: &mut CollateralTransfer) -> Result<bool, KrakenError> {
+        if !transfer.spot_settled() {
+            transfer.spot_entry = self.find_spot_entry(transfer).await?;
+        }
+        if !transfer.futures_settled() {
+            let request = AccountLogRequest {
+                from: transfer.log_baseline.map(|id| id + 1),
+                count: Some(LOG_PAGE_SIZE),
+                ..Default::default()
+            };
+            let mut logs = pin!(self.futures.account_log_stream(request));
+            while let Some(entry) = logs.try_next().await? {
+                if transfer.matches_futures(&entry) {
+                    transfer.futures_entry = Some(entry);
+                    break;
+                }
+            }
+        }
+        Ok(transfer.is_complete())
+    }
+
+    /// Page through the transfer entries of the spot ledger since the
+    /// transfer started, looking for this one.
+    async fn find_spot_entry(
+        &self,
+        transfer: &CollateralTransfer,
+    ) -> Result<Option<LedgerEntry>, KrakenError> {
+        let mut request = LedgersRequest {
+            asset: Some(transfer.asset.clone()),
+            ledger_type: Some(LedgerType::Transfer),
+            start: Some(transfer.started_at as i64 - 1),
+            ..Default::default()
+        };
+        let mut seen = 0;
+        loop {
+            let ledgers = self.spot.get_ledgers(Some(&request)).await?;
+            let page = ledgers.ledger.len() as u32;
+            if let Some(entry) = ledgers
+                .ledger
+                .into_values()
+                .find(|entry| transfer.matches_spot(entry))
+            {
+                return Ok(Some(entry));
+            }
+            seen += page;
+            if page == 0 || ledgers.count.is_none_or(|count| seen >= count) {
+                return Ok(None);
+            }
+            request.ofs = Some(seen);
+        }
+    }
+
+    /// Record the starting point on both venues before initiating a transfer.
+    async fn pending(
+        &self,
+        direction: TransferDirection,
+        asset: &str,
+        amount: Decimal,
+    ) -> Result<CollateralTransfer, KrakenError> {
+        let latest = self
+            .futures
+            .get_account_log(&AccountLogRequest {
+                count: Some(1),
+                ..Default::default()
+            })
+            .await?;
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Ok(CollateralTransfer {
+            direction,
+            asset: asset.to_string(),
+            amount,
+            spot_refid: None,
+            futures_uid: None,
+            spot_entry: None,
+            futures_entry: None,
+            started_at,
+            log_baseline: latest.logs.first().map(|entry| entry.id),
+        })
+    }
+}
+
+/// Compare asset codes across venues (e.g., "XXBT", "xbt" and "BTC").
+fn same_asset(a: &str, b: &str) -> bool {
+    asset_ws_name(a) == asset_ws_name(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn transfer(direction: TransferDirection) -> CollateralTransfer {
+        CollateralTransfer {
+            direction,
+            asset: "USD".to_string(),
+            amount: d("100"),
+            spot_refid: None,
+            futures_uid: None,
+            spot_entry: None,
+            futures_entry: None,
+            started_at: 1_700_000_000,
+            log_baseline: Some(41),
+        }
+    }
+
+    fn log_entry(id: u64, info: &str, old: &str, new: &str) -> AccountLogEntry {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "date": "2024-01-01T00:00:00.000Z",
+            "asset": "usd",
+            "info": info,
+            "old_balance": old,
+            "new_balance": new
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_same_asset() {
+        assert!(same_asset("XXBT", "xbt"));
+        assert!(same_asset("BTC", "XBT"));
+        assert!(same_asset("ZUSD", "usd"));
+        assert!(!same_asset("USDT", "USD"));
+        assert!(!same_asset("ZEUS", "EUS"));
+    }
+
+    #[test]
+    fn test_futures_entry_matching() {
+        let incoming = transfer(TransferDirection::SpotToFutures);
+        assert!(incoming.matches_futures(&log_entry(42, "cross-exchange transfer", "5", "105")));
+        assert!(!incoming.matches_futures(&log_entry(41, "cross-exchange transfer", "5", "105")));
+        assert!(!incoming.matches_futures(&log_entry(43, "futures trade", "5", "105")));
+
+        let outgoing = transfer(TransferDirection::FuturesToSpot);
+        assert!(outgoing.matches_futures(&log_entry(42, "cross-exchange transfer", "105", "5")));
+        assert!(!outgoing.matches_futures(&log_entry(42, "cross-exchange transfer", "5", "105")));
+    }
+
+    #[test]
+    fn test_futures_entry_without_balances() {
+        let entry = |booking_uid: &str| -> AccountLogEntry {
+            serde_json::from_value(serde_json::json!({
+                "id": 42,
+                "date": "2024-01-01T00:00:00.000Z",
+                "asset": "usd",
+                "info": "cross-exchange transfer",
+                "booking_uid": booking_uid
+            }))
+            .unwrap()
+        };
+
+        // Another transfer of the same asset is not taken for this one.
+        let mut outgoing = transfer(TransferDirection::FuturesToSpot);
+        assert!(!outgoing.matches_futures(&entry("other-uid")));
+
+        outgoing.futures_uid = Some("withdrawal-uid".to_string());
+        assert!(outgoing.matches_futures(&entry("withdrawal-uid")));
+        assert!(!outgoing.matches_futures(&entry("other-uid")));
+    }
+}
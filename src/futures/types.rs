@@ -5,6 +5,8 @@
 
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
 
 use crate::types::common::BuySell;
 
@@ -43,6 +45,8 @@ pub enum FuturesOrderType {
     Stop,
     /// Take profit order
     TakeProfit,
+    /// Trailing stop order
+    TrailingStop,
     /// Immediate or cancel
     #[serde(alias = "ioc")]
     ImmediateOrCancel,
@@ -67,6 +71,77 @@ pub enum FuturesOrderStatus {
     Cancelled,
 }
 
+/// Lifecycle state reported by the order status endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderLifecycle {
+    /// Order is resting in the book
+    EnteredBook,
+    /// Order is fully filled
+    FullyExecuted,
+    /// Order was rejected
+    Rejected,
+    /// Order was cancelled
+    Cancelled,
+    /// Trigger order is waiting for its trigger price
+    TriggerPlaced,
+    /// Trigger fired but the resulting order could not be placed
+    TriggerActivationFailure,
+    /// State not known to this client
+    #[serde(other)]
+    Unknown,
+}
+
+impl OrderLifecycle {
+    /// Whether the order can no longer change.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            OrderLifecycle::FullyExecuted
+                | OrderLifecycle::Rejected
+                | OrderLifecycle::Cancelled
+                | OrderLifecycle::TriggerActivationFailure
+        )
+    }
+
+    /// Map to the coarser [`FuturesOrderStatus`], given the filled size.
+    pub fn order_status(&self, filled: Decimal) -> FuturesOrderStatus {
+        match self {
+            OrderLifecycle::FullyExecuted => FuturesOrderStatus::Filled,
+            OrderLifecycle::Rejected
+            | OrderLifecycle::Cancelled
+            | OrderLifecycle::TriggerActivationFailure => FuturesOrderStatus::Cancelled,
+            _ if filled > Decimal::ZERO => FuturesOrderStatus::PartiallyFilled,
+            _ => FuturesOrderStatus::Open,
+        }
+    }
+}
+
+/// Price signal that triggers a stop, take-profit or trailing-stop order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerSignal {
+    /// Mark price
+    #[serde(alias = "mark_price", alias = "MARK_PRICE")]
+    Mark,
+    /// Index (spot) price
+    #[serde(alias = "index_price", alias = "spot", alias = "SPOT_PRICE")]
+    Index,
+    /// Last traded price
+    #[serde(alias = "last_price", alias = "LAST_PRICE")]
+    Last,
+}
+
+/// Unit of a trailing stop's maximum deviation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TrailingStopDeviationUnit {
+    /// Deviation in percent of the trigger price
+    Percent,
+    /// Deviation as an absolute amount of the quote currency
+    QuoteCurrency,
+}
+
 /// Fill type classification.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -105,6 +180,51 @@ pub enum AccountType {
     FlexFutures,
 }
 
+/// A futures wallet that funds can be transferred to or from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum FuturesWallet {
+    /// Cash account
+    Cash,
+    /// Flex (multi-collateral) wallet
+    Flex,
+    /// Single-collateral margin account, by name (e.g., "fi_xbtusd")
+    Margin(String),
+}
+
+impl FuturesWallet {
+    /// Wallet name as used by the API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            FuturesWallet::Cash => "cash",
+            FuturesWallet::Flex => "flex",
+            FuturesWallet::Margin(name) => name,
+        }
+    }
+}
+
+impl From<String> for FuturesWallet {
+    fn from(name: String) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "cash" => FuturesWallet::Cash,
+            "flex" => FuturesWallet::Flex,
+            _ => FuturesWallet::Margin(name),
+        }
+    }
+}
+
+impl From<FuturesWallet> for String {
+    fn from(wallet: FuturesWallet) -> Self {
+        wallet.as_str().to_string()
+    }
+}
+
+impl std::fmt::Display for FuturesWallet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 
 // Position and Order Structs
 
@@ -375,6 +495,9 @@ pub struct FuturesInstrument {
     /// Contract size (value per unit)
     #[serde(default, alias = "contractSize")]
     pub contract_size: Option<Decimal>,
+    /// Decimal places allowed in order sizes (negative for multiples of ten)
+    #[serde(default, alias = "contractValueTradePrecision")]
+    pub contract_value_trade_precision: Option<i32>,
     /// Maximum leverage
     #[serde(default)]
     pub leverage: Option<String>,
@@ -399,8 +522,13 @@ pub struct FuturesInstrument {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MarginLevel {
-    /// Number of contracts
+    /// Number of contracts from which this tier applies
+    #[serde(default)]
     pub contracts: Decimal,
+    /// Notional (quote currency units) from which this tier applies, for
+    /// instruments whose tiers are expressed in value rather than contracts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_non_contract_units: Option<Decimal>,
     /// Initial margin percentage
     #[serde(alias = "initialMargin")]
     pub initial_margin: Decimal,
@@ -544,6 +672,387 @@ pub struct FuturesTrade {
     pub timestamp: String,
 }
 
+
+// Funding Rates
+
+
+/// A historical funding rate for a perpetual contract.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRate {
+    /// Start of the funding period
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    /// Absolute funding rate for the period, per contract unit
+    pub funding_rate: Decimal,
+    /// Funding rate relative to the contract price
+    pub relative_funding_rate: Decimal,
+}
+
+impl FundingRate {
+    /// Funding for one period on a position of `size` contracts.
+    ///
+    /// Positive rates mean longs pay shorts, so the result is negative for a
+    /// long position paying funding and positive for a short receiving it.
+    pub fn payment(&self, side: BuySell, size: Decimal) -> Decimal {
+        match side {
+            BuySell::Buy => -self.funding_rate * size,
+            BuySell::Sell => self.funding_rate * size,
+        }
+    }
+}
+
+/// Funding rate history for one symbol, ordered by time.
+///
+/// Returned by
+/// [`get_historical_funding_rates`](crate::futures::rest::FuturesRestClient::get_historical_funding_rates).
+/// Use [`position_funding`](Self::position_funding) or
+/// [`funding_pnl`](Self::funding_pnl) to attribute funding to positions.
+#[derive(Debug, Clone, Default)]
+pub struct FundingRateSeries {
+    symbol: String,
+    rates: Vec<FundingRate>,
+}
+
+impl FundingRateSeries {
+    /// Create a series, sorting the rates by timestamp.
+    pub fn new(symbol: impl Into<String>, mut rates: Vec<FundingRate>) -> Self {
+        rates.sort_by_key(|r| r.timestamp);
+        Self {
+            symbol: symbol.into(),
+            rates,
+        }
+    }
+
+    /// Symbol of the series.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// All rates, oldest first.
+    pub fn rates(&self) -> &[FundingRate] {
+        &self.rates
+    }
+
+    /// Number of funding periods in the series.
+    pub fn len(&self) -> usize {
+        self.rates.len()
+    }
+
+    /// Whether the series is empty.
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+
+    /// Most recent rate.
+    pub fn latest(&self) -> Option<&FundingRate> {
+        self.rates.last()
+    }
+
+    /// Rate in effect at `time` (the last period starting at or before it).
+    pub fn rate_at(&self, time: OffsetDateTime) -> Option<&FundingRate> {
+        let idx = self.rates.partition_point(|r| r.timestamp <= time);
+        idx.checked_sub(1).map(|i| &self.rates[i])
+    }
+
+    /// Rates for periods starting in `[from, to)`.
+    pub fn between(&self, from: OffsetDateTime, to: OffsetDateTime) -> &[FundingRate] {
+        let start = self.rates.partition_point(|r| r.timestamp < from);
+        let end = self.rates.partition_point(|r| r.timestamp < to).max(start);
+        &self.rates[start..end]
+    }
+
+    /// Total funding for a constant position held over `[from, to)`.
+    ///
+    /// See [`FundingRate::payment`] for the sign convention.
+    pub fn funding_pnl(
+        &self,
+        side: BuySell,
+        size: Decimal,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+    ) -> Decimal {
+        self.between(from, to)
+            .iter()
+            .map(|r| r.payment(side, size))
+            .sum()
+    }
+
+    /// Funding attributed to an open position from its fill time up to `to`.
+    ///
+    /// Assumes the current size was held the whole time, which is exact only
+    /// if the position was not resized. Returns `None` if the position is for
+    /// another symbol or has no parseable fill time.
+    pub fn position_funding(
+        &self,
+        position: &FuturesPosition,
+        to: OffsetDateTime,
+    ) -> Option<Decimal> {
+        if position.symbol != self.symbol {
+            return None;
+        }
+        let opened = OffsetDateTime::parse(position.fill_time.as_deref()?, &Rfc3339).ok()?;
+        Some(self.funding_pnl(position.side, position.size, opened, to))
+    }
+}
+
+
+// Account Preferences
+
+
+/// Margin mode preference for a symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarginMode {
+    /// Cross margin: the position draws on the account's shared collateral
+    Cross,
+    /// Isolated margin, capped at a maximum leverage
+    Isolated {
+        /// Maximum leverage for the position
+        max_leverage: Decimal,
+    },
+}
+
+/// Leverage preference for a symbol.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeveragePreference {
+    /// Futures symbol
+    pub symbol: String,
+    /// Maximum leverage (set only for isolated margin)
+    #[serde(default)]
+    pub max_leverage: Option<Decimal>,
+}
+
+impl LeveragePreference {
+    /// Margin mode implied by the preference.
+    pub fn margin_mode(&self) -> MarginMode {
+        match self.max_leverage {
+            Some(max_leverage) => MarginMode::Isolated { max_leverage },
+            None => MarginMode::Cross,
+        }
+    }
+}
+
+/// Currency in which a symbol's PnL is realized.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum PnlCurrency {
+    /// US dollar
+    Usd,
+    /// Tether
+    Usdt,
+    /// USD Coin
+    Usdc,
+    /// Euro
+    Eur,
+    /// Pound sterling
+    Gbp,
+    /// Bitcoin
+    Btc,
+    /// Any other currency code
+    Other(String),
+}
+
+impl PnlCurrency {
+    /// Currency code as used by the API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            PnlCurrency::Usd => "USD",
+            PnlCurrency::Usdt => "USDT",
+            PnlCurrency::Usdc => "USDC",
+            PnlCurrency::Eur => "EUR",
+            PnlCurrency::Gbp => "GBP",
+            PnlCurrency::Btc => "BTC",
+            PnlCurrency::Other(code) => code,
+        }
+    }
+}
+
+impl From<String> for PnlCurrency {
+    /// Codes without a variant, including aliases such as "XBT", are kept
+    /// verbatim in [`PnlCurrency::Other`] so they round-trip unchanged.
+    fn from(code: String) -> Self {
+        match code.as_str() {
+            "USD" => PnlCurrency::Usd,
+            "USDT" => PnlCurrency::Usdt,
+            "USDC" => PnlCurrency::Usdc,
+            "EUR" => PnlCurrency::Eur,
+            "GBP" => PnlCurrency::Gbp,
+            "BTC" => PnlCurrency::Btc,
+            _ => PnlCurrency::Other(code),
+        }
+    }
+}
+
+impl From<PnlCurrency> for String {
+    fn from(currency: PnlCurrency) -> Self {
+        currency.as_str().to_string()
+    }
+}
+
+impl std::fmt::Display for PnlCurrency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// PnL currency preference for a symbol.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PnlPreference {
+    /// Futures symbol
+    pub symbol: String,
+    /// PnL currency
+    pub pnl_currency: PnlCurrency,
+}
+
+
+// Charts
+
+
+/// Price series a chart is built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TickType {
+    /// Traded prices
+    Trade,
+    /// Mark prices
+    Mark,
+    /// Spot index prices
+    Spot,
+}
+
+impl TickType {
+    /// Path segment used by the charts API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TickType::Trade => "trade",
+            TickType::Mark => "mark",
+            TickType::Spot => "spot",
+        }
+    }
+}
+
+impl std::fmt::Display for TickType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Candle resolution for the charts API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChartResolution {
+    /// 1 minute
+    #[serde(rename = "1m")]
+    Min1,
+    /// 5 minutes
+    #[serde(rename = "5m")]
+    Min5,
+    /// 15 minutes
+    #[serde(rename = "15m")]
+    Min15,
+    /// 30 minutes
+    #[serde(rename = "30m")]
+    Min30,
+    /// 1 hour
+    #[serde(rename = "1h")]
+    Hour1,
+    /// 4 hours
+    #[serde(rename = "4h")]
+    Hour4,
+    /// 12 hours
+    #[serde(rename = "12h")]
+    Hour12,
+    /// 1 day
+    #[serde(rename = "1d")]
+    Day1,
+    /// 1 week
+    #[serde(rename = "1w")]
+    Week1,
+}
+
+impl ChartResolution {
+    /// Path segment used by the charts API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChartResolution::Min1 => "1m",
+            ChartResolution::Min5 => "5m",
+            ChartResolution::Min15 => "15m",
+            ChartResolution::Min30 => "30m",
+            ChartResolution::Hour1 => "1h",
+            ChartResolution::Hour4 => "4h",
+            ChartResolution::Hour12 => "12h",
+            ChartResolution::Day1 => "1d",
+            ChartResolution::Week1 => "1w",
+        }
+    }
+
+    /// Length of one candle in seconds.
+    pub fn seconds(&self) -> i64 {
+        match self {
+            ChartResolution::Min1 => 60,
+            ChartResolution::Min5 => 300,
+            ChartResolution::Min15 => 900,
+            ChartResolution::Min30 => 1_800,
+            ChartResolution::Hour1 => 3_600,
+            ChartResolution::Hour4 => 14_400,
+            ChartResolution::Hour12 => 43_200,
+            ChartResolution::Day1 => 86_400,
+            ChartResolution::Week1 => 604_800,
+        }
+    }
+}
+
+impl std::fmt::Display for ChartResolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A futures OHLC candle.
+///
+/// Mirrors the spot [`OhlcEntry`](crate::spot::rest::public::OhlcEntry):
+/// `time` is in Unix seconds (the charts API reports milliseconds), and the
+/// remaining fields carry the same meaning. The charts API does not report
+/// VWAP or trade count.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FuturesCandle {
+    /// Unix timestamp (seconds) of the candle open.
+    #[serde(with = "millis_as_secs")]
+    pub time: i64,
+    /// Open price.
+    pub open: Decimal,
+    /// High price.
+    pub high: Decimal,
+    /// Low price.
+    pub low: Decimal,
+    /// Close price.
+    pub close: Decimal,
+    /// Volume.
+    #[serde(default)]
+    pub volume: Decimal,
+}
+
+/// Seconds held as the milliseconds Kraken sends on the wire.
+mod millis_as_secs {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(secs: &i64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_i64(secs.saturating_mul(1000))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<i64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let millis = i64::deserialize(deserializer)?;
+        Ok(millis.div_euclid(1000))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -618,6 +1127,85 @@ mod tests {
         assert!(ticker.funding_rate.is_some());
     }
 
+    #[test]
+    fn test_funding_rate_series() {
+        let json = r#"[
+            {"timestamp": "2024-01-01T02:00:00.000Z", "fundingRate": "2", "relativeFundingRate": "0.00004"},
+            {"timestamp": "2024-01-01T00:00:00.000Z", "fundingRate": "1", "relativeFundingRate": "0.00002"},
+            {"timestamp": "2024-01-01T01:00:00.000Z", "fundingRate": "-0.5", "relativeFundingRate": "-0.00001"}
+        ]"#;
+        let rates: Vec<FundingRate> = serde_json::from_str(json).unwrap();
+        let series = FundingRateSeries::new("PF_XBTUSD", rates);
+        let at = |s: &str| OffsetDateTime::parse(s, &Rfc3339).unwrap();
+
+        assert_eq!(series.len(), 3);
+        assert_eq!(series.latest().unwrap().funding_rate, Decimal::TWO);
+        assert!(series.rate_at(at("2023-12-31T23:00:00Z")).is_none());
+        assert_eq!(
+            series.rate_at(at("2024-01-01T01:30:00Z")).unwrap().funding_rate,
+            "-0.5".parse().unwrap()
+        );
+        assert_eq!(
+            series
+                .between(at("2024-01-01T00:30:00Z"), at("2024-01-01T02:00:00Z"))
+                .len(),
+            1
+        );
+
+        let position: FuturesPosition = serde_json::from_str(
+            r#"{
+                "symbol": "PF_XBTUSD",
+                "side": "buy",
+                "size": "2",
+                "price": "40000",
+                "fillTime": "2023-12-31T23:30:00.000Z"
+            }"#,
+        )
+        .unwrap();
+        // Longs pay 1 and 2, receive 0.5, per contract.
+        assert_eq!(
+            series.position_funding(&position, at("2024-01-01T03:00:00Z")),
+            Some("-5".parse().unwrap())
+        );
+
+        let mut short = position.clone();
+        short.side = BuySell::Sell;
+        assert_eq!(
+            series.position_funding(&short, at("2024-01-01T01:00:00Z")),
+            Some(Decimal::TWO)
+        );
+
+        short.symbol = "PF_ETHUSD".into();
+        assert!(series.position_funding(&short, at("2024-01-01T03:00:00Z")).is_none());
+    }
+
+    #[test]
+    fn test_deserialize_candle() {
+        let json = r#"{
+            "time": 1700000060000,
+            "open": "37000.5",
+            "high": "37010",
+            "low": "36990",
+            "close": "37005",
+            "volume": 12
+        }"#;
+
+        let candle: FuturesCandle = serde_json::from_str(json).unwrap();
+        assert_eq!(candle.time, 1_700_000_060);
+        assert_eq!(candle.volume, Decimal::from(12));
+
+        let value = serde_json::to_value(&candle).unwrap();
+        assert_eq!(value["time"], 1_700_000_060_000i64);
+        assert_eq!(
+            serde_json::from_value::<FuturesCandle>(value).unwrap(),
+            candle
+        );
+        assert_eq!(
+            serde_json::to_string(&ChartResolution::Hour12).unwrap(),
+            r#""12h""#
+        );
+    }
+
     #[test]
     fn test_contract_type_serde() {
         assert_eq!(
@@ -639,4 +1227,16 @@ mod tests {
             FuturesOrderType::TakeProfit
         );
     }
+
+    #[test]
+    fn test_pnl_currency_keeps_wire_code() {
+        let btc: PnlCurrency = serde_json::from_str(r#""BTC""#).unwrap();
+        assert_eq!(btc, PnlCurrency::Btc);
+
+        for code in [r#""XBT""#, r#""usd""#] {
+            let currency: PnlCurrency = serde_json::from_str(code).unwrap();
+            assert!(matches!(currency, PnlCurrency::Other(_)));
+            assert_eq!(serde_json::to_string(&currency).unwrap(), code);
+        }
+    }
 }
@@ -0,0 +1,364 @@
+//! Local order book state for the futures `book` feed.
+
+use rust_decimal::Decimal;
+
+use crate::futures::ws::messages::{BookMessage, BookSnapshotMessage};
+use crate::futures::ws::stream::FuturesWsEvent;
+use crate::types::book::BookSide;
+use crate::types::{BookView, PriceLevel};
+
+/// Reasons a [`FuturesBookState`] lost sync with the feed.
+///
+/// After any of these the book must be rebuilt from a fresh snapshot, e.g. via
+/// [`FuturesStream::resync_book`](crate::futures::ws::FuturesStream::resync_book).
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BookSyncError {
+    /// One or more updates were skipped.
+    #[error("Sequence gap on {product_id}: expected {expected}, received {received}")]
+    Gap {
+        /// Product ID.
+        product_id: String,
+        /// Next expected sequence number.
+        expected: u64,
+        /// Sequence number received.
+        received: u64,
+    },
+    /// An update arrived with a sequence number already applied.
+    #[error("Out-of-order update on {product_id}: expected {expected}, received {received}")]
+    OutOfOrder {
+        /// Product ID.
+        product_id: String,
+        /// Next expected sequence number.
+        expected: u64,
+        /// Sequence number received.
+        received: u64,
+    },
+    /// A snapshot or update arrived without a sequence number.
+    #[error("Missing sequence number on {product_id}")]
+    MissingSeq {
+        /// Product ID.
+        product_id: String,
+    },
+    /// An update arrived while the book is waiting for a snapshot.
+    #[error("Book for {product_id} is awaiting a snapshot")]
+    NotSynced {
+        /// Product ID.
+        product_id: String,
+    },
+}
+
+/// Order book state for one futures product, built from the `book` feed.
+///
+/// Unlike the REST snapshot [`crate::futures::FuturesOrderBook`], this is
+/// kept up to date from WebSocket deltas and validates their `seq` numbers.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kraken_api_client::futures::ws::{feeds, FuturesBookState};
+/// use kraken_api_client::types::BookView;
+///
+/// let mut book = FuturesBookState::new("PI_XBTUSD");
+/// stream.subscribe_public(feeds::BOOK, vec!["PI_XBTUSD"]).await?;
+///
+/// while let Some(event) = stream.next().await {
+///     match book.apply_event(&event?) {
+///         Ok(true) => println!("mid: {:?}", book.mid_price()),
+///         Ok(false) => {}
+///         Err(e) => {
+///             tracing::warn!("{}", e);
+///             stream.resync_book(book.product_id()).await?;
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FuturesBookState {
+    product_id: String,
+    bids: BookSide,
+    asks: BookSide,
+    seq: Option<u64>,
+    timestamp: Option<u64>,
+    synced: bool,
+}
+
+impl FuturesBookState {
+    /// Create an empty book for a product.
+    pub fn new(product_id: impl Into<String>) -> Self {
+        Self {
+            product_id: product_id.into(),
+            bids: BookSide::bids(),
+            asks: BookSide::asks(),
+            seq: None,
+            timestamp: None,
+            synced: false,
+        }
+    }
+
+    /// Product ID of this book.
+    pub fn product_id(&self) -> &str {
+        &self.product_id
+    }
+
+    /// Sequence number of the last applied message.
+    pub fn seq(&self) -> Option<u64> {
+        self.seq
+    }
+
+    /// Timestamp (milliseconds) of the last applied message.
+    pub fn timestamp(&self) -> Option<u64> {
+        self.timestamp
+    }
+
+    /// Mark the book as out of sync until the next snapshot.
+    pub fn invalidate(&mut self) {
+        self.synced = false;
+    }
+
+    /// Replace the book contents with a snapshot.
+    ///
+    /// Snapshots for other products are ignored.
+    pub fn apply_snapshot(&mut self, msg: &BookSnapshotMessage) -> Result<(), BookSyncError> {
+        if msg.product_id != self.product_id {
+            return Ok(());
+        }
+        let Some(seq) = msg.seq else {
+            self.synced = false;
+            return Err(BookSyncError::MissingSeq {
+                product_id: self.product_id.clone(),
+            });
+        };
+
+        self.bids.clear();
+        self.asks.clear();
+        for level in &msg.bids {
+            self.bids.apply(level.price, level.qty);
+        }
+        for level in &msg.asks {
+            self.asks.apply(level.price, level.qty);
+        }
+        self.seq = Some(seq);
+        self.timestamp = msg.timestamp;
+        self.synced = true;
+        Ok(())
+    }
+
+    /// Apply an incremental update.
+    ///
+    /// Updates for other products are ignored. Any sequence problem marks the
+    /// book as out of sync; it stays that way until the next snapshot.
+    pub fn apply_delta(&mut self, msg: &BookMessage) -> Result<(), BookSyncError> {
+        if msg.product_id != self.product_id {
+            return Ok(());
+        }
+        if let Err(e) = self.check_seq(msg.seq) {
+            self.synced = false;
+            return Err(e);
+        }
+
+        if let (Some(side), Some(price), Some(qty)) = (&msg.side, msg.price, msg.qty) {
+            self.apply_level(side, price, qty);
+        }
+        for level in &msg.bids {
+            self.bids.apply(level.price, level.qty);
+        }
+        for level in &msg.asks {
+            self.asks.apply(level.price, level.qty);
+        }
+        self.seq = msg.seq;
+        if msg.timestamp.is_some() {
+            self.timestamp = msg.timestamp;
+        }
+        Ok(())
+    }
+
+    /// Apply a stream event, if it is a book message for this product.
+    ///
+    /// Returns `Ok(true)` if the book changed.
+    pub fn apply_event(&mut self, event: &FuturesWsEvent) -> Result<bool, BookSyncError> {
+        match event {
+            FuturesWsEvent::BookSnapshot(msg) if msg.product_id == self.product_id => {
+                self.apply_snapshot(msg).map(|()| true)
+            }
+            FuturesWsEvent::Book(msg) if msg.product_id == self.product_id => {
+                self.apply_delta(msg).map(|()| true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn check_seq(&self, received: Option<u64>) -> Result<(), BookSyncError> {
+        let product_id = || self.product_id.clone();
+        let (Some(last), true) = (self.seq, self.synced) else {
+            return Err(BookSyncError::NotSynced {
+                product_id: product_id(),
+            });
+        };
+        let Some(received) = received else {
+            return Err(BookSyncError::MissingSeq {
+                product_id: product_id(),
+            });
+        };
+
+        let expected = last + 1;
+        if received > expected {
+            Err(BookSyncError::Gap {
+                product_id: product_id(),
+                expected,
+                received,
+            })
+        } else if received < expected {
+            Err(BookSyncError::OutOfOrder {
+                product_id: product_id(),
+                expected,
+                received,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn apply_level(&mut self, side: &str, price: Decimal, qty: Decimal) {
+        match side {
+            "buy" => self.bids.apply(price, qty),
+            "sell" => self.asks.apply(price, qty),
+            other => tracing::warn!("Unknown book side: {}", other),
+        }
+    }
+}
+
+impl BookView for FuturesBookState {
+    fn symbol(&self) -> &str {
+        &self.product_id
+    }
+
+    fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    fn bids(&self, depth: usize) -> Vec<PriceLevel> {
+        self.bids.top(depth)
+    }
+
+    fn asks(&self, depth: usize) -> Vec<PriceLevel> {
+        self.asks.top(depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn snapshot(seq: u64) -> BookSnapshotMessage {
+        serde_json::from_value(serde_json::json!({
+            "feed": "book_snapshot",
+            "product_id": "PI_XBTUSD",
+            "timestamp": 1612269825817u64,
+            "seq": seq,
+            "bids": [
+                { "price": "34892.5", "qty": "6385" },
+                { "price": "34892.0", "qty": "10924" }
+            ],
+            "asks": [
+                { "price": "34911.5", "qty": "20598" },
+                { "price": "34912.0", "qty": "2300" }
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn delta(seq: Option<u64>, side: &str, price: &str, qty: &str) -> BookMessage {
+        serde_json::from_value(serde_json::json!({
+            "feed": "book",
+            "product_id": "PI_XBTUSD",
+            "side": side,
+            "seq": seq,
+            "price": price,
+            "qty": qty,
+            "timestamp": 1612269953629u64
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_and_deltas() {
+        let mut book = FuturesBookState::new("PI_XBTUSD");
+        assert!(!book.is_synced());
+        book.apply_snapshot(&snapshot(100)).unwrap();
+        assert!(book.is_synced());
+        assert_eq!(book.best_bid().unwrap().price, d("34892.5"));
+        assert_eq!(book.best_ask().unwrap().price, d("34911.5"));
+        assert_eq!(book.spread(), Some(d("19.0")));
+
+        book.apply_delta(&delta(Some(101), "buy", "34895.0", "100"))
+            .unwrap();
+        assert_eq!(
+            book.best_bid().unwrap(),
+            PriceLevel::new(d("34895.0"), d("100"))
+        );
+
+        book.apply_delta(&delta(Some(102), "sell", "34911.5", "0"))
+            .unwrap();
+        assert_eq!(book.best_ask().unwrap().price, d("34912.0"));
+        assert_eq!(book.asks(10).len(), 1);
+        assert_eq!(book.seq(), Some(102));
+    }
+
+    #[test]
+    fn test_gap_detection_and_recovery() {
+        let mut book = FuturesBookState::new("PI_XBTUSD");
+        book.apply_snapshot(&snapshot(100)).unwrap();
+
+        let err = book
+            .apply_delta(&delta(Some(103), "buy", "34895.0", "100"))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            BookSyncError::Gap {
+                expected: 101,
+                received: 103,
+                ..
+            }
+        ));
+        assert!(!book.is_synced());
+
+        // Further deltas are rejected until a fresh snapshot arrives.
+        assert!(matches!(
+            book.apply_delta(&delta(Some(104), "buy", "34895.0", "100")),
+            Err(BookSyncError::NotSynced { .. })
+        ));
+
+        book.apply_snapshot(&snapshot(200)).unwrap();
+        assert!(book.is_synced());
+        book.apply_delta(&delta(Some(201), "buy", "34895.0", "100"))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_out_of_order_and_missing_seq() {
+        let mut book = FuturesBookState::new("PI_XBTUSD");
+        book.apply_snapshot(&snapshot(100)).unwrap();
+        assert!(matches!(
+            book.apply_delta(&delta(Some(100), "buy", "1", "1")),
+            Err(BookSyncError::OutOfOrder { .. })
+        ));
+
+        book.apply_snapshot(&snapshot(100)).unwrap();
+        assert!(matches!(
+            book.apply_delta(&delta(None, "buy", "1", "1")),
+            Err(BookSyncError::MissingSeq { .. })
+        ));
+    }
+
+    #[test]
+    fn test_other_products_ignored() {
+        let mut book = FuturesBookState::new("PI_ETHUSD");
+        let event = FuturesWsEvent::BookSnapshot(snapshot(1));
+        assert_eq!(book.apply_event(&event), Ok(false));
+        assert!(!book.is_synced());
+    }
+}
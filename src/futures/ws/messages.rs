@@ -196,6 +196,9 @@ pub struct InfoResponse {
 
 
 /// Order book update message.
+///
+/// Updates usually carry a single level in `side`, `price` and `qty`; a
+/// quantity of zero removes the level.
 #[derive(Debug, Clone, Deserialize)]
 pub struct BookMessage {
     /// Feed name.
@@ -208,6 +211,15 @@ pub struct BookMessage {
     /// Timestamp in milliseconds.
     #[serde(default)]
     pub timestamp: Option<u64>,
+    /// Side of the updated level ("buy" or "sell").
+    #[serde(default)]
+    pub side: Option<String>,
+    /// Price of the updated level.
+    #[serde(default)]
+    pub price: Option<Decimal>,
+    /// New quantity of the updated level.
+    #[serde(default)]
+    pub qty: Option<Decimal>,
     /// Bids (price levels).
     #[serde(default)]
     pub bids: Vec<BookLevel>,
@@ -306,6 +318,54 @@ pub struct TickerMessage {
     pub suspended: Option<bool>,
 }
 
+/// Lightweight ticker message (`ticker_lite` feed).
+#[derive(Debug, Clone, Deserialize)]
+pub struct TickerLiteMessage {
+    /// Feed name.
+    pub feed: String,
+    /// Product ID.
+    pub product_id: String,
+    /// Best bid price.
+    #[serde(default)]
+    pub bid: Option<Decimal>,
+    /// Best ask price.
+    #[serde(default)]
+    pub ask: Option<Decimal>,
+    /// Change in last 24h (percentage).
+    #[serde(default)]
+    pub change: Option<Decimal>,
+    /// Premium.
+    #[serde(default)]
+    pub premium: Option<Decimal>,
+    /// 24h volume.
+    #[serde(default)]
+    pub volume: Option<Decimal>,
+    /// Contract tag (e.g. "perpetual", "month").
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Currency pair (e.g. "XBT:USD").
+    #[serde(default)]
+    pub pair: Option<String>,
+    /// Days to maturity.
+    #[serde(default)]
+    pub dtm: Option<i64>,
+    /// Maturity time in milliseconds.
+    #[serde(default, rename = "maturityTime")]
+    pub maturity_time: Option<u64>,
+    /// 24h volume in quote currency.
+    #[serde(default, rename = "volumeQuote")]
+    pub volume_quote: Option<Decimal>,
+}
+
+/// Heartbeat message, sent every minute on the `heartbeat` feed.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeartbeatMessage {
+    /// Feed name.
+    pub feed: String,
+    /// Server time in milliseconds.
+    pub time: u64,
+}
+
 /// Trade message.
 #[derive(Debug, Clone, Deserialize)]
 pub struct TradeMessage {
@@ -586,6 +646,114 @@ pub struct FlexFuturesBalance {
     pub unrealized_pnl: Option<Decimal>,
 }
 
+/// Account log snapshot message (`account_log_snapshot`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountLogSnapshotMessage {
+    /// Feed name.
+    pub feed: String,
+    /// Recent account log entries.
+    pub logs: Vec<AccountLogEntry>,
+}
+
+/// Account log update message (`account_log`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountLogMessage {
+    /// Feed name.
+    pub feed: String,
+    /// The new account log entry.
+    pub new_entry: AccountLogEntry,
+}
+
+/// A single account log entry.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountLogEntry {
+    /// Entry ID.
+    pub id: u64,
+    /// Entry date (ISO 8601).
+    pub date: String,
+    /// Asset the entry relates to.
+    pub asset: String,
+    /// Description of the entry (e.g. "futures trade", "funding rate change").
+    pub info: String,
+    /// Booking UID.
+    #[serde(default)]
+    pub booking_uid: Option<String>,
+    /// Margin account (e.g. "f-xbt:usd", "flex").
+    #[serde(default)]
+    pub margin_account: Option<String>,
+    /// Balance before the entry.
+    #[serde(default)]
+    pub old_balance: Option<Decimal>,
+    /// Balance after the entry.
+    #[serde(default)]
+    pub new_balance: Option<Decimal>,
+    /// Average entry price before the entry.
+    #[serde(default)]
+    pub old_average_entry_price: Option<Decimal>,
+    /// Average entry price after the entry.
+    #[serde(default)]
+    pub new_average_entry_price: Option<Decimal>,
+    /// Trade price.
+    #[serde(default)]
+    pub trade_price: Option<Decimal>,
+    /// Mark price.
+    #[serde(default)]
+    pub mark_price: Option<Decimal>,
+    /// Realized PnL.
+    #[serde(default)]
+    pub realized_pnl: Option<Decimal>,
+    /// Fee.
+    #[serde(default)]
+    pub fee: Option<Decimal>,
+    /// Execution ID (empty for non-trade entries).
+    #[serde(default)]
+    pub execution: Option<String>,
+    /// Collateral currency.
+    #[serde(default)]
+    pub collateral: Option<String>,
+    /// Funding rate.
+    #[serde(default)]
+    pub funding_rate: Option<Decimal>,
+    /// Realized funding.
+    #[serde(default)]
+    pub realized_funding: Option<Decimal>,
+    /// Conversion spread percentage.
+    #[serde(default)]
+    pub conversion_spread_percentage: Option<Decimal>,
+    /// Liquidation fee.
+    #[serde(default)]
+    pub liquidation_fee: Option<Decimal>,
+}
+
+/// Notifications message (`notifications_auth`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotificationsMessage {
+    /// Feed name.
+    pub feed: String,
+    /// Current notifications.
+    pub notifications: Vec<Notification>,
+}
+
+/// A platform notification.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Notification {
+    /// Notification ID.
+    pub id: u64,
+    /// Notification type ("market", "general", "new_feature", "bug_fix",
+    /// "maintenance", "settlement").
+    #[serde(rename = "type")]
+    pub notification_type: String,
+    /// Priority ("low", "medium", "high").
+    pub priority: String,
+    /// Notification text.
+    pub note: String,
+    /// Time the notification takes effect, in milliseconds.
+    #[serde(default)]
+    pub effective_time: Option<u64>,
+    /// Expected downtime in minutes (maintenance notifications).
+    #[serde(default)]
+    pub expected_downtime_minutes: Option<u64>,
+}
 
 // Tests
 
@@ -6,7 +6,8 @@
 //! ## Features
 //!
 //! - Real-time market data (ticker, order book, trades)
-//! - Private feeds (orders, fills, positions, balances)
+//! - Local order book state with sequence-gap detection ([`FuturesBookState`])
+//! - Private feeds (orders, fills, positions, balances, account log, notifications)
 //! - Automatic reconnection with exponential backoff
 //! - Subscription restoration after reconnect
 //! - Challenge-based authentication
@@ -53,10 +54,12 @@
 //! stream.subscribe_private(feeds::FILLS).await?;
 //! ```
 
+mod book;
 mod client;
 mod messages;
 mod stream;
 
+pub use book::{BookSyncError, FuturesBookState};
 pub use client::{FuturesWsClient, WsConfig, WsConfigBuilder};
 pub use messages::*;
 pub use stream::{FuturesStream, FuturesWsEvent};
@@ -80,6 +83,8 @@ pub mod feeds {
     pub const TICKER_LITE: &str = "ticker_lite";
     /// Trade feed - individual trade executions.
     pub const TRADE: &str = "trade";
+    /// Heartbeat feed - a message every minute to confirm the connection is alive.
+    pub const HEARTBEAT: &str = "heartbeat";
 
     // Private feeds
     /// Open orders feed - user's open orders.
@@ -92,4 +97,6 @@ pub mod feeds {
     pub const BALANCES: &str = "balances";
     /// Account log feed - account activity.
     pub const ACCOUNT_LOG: &str = "account_log";
+    /// Notifications feed - platform notifications (maintenance, settlements, ...).
+    pub const NOTIFICATIONS_AUTH: &str = "notifications_auth";
 }
@@ -1,6 +1,7 @@
 //! Futures WebSocket stream implementation.
 
 use std::collections::HashMap;
+use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -18,10 +19,12 @@ use crate::auth::CredentialsProvider;
 use crate::error::KrakenError;
 use crate::futures::ws::client::{WsConfig, sign_challenge};
 use crate::futures::ws::messages::*;
+use crate::pairs::IntoFuturesSymbol;
 
 type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 type WsSink = SplitSink<WsStream, WsMessage>;
 type WsReceiver = SplitStream<WsStream>;
+type ReconnectFuture = Pin<Box<dyn Future<Output = Result<Connection, ReconnectError>> + Send>>;
 
 /// Events from the Futures WebSocket connection.
 #[derive(Debug, Clone)]
@@ -30,6 +33,15 @@ pub enum FuturesWsEvent {
     Info(InfoResponse),
     /// Error from the server.
     Error(ErrorResponse),
+    /// Authentication failed.
+    ///
+    /// Emitted when the challenge handshake fails during a reconnect, or when
+    /// the server rejects the signed challenge of a private subscription.
+    /// Private feeds receive no data until the connection re-authenticates.
+    AuthFailed {
+        /// Reason reported by the server or the handshake.
+        message: String,
+    },
     /// Subscription confirmed.
     Subscribed(SubscribedResponse),
     /// Unsubscription confirmed.
@@ -40,6 +52,10 @@ pub enum FuturesWsEvent {
     BookSnapshot(BookSnapshotMessage),
     /// Ticker data.
     Ticker(TickerMessage),
+    /// Lightweight ticker data.
+    TickerLite(TickerLiteMessage),
+    /// Heartbeat.
+    Heartbeat(HeartbeatMessage),
     /// Trade data.
     Trade(TradeMessage),
     /// Trades snapshot.
@@ -52,6 +68,12 @@ pub enum FuturesWsEvent {
     OpenPositions(OpenPositionsMessage),
     /// Balances (private).
     Balances(BalancesMessage),
+    /// Account log snapshot (private).
+    AccountLogSnapshot(AccountLogSnapshotMessage),
+    /// Account log update (private).
+    AccountLog(AccountLogMessage),
+    /// Notifications (private).
+    Notifications(NotificationsMessage),
     /// Raw/unknown message.
     Raw(serde_json::Value),
     /// Connection disconnected.
@@ -78,6 +100,21 @@ struct AuthState {
     signed_challenge: String,
 }
 
+/// A freshly opened (and, for private streams, authenticated) connection.
+struct Connection {
+    sink: Arc<Mutex<WsSink>>,
+    receiver: WsReceiver,
+    auth_state: Option<AuthState>,
+}
+
+/// Why a reconnect attempt failed.
+enum ReconnectError {
+    /// The WebSocket connection could not be opened.
+    Connect(KrakenError),
+    /// The connection opened but the challenge handshake failed.
+    Auth(KrakenError),
+}
+
 /// A stream of messages from a Kraken Futures WebSocket connection.
 ///
 /// This stream handles:
@@ -94,7 +131,8 @@ pub struct FuturesStream {
     config: WsConfig,
     /// URL to connect to.
     url: String,
-    /// Credentials for private connections.
+    /// Credentials for private connections, asked for the current key on
+    /// every handshake. A rotated key takes effect on the next reconnect.
     credentials: Option<Arc<dyn CredentialsProvider>>,
     /// Authentication state.
     auth_state: Option<AuthState>,
@@ -114,6 +152,10 @@ pub struct FuturesStream {
     authenticated: bool,
     /// Pending authentication (waiting for challenge response).
     pending_auth: bool,
+    /// In-flight reconnect (connect + challenge handshake).
+    reconnect_task: Option<ReconnectFuture>,
+    /// Whether the stream was closed and should not reconnect.
+    closed: bool,
 }
 
 impl std::fmt::Debug for FuturesStream {
@@ -173,6 +215,8 @@ impl FuturesStream {
             reconnecting: false,
             authenticated: false,
             pending_auth: false,
+            reconnect_task: None,
+            closed: false,
         })
     }
 
@@ -180,89 +224,42 @@ impl FuturesStream {
     async fn authenticate(&mut self) -> Result<(), KrakenError> {
         let credentials = self
             .credentials
-            .as_ref()
+            .clone()
             .ok_or(KrakenError::MissingCredentials)?;
+        let sink = self
+            .sink
+            .clone()
+            .ok_or_else(|| KrakenError::WebSocketMsg("Not connected".into()))?;
+        let receiver = self
+            .receiver
+            .as_mut()
+            .ok_or_else(|| KrakenError::WebSocketMsg("Not connected".into()))?;
 
-        // Clone the credentials to avoid borrow issues
-        let creds = credentials.get_credentials().clone();
-
-        // Send challenge request
-        let challenge_req = ChallengeRequest::new(&creds.api_key);
-        self.send_json(&challenge_req).await?;
         self.pending_auth = true;
+        let result = challenge_handshake(&sink, receiver, credentials.as_ref()).await;
+        self.pending_auth = false;
 
-        // Wait for challenge response
-        let challenge = self.wait_for_challenge().await?;
-
-        // Sign the challenge.
-        let signed = sign_challenge(&creds, &challenge)?;
-
-        self.auth_state = Some(AuthState {
-            challenge,
-            signed_challenge: signed,
-        });
-
+        self.auth_state = Some(result?);
         self.authenticated = true;
-        self.pending_auth = false;
 
         Ok(())
     }
 
-    /// Wait for challenge response from the server.
-    async fn wait_for_challenge(&mut self) -> Result<String, KrakenError> {
-        let timeout = Duration::from_secs(10);
-        let start = Instant::now();
-
-        while start.elapsed() < timeout {
-            if let Some(receiver) = &mut self.receiver {
-                match tokio::time::timeout(Duration::from_millis(100), receiver.next()).await {
-                    Ok(Some(Ok(WsMessage::Text(text)))) => {
-                        let value: serde_json::Value =
-                            serde_json::from_str(&text).map_err(KrakenError::Json)?;
-
-                        if let Some(event) = value.get("event").and_then(|e| e.as_str()) {
-                            if event == "challenge" {
-                                if let Some(message) = value.get("message").and_then(|m| m.as_str())
-                                {
-                                    return Ok(message.to_string());
-                                }
-                            } else if event == "error" {
-                                let msg = value
-                                    .get("message")
-                                    .and_then(|m| m.as_str())
-                                    .unwrap_or("Unknown error");
-                                return Err(KrakenError::WebSocketMsg(format!(
-                                    "Authentication error: {}",
-                                    msg
-                                )));
-                            }
-                        }
-                    }
-                    Ok(Some(Err(e))) => {
-                        return Err(KrakenError::WebSocket(e));
-                    }
-                    _ => continue,
-                }
-            }
-        }
-
-        Err(KrakenError::WebSocketMsg(
-            "Timeout waiting for challenge response".into(),
-        ))
-    }
-
     /// Subscribe to a public feed.
     ///
     /// # Arguments
     ///
     /// * `feed` - Feed name (e.g., "ticker", "book", "trade")
     /// * `product_ids` - Product IDs to subscribe to (e.g., ["PI_XBTUSD"])
-    pub async fn subscribe_public(
+    pub async fn subscribe_public<S: IntoFuturesSymbol>(
         &mut self,
         feed: &str,
-        product_ids: Vec<&str>,
+        product_ids: impl IntoIterator<Item = S>,
     ) -> Result<(), KrakenError> {
-        let product_ids: Vec<String> = product_ids.into_iter().map(|s| s.to_string()).collect();
+        let product_ids: Vec<String> = product_ids
+            .into_iter()
+            .map(S::into_futures_symbol)
+            .collect();
         let key = subscription_key(feed, &product_ids);
 
         // Store subscription
@@ -315,17 +312,20 @@ impl FuturesStream {
     }
 
     /// Subscribe to a private feed for specific products.
-    pub async fn subscribe_private_with_products(
+    pub async fn subscribe_private_with_products<S: IntoFuturesSymbol>(
         &mut self,
         feed: &str,
-        product_ids: Vec<&str>,
+        product_ids: impl IntoIterator<Item = S>,
     ) -> Result<(), KrakenError> {
         let auth = self
             .auth_state
             .as_ref()
             .ok_or_else(|| KrakenError::WebSocketMsg("Not authenticated".into()))?;
 
-        let product_ids: Vec<String> = product_ids.into_iter().map(|s| s.to_string()).collect();
+        let product_ids: Vec<String> = product_ids
+            .into_iter()
+            .map(S::into_futures_symbol)
+            .collect();
         let key = subscription_key(feed, &product_ids);
 
         // Store subscription
@@ -349,12 +349,15 @@ impl FuturesStream {
     }
 
     /// Unsubscribe from a feed.
-    pub async fn unsubscribe(
+    pub async fn unsubscribe<S: IntoFuturesSymbol>(
         &mut self,
         feed: &str,
-        product_ids: Vec<&str>,
+        product_ids: impl IntoIterator<Item = S>,
     ) -> Result<(), KrakenError> {
-        let product_ids: Vec<String> = product_ids.into_iter().map(|s| s.to_string()).collect();
+        let product_ids: Vec<String> = product_ids
+            .into_iter()
+            .map(S::into_futures_symbol)
+            .collect();
         let key = subscription_key(feed, &product_ids);
         self.subscriptions.remove(&key);
 
@@ -362,6 +365,19 @@ impl FuturesStream {
         self.send_json(&request).await
     }
 
+    /// Request a fresh snapshot of a product's order book.
+    ///
+    /// Unsubscribes and resubscribes the `book` feed for `product_id`; the
+    /// server answers the new subscription with a `book_snapshot`. Use this
+    /// after a [`FuturesBookState`](super::FuturesBookState) reports a
+    /// [`BookSyncError`](super::BookSyncError).
+    pub async fn resync_book(&mut self, product_id: &str) -> Result<(), KrakenError> {
+        self.unsubscribe(super::feeds::BOOK, vec![product_id])
+            .await?;
+        self.subscribe_public(super::feeds::BOOK, vec![product_id])
+            .await
+    }
+
     /// Send a JSON message.
     async fn send_json<T: serde::Serialize>(&self, msg: &T) -> Result<(), KrakenError> {
         let sink = self
@@ -369,14 +385,7 @@ impl FuturesStream {
             .as_ref()
             .ok_or_else(|| KrakenError::WebSocketMsg("Not connected".into()))?;
 
-        let json = serde_json::to_string(msg).map_err(|e| {
-            KrakenError::WebSocketMsg(format!("Failed to serialize message: {}", e))
-        })?;
-
-        let mut sink = sink.lock().await;
-        sink.send(WsMessage::Text(json.into()))
-            .await
-            .map_err(|e| KrakenError::WebSocketMsg(format!("Failed to send message: {}", e)))
+        send_json(sink, msg).await
     }
 
     /// Check if we should reconnect.
@@ -388,7 +397,6 @@ impl FuturesStream {
     }
 
     /// Calculate backoff duration for reconnection.
-    #[allow(dead_code)]
     fn backoff_duration(&self) -> Duration {
         let base = self.config.initial_backoff.as_millis() as u64;
         let max = self.config.max_backoff.as_millis() as u64;
@@ -397,84 +405,98 @@ impl FuturesStream {
         Duration::from_millis(backoff_ms)
     }
 
-    /// Attempt to reconnect.
-    #[allow(dead_code)]
-    async fn reconnect(&mut self) -> Result<(), KrakenError> {
+    /// Drop the current connection and start reconnecting in the background.
+    ///
+    /// The new connection is authenticated with a fresh challenge before any
+    /// subscription is restored; see [`Self::finish_reconnect`].
+    fn start_reconnect(&mut self) -> FuturesWsEvent {
+        let backoff = self.backoff_duration();
         self.reconnect_attempt += 1;
         self.connected = false;
         self.reconnecting = true;
         self.authenticated = false;
 
-        // Close existing connection
+        // The old challenge is tied to the old connection.
+        self.auth_state = None;
         self.sink = None;
         self.receiver = None;
 
-        // Wait with backoff
-        let backoff = self.backoff_duration();
-        tokio::time::sleep(backoff).await;
+        self.reconnect_task = Some(Box::pin(open_connection(
+            self.url.clone(),
+            self.credentials.clone(),
+            backoff,
+        )));
 
-        // Try to reconnect
-        let (ws_stream, _) = connect_async(&self.url)
-            .await
-            .map_err(|e| KrakenError::WebSocketMsg(format!("Failed to reconnect: {}", e)))?;
+        FuturesWsEvent::Reconnecting {
+            attempt: self.reconnect_attempt,
+        }
+    }
 
-        let (sink, receiver) = ws_stream.split();
-        self.sink = Some(Arc::new(Mutex::new(sink)));
-        self.receiver = Some(receiver);
+    /// Install a re-established connection and restore subscriptions.
+    fn finish_reconnect(&mut self, connection: Connection) {
+        self.sink = Some(connection.sink.clone());
+        self.receiver = Some(connection.receiver);
+        self.authenticated = connection.auth_state.is_some();
+        self.auth_state = connection.auth_state;
         self.connected = true;
         self.reconnecting = false;
         self.reconnect_attempt = 0;
         self.last_message = Instant::now();
 
-        // Re-authenticate if we have credentials
-        if self.credentials.is_some() {
-            self.authenticate().await?;
+        let requests = self.restore_requests();
+        if requests.is_empty() {
+            return;
         }
 
-        // Restore subscriptions
-        self.restore_subscriptions().await?;
-
-        Ok(())
+        // Sent in order while holding the sink, so user requests queue behind them.
+        let sink = connection.sink;
+        tokio::spawn(async move {
+            let mut sink = sink.lock().await;
+            for json in requests {
+                if let Err(e) = sink.send(WsMessage::Text(json.into())).await {
+                    tracing::warn!("Failed to restore subscription: {}", e);
+                    break;
+                }
+            }
+        });
     }
 
-    /// Restore subscriptions after reconnection.
-    #[allow(dead_code)]
-    async fn restore_subscriptions(&mut self) -> Result<(), KrakenError> {
-        let subs: Vec<_> = self.subscriptions.values().cloned().collect();
-
-        for sub in subs {
-            if sub.is_private {
-                if sub.product_ids.is_empty() {
-                    let auth = self
-                        .auth_state
-                        .as_ref()
-                        .ok_or_else(|| KrakenError::WebSocketMsg("Not authenticated".into()))?;
-                    let request = PrivateSubscribeRequest::new(
-                        &sub.feed,
-                        auth.challenge.clone(),
-                        auth.signed_challenge.clone(),
-                    );
-                    self.send_json(&request).await?;
-                } else {
-                    let auth = self
-                        .auth_state
-                        .as_ref()
-                        .ok_or_else(|| KrakenError::WebSocketMsg("Not authenticated".into()))?;
-                    let request = PrivateSubscribeRequest::new(
-                        &sub.feed,
-                        auth.challenge.clone(),
-                        auth.signed_challenge.clone(),
-                    )
-                    .with_product_ids(sub.product_ids);
-                    self.send_json(&request).await?;
+    /// Serialized subscription requests to replay after reconnection.
+    ///
+    /// Private feeds are signed with the current challenge and skipped if the
+    /// connection is not authenticated.
+    fn restore_requests(&self) -> Vec<String> {
+        let mut requests = Vec::new();
+
+        for sub in self.subscriptions.values() {
+            let json = if sub.is_private {
+                let Some(auth) = &self.auth_state else {
+                    tracing::warn!("Not authenticated, skipping private feed {}", sub.feed);
+                    continue;
+                };
+                let mut request = PrivateSubscribeRequest::new(
+                    &sub.feed,
+                    auth.challenge.clone(),
+                    auth.signed_challenge.clone(),
+                );
+                if !sub.product_ids.is_empty() {
+                    request = request.with_product_ids(sub.product_ids.clone());
                 }
+                serde_json::to_string(&request)
             } else {
-                let request = SubscribeRequest::public(&sub.feed, sub.product_ids);
-                self.send_json(&request).await?;
+                serde_json::to_string(&SubscribeRequest::public(
+                    &sub.feed,
+                    sub.product_ids.clone(),
+                ))
+            };
+
+            match json {
+                Ok(json) => requests.push(json),
+                Err(e) => tracing::warn!("Failed to serialize subscription: {}", e),
             }
         }
 
-        Ok(())
+        requests
     }
 
     /// Parse and handle an incoming message.
@@ -504,7 +526,7 @@ impl FuturesStream {
 
         // Check feed type
         if let Some(feed) = feed {
-            return self.handle_feed_message(&feed, value);
+            return feed_event(&feed, value);
         }
 
         // Unknown format
@@ -513,13 +535,19 @@ impl FuturesStream {
 
     /// Handle event-based messages (subscribed, error, etc.).
     fn handle_event_message(
-        &self,
+        &mut self,
         event: &str,
         value: serde_json::Value,
     ) -> Option<FuturesWsEvent> {
         match event {
             "info" | "alert" => {
                 if let Ok(info) = serde_json::from_value::<InfoResponse>(value) {
+                    if info.event == "alert" && is_auth_failure(&info.message) {
+                        self.authenticated = false;
+                        return Some(FuturesWsEvent::AuthFailed {
+                            message: info.message,
+                        });
+                    }
                     return Some(FuturesWsEvent::Info(info));
                 }
             }
@@ -535,6 +563,12 @@ impl FuturesStream {
             }
             "error" => {
                 if let Ok(err) = serde_json::from_value::<ErrorResponse>(value) {
+                    if is_auth_failure(&err.message) {
+                        self.authenticated = false;
+                        return Some(FuturesWsEvent::AuthFailed {
+                            message: err.message,
+                        });
+                    }
                     return Some(FuturesWsEvent::Error(err));
                 }
             }
@@ -549,61 +583,6 @@ impl FuturesStream {
         None
     }
 
-    /// Handle feed-based messages (book, ticker, etc.).
-    fn handle_feed_message(&self, feed: &str, value: serde_json::Value) -> Option<FuturesWsEvent> {
-        match feed {
-            "book" => {
-                if let Ok(book) = serde_json::from_value::<BookMessage>(value) {
-                    return Some(FuturesWsEvent::Book(book));
-                }
-            }
-            "book_snapshot" => {
-                if let Ok(snapshot) = serde_json::from_value::<BookSnapshotMessage>(value) {
-                    return Some(FuturesWsEvent::BookSnapshot(snapshot));
-                }
-            }
-            "ticker" | "ticker_lite" => {
-                if let Ok(ticker) = serde_json::from_value::<TickerMessage>(value) {
-                    return Some(FuturesWsEvent::Ticker(ticker));
-                }
-            }
-            "trade" => {
-                if let Ok(trade) = serde_json::from_value::<TradeMessage>(value) {
-                    return Some(FuturesWsEvent::Trade(trade));
-                }
-            }
-            "trade_snapshot" => {
-                if let Ok(snapshot) = serde_json::from_value::<TradesSnapshotMessage>(value) {
-                    return Some(FuturesWsEvent::TradesSnapshot(snapshot));
-                }
-            }
-            "open_orders" | "open_orders_snapshot" => {
-                if let Ok(orders) = serde_json::from_value::<OpenOrdersMessage>(value) {
-                    return Some(FuturesWsEvent::OpenOrders(orders));
-                }
-            }
-            "fills" | "fills_snapshot" => {
-                if let Ok(fills) = serde_json::from_value::<FillsMessage>(value) {
-                    return Some(FuturesWsEvent::Fills(fills));
-                }
-            }
-            "open_positions" | "open_positions_snapshot" => {
-                if let Ok(positions) = serde_json::from_value::<OpenPositionsMessage>(value) {
-                    return Some(FuturesWsEvent::OpenPositions(positions));
-                }
-            }
-            "balances" | "balances_snapshot" => {
-                if let Ok(balances) = serde_json::from_value::<BalancesMessage>(value) {
-                    return Some(FuturesWsEvent::Balances(balances));
-                }
-            }
-            _ => {
-                return Some(FuturesWsEvent::Raw(value));
-            }
-        }
-        None
-    }
-
     /// Close the connection gracefully.
     pub async fn close(&mut self) -> Result<(), KrakenError> {
         if let Some(sink) = self.sink.take() {
@@ -611,7 +590,9 @@ impl FuturesStream {
             let _ = sink.send(WsMessage::Close(None)).await;
         }
         self.receiver = None;
+        self.reconnect_task = None;
         self.connected = false;
+        self.closed = true;
         Ok(())
     }
 
@@ -637,6 +618,37 @@ impl Stream for FuturesStream {
             // which tokio-tungstenite handles automatically
         }
 
+        if self.closed {
+            return Poll::Ready(None);
+        }
+
+        // Drive an in-flight reconnect
+        if let Some(task) = self.reconnect_task.as_mut() {
+            let result = match task.as_mut().poll(cx) {
+                Poll::Ready(result) => result,
+                Poll::Pending => return Poll::Pending,
+            };
+            let this = self.as_mut().get_mut();
+            this.reconnect_task = None;
+            this.reconnecting = false;
+
+            match result {
+                Ok(connection) => {
+                    this.finish_reconnect(connection);
+                    return Poll::Ready(Some(Ok(FuturesWsEvent::Reconnected)));
+                }
+                Err(ReconnectError::Auth(e)) => {
+                    tracing::warn!("Re-authentication failed: {}", e);
+                    return Poll::Ready(Some(Ok(FuturesWsEvent::AuthFailed {
+                        message: e.to_string(),
+                    })));
+                }
+                Err(ReconnectError::Connect(e)) => {
+                    tracing::warn!("Reconnect attempt {} failed: {}", this.reconnect_attempt, e);
+                }
+            }
+        }
+
         // Poll the receiver for messages
         if let Some(receiver) = self.receiver.as_mut() {
             match Pin::new(receiver).poll_next(cx) {
@@ -668,10 +680,9 @@ impl Stream for FuturesStream {
                         WsMessage::Close(_) => {
                             this.connected = false;
                             if this.should_reconnect() {
-                                return Poll::Ready(Some(Ok(FuturesWsEvent::Reconnecting {
-                                    attempt: this.reconnect_attempt + 1,
-                                })));
+                                return Poll::Ready(Some(Ok(this.start_reconnect())));
                             } else {
+                                this.closed = true;
                                 return Poll::Ready(Some(Ok(FuturesWsEvent::Disconnected)));
                             }
                         }
@@ -687,10 +698,9 @@ impl Stream for FuturesStream {
                     tracing::warn!("WebSocket error: {}", e);
 
                     if this.should_reconnect() {
-                        return Poll::Ready(Some(Ok(FuturesWsEvent::Reconnecting {
-                            attempt: this.reconnect_attempt + 1,
-                        })));
+                        return Poll::Ready(Some(Ok(this.start_reconnect())));
                     } else {
+                        this.closed = true;
                         return Poll::Ready(Some(Err(KrakenError::WebSocket(e))));
                     }
                 }
@@ -699,26 +709,151 @@ impl Stream for FuturesStream {
                     this.connected = false;
 
                     if this.should_reconnect() {
-                        return Poll::Ready(Some(Ok(FuturesWsEvent::Reconnecting {
-                            attempt: this.reconnect_attempt + 1,
-                        })));
+                        return Poll::Ready(Some(Ok(this.start_reconnect())));
                     } else {
+                        this.closed = true;
                         return Poll::Ready(None);
                     }
                 }
                 Poll::Pending => {}
             }
-        } else if !self.reconnecting && self.should_reconnect() {
-            // Need to reconnect
-            return Poll::Ready(Some(Ok(FuturesWsEvent::Reconnecting {
-                attempt: self.reconnect_attempt + 1,
-            })));
+        } else if !self.reconnecting {
+            // The last reconnect attempt failed
+            let this = self.as_mut().get_mut();
+            if this.should_reconnect() {
+                return Poll::Ready(Some(Ok(this.start_reconnect())));
+            }
+            this.closed = true;
+            return Poll::Ready(Some(Ok(FuturesWsEvent::Disconnected)));
         }
 
         Poll::Pending
     }
 }
 
+/// Open a new connection after `backoff` and, if credentials are given,
+/// authenticate it with a fresh challenge.
+async fn open_connection(
+    url: String,
+    credentials: Option<Arc<dyn CredentialsProvider>>,
+    backoff: Duration,
+) -> Result<Connection, ReconnectError> {
+    tokio::time::sleep(backoff).await;
+
+    let (ws_stream, _) = connect_async(&url).await.map_err(|e| {
+        ReconnectError::Connect(KrakenError::WebSocketMsg(format!(
+            "Failed to reconnect: {}",
+            e
+        )))
+    })?;
+
+    let (sink, mut receiver) = ws_stream.split();
+    let sink = Arc::new(Mutex::new(sink));
+
+    let auth_state = match credentials {
+        Some(credentials) => Some(
+            challenge_handshake(&sink, &mut receiver, credentials.as_ref())
+                .await
+                .map_err(ReconnectError::Auth)?,
+        ),
+        None => None,
+    };
+
+    Ok(Connection {
+        sink,
+        receiver,
+        auth_state,
+    })
+}
+
+/// Request a challenge on this connection and sign it with the provider's
+/// current key.
+async fn challenge_handshake(
+    sink: &Mutex<WsSink>,
+    receiver: &mut WsReceiver,
+    credentials: &dyn CredentialsProvider,
+) -> Result<AuthState, KrakenError> {
+    let creds = credentials.get_credentials().await?;
+
+    // Send challenge request
+    send_json(sink, &ChallengeRequest::new(&creds.api_key)).await?;
+
+    // Wait for challenge response
+    let challenge = wait_for_challenge(receiver).await?;
+
+    // Sign the challenge.
+    let signed_challenge = sign_challenge(&creds, &challenge)?;
+
+    Ok(AuthState {
+        challenge,
+        signed_challenge,
+    })
+}
+
+/// Wait for challenge response from the server.
+async fn wait_for_challenge(receiver: &mut WsReceiver) -> Result<String, KrakenError> {
+    let timeout = Duration::from_secs(10);
+    let start = Instant::now();
+
+    while start.elapsed() < timeout {
+        match tokio::time::timeout(Duration::from_millis(100), receiver.next()).await {
+            Ok(Some(Ok(WsMessage::Text(text)))) => {
+                let value: serde_json::Value =
+                    serde_json::from_str(&text).map_err(KrakenError::Json)?;
+
+                if let Some(event) = value.get("event").and_then(|e| e.as_str()) {
+                    if event == "challenge" {
+                        if let Some(message) = value.get("message").and_then(|m| m.as_str()) {
+                            return Ok(message.to_string());
+                        }
+                    } else if event == "error" {
+                        let msg = value
+                            .get("message")
+                            .and_then(|m| m.as_str())
+                            .unwrap_or("Unknown error");
+                        return Err(KrakenError::WebSocketMsg(format!(
+                            "Authentication error: {}",
+                            msg
+                        )));
+                    }
+                }
+            }
+            Ok(Some(Err(e))) => {
+                return Err(KrakenError::WebSocket(e));
+            }
+            Ok(None) => {
+                return Err(KrakenError::WebSocketMsg(
+                    "Connection closed while waiting for challenge".into(),
+                ));
+            }
+            _ => continue,
+        }
+    }
+
+    Err(KrakenError::WebSocketMsg(
+        "Timeout waiting for challenge response".into(),
+    ))
+}
+
+/// Serialize and send a JSON message.
+async fn send_json<T: serde::Serialize>(sink: &Mutex<WsSink>, msg: &T) -> Result<(), KrakenError> {
+    let json = serde_json::to_string(msg)
+        .map_err(|e| KrakenError::WebSocketMsg(format!("Failed to serialize message: {}", e)))?;
+
+    let mut sink = sink.lock().await;
+    sink.send(WsMessage::Text(json.into()))
+        .await
+        .map_err(|e| KrakenError::WebSocketMsg(format!("Failed to send message: {}", e)))
+}
+
+/// Whether a server error or alert message reports an authentication failure.
+fn is_auth_failure(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    ["challenge", "authenticat", "api key", "apikey"]
+        .iter()
+        .any(|needle| message.contains(needle))
+}
+
 /// Generate a subscription key for tracking.
 fn subscription_key(feed: &str, product_ids: &[String]) -> String {
     if product_ids.is_empty() {
@@ -728,6 +863,87 @@ fn subscription_key(feed: &str, product_ids: &[String]) -> String {
     }
 }
 
+/// Map a feed message to a typed event.
+fn feed_event(feed: &str, value: serde_json::Value) -> Option<FuturesWsEvent> {
+    match feed {
+        "book" => {
+            if let Ok(book) = serde_json::from_value::<BookMessage>(value.clone()) {
+                return Some(FuturesWsEvent::Book(book));
+            }
+        }
+        "book_snapshot" => {
+            if let Ok(snapshot) = serde_json::from_value::<BookSnapshotMessage>(value.clone()) {
+                return Some(FuturesWsEvent::BookSnapshot(snapshot));
+            }
+        }
+        "ticker" => {
+            if let Ok(ticker) = serde_json::from_value::<TickerMessage>(value.clone()) {
+                return Some(FuturesWsEvent::Ticker(ticker));
+            }
+        }
+        "ticker_lite" => {
+            if let Ok(ticker) = serde_json::from_value::<TickerLiteMessage>(value.clone()) {
+                return Some(FuturesWsEvent::TickerLite(ticker));
+            }
+        }
+        "heartbeat" => {
+            if let Ok(heartbeat) = serde_json::from_value::<HeartbeatMessage>(value.clone()) {
+                return Some(FuturesWsEvent::Heartbeat(heartbeat));
+            }
+        }
+        "trade" => {
+            if let Ok(trade) = serde_json::from_value::<TradeMessage>(value.clone()) {
+                return Some(FuturesWsEvent::Trade(trade));
+            }
+        }
+        "trade_snapshot" => {
+            if let Ok(snapshot) = serde_json::from_value::<TradesSnapshotMessage>(value.clone()) {
+                return Some(FuturesWsEvent::TradesSnapshot(snapshot));
+            }
+        }
+        "open_orders" | "open_orders_snapshot" => {
+            if let Ok(orders) = serde_json::from_value::<OpenOrdersMessage>(value.clone()) {
+                return Some(FuturesWsEvent::OpenOrders(orders));
+            }
+        }
+        "fills" | "fills_snapshot" => {
+            if let Ok(fills) = serde_json::from_value::<FillsMessage>(value.clone()) {
+                return Some(FuturesWsEvent::Fills(fills));
+            }
+        }
+        "open_positions" | "open_positions_snapshot" => {
+            if let Ok(positions) = serde_json::from_value::<OpenPositionsMessage>(value.clone()) {
+                return Some(FuturesWsEvent::OpenPositions(positions));
+            }
+        }
+        "balances" | "balances_snapshot" => {
+            if let Ok(balances) = serde_json::from_value::<BalancesMessage>(value.clone()) {
+                return Some(FuturesWsEvent::Balances(balances));
+            }
+        }
+        "account_log_snapshot" => {
+            if let Ok(logs) = serde_json::from_value::<AccountLogSnapshotMessage>(value.clone()) {
+                return Some(FuturesWsEvent::AccountLogSnapshot(logs));
+            }
+        }
+        "account_log" => {
+            if let Ok(log) = serde_json::from_value::<AccountLogMessage>(value.clone()) {
+                return Some(FuturesWsEvent::AccountLog(log));
+            }
+        }
+        "notifications_auth" => {
+            if let Ok(notifications) = serde_json::from_value::<NotificationsMessage>(value.clone())
+            {
+                return Some(FuturesWsEvent::Notifications(notifications));
+            }
+        }
+        _ => {
+            return Some(FuturesWsEvent::Raw(value));
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -744,6 +960,82 @@ mod tests {
         assert_eq!(key, "open_orders");
     }
 
+    fn fixture_event(fixture: &str) -> FuturesWsEvent {
+        let value: serde_json::Value = serde_json::from_str(fixture).unwrap();
+        let feed = value["feed"].as_str().unwrap().to_string();
+        feed_event(&feed, value).unwrap()
+    }
+
+    #[test]
+    fn test_account_log_snapshot_fixture() {
+        let event = fixture_event(include_str!(
+            "../../../tests/fixtures/futures_ws/account_log_snapshot.json"
+        ));
+        let FuturesWsEvent::AccountLogSnapshot(msg) = event else {
+            panic!("unexpected event: {event:?}");
+        };
+        assert_eq!(msg.logs.len(), 2);
+        let entry = &msg.logs[0];
+        assert_eq!(entry.id, 1690);
+        assert_eq!(entry.info, "funding rate change");
+        assert_eq!(entry.margin_account.as_deref(), Some("f-bch:usd"));
+        assert_eq!(
+            entry.funding_rate,
+            Some("-0.000000087002552653".parse().unwrap())
+        );
+        assert!(msg.logs[1].funding_rate.is_none());
+    }
+
+    #[test]
+    fn test_account_log_update_fixture() {
+        let event = fixture_event(include_str!(
+            "../../../tests/fixtures/futures_ws/account_log.json"
+        ));
+        let FuturesWsEvent::AccountLog(msg) = event else {
+            panic!("unexpected event: {event:?}");
+        };
+        assert_eq!(msg.new_entry.id, 1697);
+        assert_eq!(msg.new_entry.realized_pnl, Some("-19.25".parse().unwrap()));
+        assert_eq!(msg.new_entry.liquidation_fee, Some("1.5".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_notifications_fixture() {
+        let event = fixture_event(include_str!(
+            "../../../tests/fixtures/futures_ws/notifications_auth.json"
+        ));
+        let FuturesWsEvent::Notifications(msg) = event else {
+            panic!("unexpected event: {event:?}");
+        };
+        assert_eq!(msg.notifications.len(), 2);
+        assert_eq!(msg.notifications[0].notification_type, "market");
+        assert_eq!(msg.notifications[1].expected_downtime_minutes, Some(30));
+    }
+
+    #[test]
+    fn test_heartbeat_fixture() {
+        let event = fixture_event(include_str!(
+            "../../../tests/fixtures/futures_ws/heartbeat.json"
+        ));
+        let FuturesWsEvent::Heartbeat(msg) = event else {
+            panic!("unexpected event: {event:?}");
+        };
+        assert_eq!(msg.time, 1534262350627);
+    }
+
+    #[test]
+    fn test_ticker_lite_fixture() {
+        let event = fixture_event(include_str!(
+            "../../../tests/fixtures/futures_ws/ticker_lite.json"
+        ));
+        let FuturesWsEvent::TickerLite(msg) = event else {
+            panic!("unexpected event: {event:?}");
+        };
+        assert_eq!(msg.product_id, "PI_XBTUSD");
+        assert_eq!(msg.bid, Some("34932".parse().unwrap()));
+        assert_eq!(msg.pair.as_deref(), Some("XBT:USD"));
+    }
+
     #[test]
     fn test_backoff_calculation() {
         let config = WsConfig {
@@ -771,4 +1063,102 @@ mod tests {
         let result = (base * multiplier).min(max);
         assert_eq!(Duration::from_millis(result), Duration::from_secs(60));
     }
+
+    #[test]
+    fn test_is_auth_failure() {
+        assert!(is_auth_failure("Invalid challenge"));
+        assert!(is_auth_failure("Failed to subscribe to authenticated feed"));
+        assert!(!is_auth_failure("Invalid product id"));
+    }
+
+    async fn next_json(ws: &mut WebSocketStream<TcpStream>) -> serde_json::Value {
+        let msg = ws.next().await.unwrap().unwrap();
+        serde_json::from_str(msg.to_text().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_reauthenticates_before_private_resubscribe() {
+        use crate::auth::StaticCredentials;
+        use tokio::net::TcpListener;
+        use tokio::sync::mpsc;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (tx, mut rx) = mpsc::unbounded_channel::<(usize, serde_json::Value)>();
+
+        tokio::spawn(async move {
+            for conn in 0..2usize {
+                let (tcp, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+                let challenge_request = next_json(&mut ws).await;
+                tx.send((conn, challenge_request)).unwrap();
+                let challenge = serde_json::json!({
+                    "event": "challenge",
+                    "message": format!("challenge-{conn}"),
+                });
+                ws.send(WsMessage::Text(challenge.to_string().into()))
+                    .await
+                    .unwrap();
+
+                let subscribe = next_json(&mut ws).await;
+                tx.send((conn, subscribe)).unwrap();
+
+                if conn == 0 {
+                    ws.close(None).await.unwrap();
+                } else {
+                    let error = serde_json::json!({
+                        "event": "error",
+                        "message": "Invalid challenge",
+                    });
+                    ws.send(WsMessage::Text(error.to_string().into()))
+                        .await
+                        .unwrap();
+                }
+            }
+        });
+
+        let credentials = Arc::new(StaticCredentials::new("key", "c2VjcmV0"));
+        let config = WsConfig {
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let mut stream = FuturesStream::connect_private(&url, config, credentials.clone())
+            .await
+            .unwrap();
+        stream.subscribe_private("open_orders").await.unwrap();
+
+        let (_, msg) = rx.recv().await.unwrap();
+        assert_eq!(msg["event"], "challenge");
+        let (_, msg) = rx.recv().await.unwrap();
+        assert_eq!(msg["original_challenge"], "challenge-0");
+
+        assert!(matches!(
+            stream.next().await,
+            Some(Ok(FuturesWsEvent::Reconnecting { attempt: 1 }))
+        ));
+        assert!(matches!(
+            stream.next().await,
+            Some(Ok(FuturesWsEvent::Reconnected))
+        ));
+        assert!(stream.is_authenticated());
+
+        // The new connection is challenged before the private feed is restored.
+        let (conn, msg) = rx.recv().await.unwrap();
+        assert_eq!((conn, msg["event"].as_str()), (1, Some("challenge")));
+        let (conn, msg) = rx.recv().await.unwrap();
+        assert_eq!(conn, 1);
+        assert_eq!(msg["feed"], "open_orders");
+        assert_eq!(msg["original_challenge"], "challenge-1");
+        let expected = sign_challenge(credentials.credentials(), "challenge-1").unwrap();
+        assert_eq!(msg["signed_challenge"], expected.as_str());
+
+        match stream.next().await {
+            Some(Ok(FuturesWsEvent::AuthFailed { message })) => {
+                assert_eq!(message, "Invalid challenge")
+            }
+            other => panic!("expected AuthFailed, got {:?}", other),
+        }
+        assert!(!stream.is_authenticated());
+    }
 }
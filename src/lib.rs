@@ -26,9 +26,11 @@
 
 pub mod auth;
 pub mod error;
+pub mod pairs;
 pub mod rate_limit;
 pub mod spot;
 pub mod types;
+pub mod validation;
 
 // Placeholder for future Kraken Futures API support
 pub mod futures;
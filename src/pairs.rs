@@ -0,0 +1,624 @@
+//! Market metadata registry with symbol normalization.
+//!
+//! Kraken uses several names for the same market: `XXBTZUSD` (REST key),
+//! `XBTUSD` (altname), `XBT/USD` (`AssetPair.wsname`), `BTC/USD` (WebSocket
+//! v2) and `PF_XBTUSD` (futures). Asset codes differ the same way (`XXBT`,
+//! `XBT`, `BTC`).
+//!
+//! [`PairRegistry`] loads spot asset pairs, spot assets and, optionally,
+//! futures instruments, and resolves any of those names to one canonical
+//! [`Pair`] or [`Asset`]. The metadata is reloaded once it is older than the
+//! registry's TTL.
+//!
+//! WebSocket and futures builders take a `&Pair` wherever they take a market
+//! name, through [`IntoWsSymbol`] and [`IntoFuturesSymbol`], and pick the
+//! name their venue expects. Spot REST builders take [`Pair::try_rest`],
+//! which fails up front for futures-only markets.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kraken_api_client::pairs::PairRegistry;
+//! use kraken_api_client::spot::rest::public::OrderBookRequest;
+//! use kraken_api_client::spot::ws::messages::{channels, SubscribeParams};
+//!
+//! let registry = PairRegistry::new(SpotRestClient::new()).with_futures(FuturesRestClient::new());
+//!
+//! let pair = registry.resolve("PF_XBTUSD").await?;
+//! assert_eq!(pair.name, "BTC/USD");
+//!
+//! // Each venue takes its own name for the market.
+//! let book = spot.get_order_book(&OrderBookRequest::new(pair.try_rest()?)).await?;
+//! let order = futures.send_order(&SendOrderRequest::market(&pair, BuySell::Buy, dec!(1))).await?;
+//! ws.subscribe(SubscribeParams::public_symbols(channels::TICKER, [&pair])).await?;
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::RwLock;
+
+use crate::error::KrakenError;
+use crate::futures::rest::{FuturesClient, FuturesRestClient};
+use crate::futures::types::{ContractType, FuturesInstrument};
+use crate::spot::rest::KrakenClient;
+use crate::spot::rest::public::{AssetInfo, AssetPair};
+use crate::types::common::{asset_altname, asset_ws_name};
+
+/// Default time after which metadata is reloaded.
+const DEFAULT_TTL: Duration = Duration::from_secs(3600);
+
+/// Futures symbol prefixes, in order of preference for [`Pair::perpetual`].
+const PERPETUAL_PREFIXES: [&str; 2] = ["PF_", "PI_"];
+
+/// Futures symbol prefixes that name tradeable contracts.
+const CONTRACT_PREFIXES: [&str; 4] = ["PF_", "PI_", "FF_", "FI_"];
+
+/// A market and all of its names.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pair {
+    /// Canonical name, "BASE/QUOTE" in WebSocket v2 asset codes (e.g., "BTC/USD")
+    pub name: String,
+    /// Canonical base asset (e.g., "BTC")
+    pub base: String,
+    /// Canonical quote asset (e.g., "USD")
+    pub quote: String,
+    /// Spot REST key (e.g., "XXBTZUSD"), if the market trades on spot
+    pub rest_name: Option<String>,
+    /// Spot alternate name (e.g., "XBTUSD")
+    pub altname: Option<String>,
+    /// `AssetPair.wsname` (e.g., "XBT/USD")
+    pub ws_name: Option<String>,
+    /// Futures symbols for this market (e.g., "PF_XBTUSD")
+    pub futures_symbols: Vec<String>,
+}
+
+impl Pair {
+    /// Name to pass to spot REST requests, or `None` for futures-only
+    /// markets.
+    pub fn rest(&self) -> Option<&str> {
+        self.rest_name.as_deref()
+    }
+
+    /// Name to pass to spot REST requests.
+    ///
+    /// Fails with [`KrakenError::UnknownSymbol`] for futures-only markets,
+    /// which spot would otherwise reject only once the request is sent.
+    pub fn try_rest(&self) -> Result<&str, KrakenError> {
+        self.rest()
+            .ok_or_else(|| KrakenError::UnknownSymbol(self.name.clone()))
+    }
+
+    /// Name to pass to spot WebSocket v2 requests (e.g., "BTC/USD").
+    pub fn ws(&self) -> &str {
+        &self.name
+    }
+
+    /// Perpetual futures symbol, preferring linear ("PF_") contracts.
+    pub fn perpetual(&self) -> Option<&str> {
+        PERPETUAL_PREFIXES.iter().find_map(|prefix| {
+            self.futures_symbols
+                .iter()
+                .find(|symbol| symbol.starts_with(prefix))
+                .map(String::as_str)
+        })
+    }
+
+    /// Whether the market trades on spot.
+    pub fn is_spot(&self) -> bool {
+        self.rest_name.is_some()
+    }
+}
+
+impl std::fmt::Display for Pair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// A symbol argument for spot WebSocket v2 requests: a name, or a [`Pair`].
+pub trait IntoWsSymbol {
+    /// The WebSocket v2 symbol of the market.
+    fn into_ws_symbol(self) -> String;
+}
+
+impl<T: Into<String>> IntoWsSymbol for T {
+    fn into_ws_symbol(self) -> String {
+        self.into()
+    }
+}
+
+impl IntoWsSymbol for &Pair {
+    fn into_ws_symbol(self) -> String {
+        self.ws().to_string()
+    }
+}
+
+/// A symbol argument for futures requests: a symbol, or a [`Pair`].
+pub trait IntoFuturesSymbol {
+    /// The futures symbol of the market.
+    fn into_futures_symbol(self) -> String;
+}
+
+impl<T: Into<String>> IntoFuturesSymbol for T {
+    fn into_futures_symbol(self) -> String {
+        self.into()
+    }
+}
+
+/// Uses the perpetual contract, then any other contract, then the canonical
+/// name for spot-only markets (which futures rejects as an unknown symbol).
+impl IntoFuturesSymbol for &Pair {
+    fn into_futures_symbol(self) -> String {
+        self.perpetual()
+            .or(self.futures_symbols.first().map(String::as_str))
+            .unwrap_or(&self.name)
+            .to_string()
+    }
+}
+
+/// An asset and all of its names.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Asset {
+    /// Canonical code, as used by WebSocket v2 (e.g., "BTC")
+    pub name: String,
+    /// Spot REST key (e.g., "XXBT")
+    pub rest_name: String,
+    /// Spot alternate name (e.g., "XBT")
+    pub altname: String,
+    /// Decimals used for balances, if known
+    pub decimals: Option<u8>,
+}
+
+/// Point-in-time market metadata with alias lookup.
+#[derive(Debug, Clone)]
+pub struct PairSnapshot {
+    pairs: Vec<Pair>,
+    pair_aliases: HashMap<String, usize>,
+    assets: Vec<Asset>,
+    asset_aliases: HashMap<String, usize>,
+    asset_pairs: HashMap<String, AssetPair>,
+    instruments: HashMap<String, FuturesInstrument>,
+    loaded_at: Instant,
+}
+
+impl PairSnapshot {
+    /// Build a snapshot from spot asset pairs, spot assets and futures
+    /// instruments, as returned by `get_asset_pairs`, `get_assets` and
+    /// `get_instruments`.
+    pub fn new(
+        asset_pairs: HashMap<String, AssetPair>,
+        assets: HashMap<String, AssetInfo>,
+        instruments: Vec<FuturesInstrument>,
+    ) -> Self {
+        let mut snapshot = Self {
+            pairs: Vec::new(),
+            pair_aliases: HashMap::new(),
+            assets: Vec::new(),
+            asset_aliases: HashMap::new(),
+            asset_pairs: HashMap::new(),
+            instruments: HashMap::new(),
+            loaded_at: Instant::now(),
+        };
+
+        // WebSocket names carry the asset codes WebSocket v2 uses, apart from
+        // a few legacy codes ("XBT/USD" is "BTC/USD" in v2).
+        let mut ws_codes: HashMap<&str, &str> = HashMap::new();
+        for pair in asset_pairs.values() {
+            if let Some((base, quote)) = pair.wsname.as_deref().and_then(|ws| ws.split_once('/')) {
+                ws_codes.entry(&pair.base).or_insert(base);
+                ws_codes.entry(&pair.quote).or_insert(quote);
+            }
+        }
+
+        let mut asset_keys: Vec<&String> = assets.keys().collect();
+        asset_keys.sort();
+        for key in asset_keys {
+            let info = &assets[key];
+            let name = ws_codes
+                .get(key.as_str())
+                .map(|code| asset_ws_name(code))
+                .unwrap_or_else(|| asset_ws_name(&info.altname));
+            snapshot.add_asset(Asset {
+                name,
+                rest_name: key.clone(),
+                altname: info.altname.clone(),
+                decimals: Some(info.decimals),
+            });
+        }
+
+        let mut pair_keys: Vec<&String> = asset_pairs
+            .keys()
+            .filter(|key| !key.ends_with(".d"))
+            .collect();
+        pair_keys.sort();
+        for key in pair_keys {
+            let info = &asset_pairs[key];
+            for code in [&info.base, &info.quote] {
+                if snapshot.asset(code).is_none() {
+                    let name = ws_codes
+                        .get(code.as_str())
+                        .map(|code| asset_ws_name(code))
+                        .unwrap_or_else(|| asset_ws_name(code));
+                    snapshot.add_asset(Asset {
+                        name,
+                        rest_name: code.clone(),
+                        altname: asset_altname(code),
+                        decimals: None,
+                    });
+                }
+            }
+            let base = snapshot.canonical_asset(&info.base);
+            let quote = snapshot.canonical_asset(&info.quote);
+            let index = snapshot.pair_index(&base, &quote);
+            let pair = &mut snapshot.pairs[index];
+            pair.rest_name = Some(key.clone());
+            pair.altname = Some(info.altname.clone());
+            pair.ws_name = info.wsname.clone();
+        }
+
+        let mut instruments: Vec<FuturesInstrument> = instruments
+            .into_iter()
+            .filter(|instrument| instrument.contract_type != Some(ContractType::Index))
+            .collect();
+        instruments.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        for instrument in instruments {
+            let symbol = instrument.symbol.to_ascii_uppercase();
+            if let Some(index) = snapshot.instrument_pair(&instrument, &symbol) {
+                snapshot.pairs[index].futures_symbols.push(symbol.clone());
+            }
+            snapshot.instruments.insert(symbol, instrument);
+        }
+
+        for index in 0..snapshot.pairs.len() {
+            let pair = snapshot.pairs[index].clone();
+            let aliases = [
+                Some(&pair.name),
+                pair.rest_name.as_ref(),
+                pair.altname.as_ref(),
+            ]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .chain(pair.ws_name.clone())
+            .chain(Some(format!("{}{}", pair.base, pair.quote)))
+            .chain(pair.futures_symbols.iter().cloned());
+            for alias in aliases {
+                snapshot
+                    .pair_aliases
+                    .entry(normalize(&alias))
+                    .or_insert(index);
+            }
+        }
+
+        snapshot.asset_pairs = asset_pairs;
+        snapshot
+    }
+
+    /// Resolve any name of a market.
+    pub fn pair(&self, alias: &str) -> Option<&Pair> {
+        self.pair_aliases
+            .get(&normalize(alias))
+            .map(|&index| &self.pairs[index])
+    }
+
+    /// Resolve any code of an asset.
+    pub fn asset(&self, alias: &str) -> Option<&Asset> {
+        self.asset_aliases
+            .get(&normalize(alias))
+            .map(|&index| &self.assets[index])
+    }
+
+    /// All known markets.
+    pub fn pairs(&self) -> &[Pair] {
+        &self.pairs
+    }
+
+    /// All known assets.
+    pub fn assets(&self) -> &[Asset] {
+        &self.assets
+    }
+
+    /// Spot metadata of a market, if it trades on spot.
+    pub fn asset_pair(&self, pair: &Pair) -> Option<&AssetPair> {
+        self.asset_pairs.get(pair.rest_name.as_ref()?)
+    }
+
+    /// Futures instrument by symbol (case-insensitive).
+    pub fn instrument(&self, symbol: &str) -> Option<&FuturesInstrument> {
+        self.instruments.get(&symbol.to_ascii_uppercase())
+    }
+
+    /// Time since the metadata was loaded.
+    pub fn age(&self) -> Duration {
+        self.loaded_at.elapsed()
+    }
+
+    fn add_asset(&mut self, asset: Asset) {
+        let index = self.assets.len();
+        for alias in [&asset.name, &asset.rest_name, &asset.altname] {
+            self.asset_aliases.entry(normalize(alias)).or_insert(index);
+        }
+        self.assets.push(asset);
+    }
+
+    /// Canonical code of an asset, known or not.
+    fn canonical_asset(&self, code: &str) -> String {
+        match self.asset(code) {
+            Some(asset) => asset.name.clone(),
+            None => asset_ws_name(code),
+        }
+    }
+
+    /// Index of the pair with the given canonical assets, created if missing.
+    fn pair_index(&mut self, base: &str, quote: &str) -> usize {
+        let name = format!("{base}/{quote}");
+        if let Some(index) = self.pairs.iter().position(|pair| pair.name == name) {
+            return index;
+        }
+        self.pairs.push(Pair {
+            name,
+            base: base.to_string(),
+            quote: quote.to_string(),
+            rest_name: None,
+            altname: None,
+            ws_name: None,
+            futures_symbols: Vec::new(),
+        });
+        self.pairs.len() - 1
+    }
+
+    /// Index of the market a futures instrument trades.
+    fn instrument_pair(&mut self, instrument: &FuturesInstrument, symbol: &str) -> Option<usize> {
+        // "XBT:USD"
+        if let Some((base, quote)) = instrument.pair.as_deref().and_then(|p| p.split_once(':')) {
+            let base = self.canonical_asset(base);
+            let quote = self.canonical_asset(quote);
+            return Some(self.pair_index(&base, &quote));
+        }
+
+        // "PF_XBTUSD" or "FI_XBTUSD_240628": match the core against spot names.
+        let core = CONTRACT_PREFIXES
+            .iter()
+            .find_map(|prefix| symbol.strip_prefix(prefix))?;
+        let core = core.split('_').next()?;
+        let name = self.pairs.iter().find_map(|pair| {
+            let altname = pair.altname.as_deref().map(normalize);
+            (altname.as_deref() == Some(core)
+                || normalize(&format!("{}{}", pair.base, pair.quote)) == core)
+                .then(|| (pair.base.clone(), pair.quote.clone()))
+        })?;
+        Some(self.pair_index(&name.0, &name.1))
+    }
+}
+
+/// Alias lookup key.
+fn normalize(alias: &str) -> String {
+    alias.trim().to_ascii_uppercase()
+}
+
+/// Market metadata from the spot and futures APIs, reloaded on a TTL.
+///
+/// Clones of the registry are not shared; wrap it in an [`Arc`] to share one
+/// cache between tasks.
+pub struct PairRegistry<S, F = FuturesRestClient> {
+    spot: S,
+    futures: Option<F>,
+    ttl: Duration,
+    snapshot: RwLock<Option<Arc<PairSnapshot>>>,
+}
+
+impl<S: KrakenClient> PairRegistry<S> {
+    /// Create a registry that loads spot metadata only.
+    pub fn new(spot: S) -> Self {
+        Self {
+            spot,
+            futures: None,
+            ttl: DEFAULT_TTL,
+            snapshot: RwLock::new(None),
+        }
+    }
+}
+
+impl<S: KrakenClient, F: FuturesClient> PairRegistry<S, F> {
+    /// Also load futures instruments from `futures`.
+    pub fn with_futures<G: FuturesClient>(self, futures: G) -> PairRegistry<S, G> {
+        PairRegistry {
+            spot: self.spot,
+            futures: Some(futures),
+            ttl: self.ttl,
+            snapshot: RwLock::new(None),
+        }
+    }
+
+    /// Set how long loaded metadata is used before it is reloaded (default: 1 hour).
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Resolve any name of a market.
+    pub async fn resolve(&self, alias: &str) -> Result<Pair, KrakenError> {
+        self.snapshot()
+            .await?
+            .pair(alias)
+            .cloned()
+            .ok_or_else(|| KrakenError::UnknownSymbol(alias.to_string()))
+    }
+
+    /// Resolve any code of an asset.
+    pub async fn resolve_asset(&self, alias: &str) -> Result<Asset, KrakenError> {
+        self.snapshot()
+            .await?
+            .asset(alias)
+            .cloned()
+            .ok_or_else(|| KrakenError::UnknownSymbol(alias.to_string()))
+    }
+
+    /// Current metadata, reloaded first if it is missing or older than the TTL.
+    pub async fn snapshot(&self) -> Result<Arc<PairSnapshot>, KrakenError> {
+        if let Some(snapshot) = self.fresh().await {
+            return Ok(snapshot);
+        }
+
+        let mut guard = self.snapshot.write().await;
+        // Another task may have reloaded while we waited for the lock.
+        if let Some(snapshot) = guard.as_ref().filter(|s| s.age() < self.ttl) {
+            return Ok(snapshot.clone());
+        }
+        let snapshot = Arc::new(self.load().await?);
+        *guard = Some(snapshot.clone());
+        Ok(snapshot)
+    }
+
+    /// Reload the metadata now, regardless of its age.
+    pub async fn refresh(&self) -> Result<Arc<PairSnapshot>, KrakenError> {
+        let mut guard = self.snapshot.write().await;
+        let snapshot = Arc::new(self.load().await?);
+        *guard = Some(snapshot.clone());
+        Ok(snapshot)
+    }
+
+    async fn fresh(&self) -> Option<Arc<PairSnapshot>> {
+        self.snapshot
+            .read()
+            .await
+            .as_ref()
+            .filter(|snapshot| snapshot.age() < self.ttl)
+            .cloned()
+    }
+
+    async fn load(&self) -> Result<PairSnapshot, KrakenError> {
+        let asset_pairs = self.spot.get_asset_pairs(None).await?;
+        let assets = self.spot.get_assets(None).await?;
+        let instruments = match &self.futures {
+            Some(futures) => futures.get_instruments().await?,
+            None => Vec::new(),
+        };
+        Ok(PairSnapshot::new(asset_pairs, assets, instruments))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> PairSnapshot {
+        let asset_pairs = serde_json::from_value(serde_json::json!({
+            "XXBTZUSD": {
+                "altname": "XBTUSD", "wsname": "XBT/USD", "aclass_base": "currency",
+                "base": "XXBT", "aclass_quote": "currency", "quote": "ZUSD",
+                "cost_decimals": 5, "pair_decimals": 1, "lot_decimals": 8, "lot_multiplier": 1
+            },
+            "XXBTZUSD.d": {
+                "altname": "XBTUSD.d", "aclass_base": "currency",
+                "base": "XXBT", "aclass_quote": "currency", "quote": "ZUSD",
+                "cost_decimals": 5, "pair_decimals": 1, "lot_decimals": 8, "lot_multiplier": 1
+            },
+            "XDGUSD": {
+                "altname": "XDGUSD", "wsname": "XDG/USD", "aclass_base": "currency",
+                "base": "XXDG", "aclass_quote": "currency", "quote": "ZUSD",
+                "cost_decimals": 5, "pair_decimals": 7, "lot_decimals": 8, "lot_multiplier": 1
+            },
+            "ETHUSDT": {
+                "altname": "ETHUSDT", "wsname": "ETH/USDT", "aclass_base": "currency",
+                "base": "XETH", "aclass_quote": "currency", "quote": "USDT",
+                "cost_decimals": 5, "pair_decimals": 2, "lot_decimals": 8, "lot_multiplier": 1
+            }
+        }))
+        .unwrap();
+        let assets = serde_json::from_value(serde_json::json!({
+            "XXBT": { "aclass": "currency", "altname": "XBT", "decimals": 10, "display_decimals": 5 },
+            "ZUSD": { "aclass": "currency", "altname": "USD", "decimals": 4, "display_decimals": 2 },
+            "XETH": { "aclass": "currency", "altname": "ETH", "decimals": 10, "display_decimals": 5 }
+        }))
+        .unwrap();
+        let instruments = serde_json::from_value(serde_json::json!([
+            { "symbol": "PF_XBTUSD", "pair": "XBT:USD" },
+            { "symbol": "PI_XBTUSD", "type": "futures_inverse" },
+            { "symbol": "FI_XBTUSD_240628", "type": "futures_inverse" },
+            { "symbol": "PF_SOLUSD", "pair": "SOL:USD" },
+            { "symbol": "in_xbtusd", "type": "spot index" }
+        ]))
+        .unwrap();
+        PairSnapshot::new(asset_pairs, assets, instruments)
+    }
+
+    #[test]
+    fn test_pair_aliases_resolve_to_one_pair() {
+        let snapshot = snapshot();
+        let pair = snapshot.pair("XXBTZUSD").unwrap();
+        assert_eq!(pair.name, "BTC/USD");
+
+        for alias in [
+            "xbtusd",
+            "XBT/USD",
+            "BTC/USD",
+            "pf_xbtusd",
+            "PI_XBTUSD",
+            "FI_XBTUSD_240628",
+        ] {
+            assert_eq!(snapshot.pair(alias), Some(pair), "{alias}");
+        }
+        assert_eq!(pair.rest(), Some("XXBTZUSD"));
+        assert_eq!(pair.ws(), "BTC/USD");
+        assert_eq!(pair.perpetual(), Some("PF_XBTUSD"));
+        assert!(snapshot.pair("in_xbtusd").is_none());
+        assert!(snapshot.asset_pair(pair).is_some());
+    }
+
+    #[test]
+    fn test_asset_codes() {
+        let snapshot = snapshot();
+        let btc = snapshot.asset("XBT").unwrap();
+        assert_eq!(btc.name, "BTC");
+        assert_eq!(btc.rest_name, "XXBT");
+        assert_eq!(snapshot.asset("xxbt"), Some(btc));
+
+        // Assets missing from `get_assets` are derived from the pair metadata.
+        let doge = snapshot.asset("XXDG").unwrap();
+        assert_eq!(doge.name, "DOGE");
+        assert_eq!(snapshot.pair("XDGUSD").unwrap().name, "DOGE/USD");
+    }
+
+    #[test]
+    fn test_builders_accept_pairs() {
+        use crate::futures::rest::SendOrderRequest;
+        use crate::spot::rest::public::OrderBookRequest;
+        use crate::spot::ws::SpotOrderBook;
+        use crate::spot::ws::messages::{AddOrderParams, SubscribeParams};
+        use crate::types::{BookView, BuySell, OrderType};
+
+        let snapshot = snapshot();
+        let btc = snapshot.pair("BTC/USD").unwrap();
+        assert_eq!(
+            OrderBookRequest::new(btc.try_rest().unwrap()).pair,
+            "XXBTZUSD"
+        );
+        assert_eq!(
+            AddOrderParams::new(OrderType::Market, BuySell::Buy, btc, "token").symbol,
+            "BTC/USD"
+        );
+        assert_eq!(
+            SubscribeParams::public_symbols("ticker", [btc]).symbol,
+            Some(vec!["BTC/USD".to_string()])
+        );
+        let order = SendOrderRequest::market(btc, BuySell::Buy, rust_decimal::Decimal::ONE);
+        assert_eq!(order.symbol, "PF_XBTUSD");
+        assert_eq!(SpotOrderBook::new(btc).symbol(), "BTC/USD");
+    }
+
+    #[test]
+    fn test_futures_only_pair() {
+        let snapshot = snapshot();
+        let sol = snapshot.pair("PF_SOLUSD").unwrap();
+        assert_eq!(sol.name, "SOL/USD");
+        assert!(!sol.is_spot());
+        assert_eq!(sol.rest(), None);
+        assert!(
+            matches!(sol.try_rest(), Err(KrakenError::UnknownSymbol(name)) if name == "SOL/USD")
+        );
+        assert!(snapshot.instrument("pf_solusd").is_some());
+    }
+}
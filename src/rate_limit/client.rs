@@ -29,7 +29,8 @@ use tokio::sync::Mutex;
 
 use crate::error::KrakenError;
 use crate::rate_limit::{
-    KeyedRateLimiter, OrderTrackingInfo, RateLimitConfig, SlidingWindow, TradingRateLimiter,
+    FuturesBudget, FuturesRateLimiter, KeyedRateLimiter, OrderTrackingInfo, RateLimitBehavior,
+    RateLimitConfig, SharedTradingLimiter, SlidingWindow, TradingRateLimiter,
 };
 use crate::spot::rest::private::{
     AddOrderRequest, AddOrderResponse, AllocationStatus, CancelOrderRequest, CancelOrderResponse,
@@ -53,6 +54,10 @@ use crate::types::VerificationTier;
 
 /// A rate-limited wrapper around any [`KrakenClient`] implementation.
 ///
+/// It also implements [`FuturesClient`](crate::futures::rest::FuturesClient)
+/// when wrapping a futures client, charging Kraken Futures' per-endpoint
+/// points budgets instead of the spot limits (see [`crate::rate_limit::FuturesBudget`]).
+///
 /// This wrapper automatically handles:
 /// - Public endpoint rate limits (sliding window)
 /// - Private endpoint rate limits (token bucket, tier-based)
@@ -78,9 +83,11 @@ pub struct RateLimitedClient<C> {
     /// Private endpoint rate limiter (token bucket)
     private_limiter: Arc<Mutex<PrivateRateLimiter>>,
     /// Trading rate limiter with order penalties
-    trading_limiter: Arc<Mutex<TradingRateLimiter>>,
+    trading_limiter: SharedTradingLimiter,
     /// Per-pair rate limiter for order book requests
     orderbook_limiter: Arc<Mutex<KeyedRateLimiter<String>>>,
+    /// Futures points budgets (used when wrapping a futures client)
+    futures_limiter: Arc<Mutex<FuturesRateLimiter>>,
 }
 
 impl<C> RateLimitedClient<C> {
@@ -100,15 +107,16 @@ impl<C> RateLimitedClient<C> {
                 max_counter,
                 decay_rate,
             ))),
-            trading_limiter: Arc::new(Mutex::new(TradingRateLimiter::new(
+            trading_limiter: SharedTradingLimiter::new(TradingRateLimiter::new(
                 max_counter,
                 decay_rate,
-            ))),
+            )),
             // Order book: 1 request per second per pair
             orderbook_limiter: Arc::new(Mutex::new(KeyedRateLimiter::new(
                 Duration::from_secs(1),
                 1,
             ))),
+            futures_limiter: Arc::new(Mutex::new(FuturesRateLimiter::new())),
         }
     }
 
@@ -123,6 +131,29 @@ impl<C> RateLimitedClient<C> {
         )
     }
 
+    /// Use a trading limiter shared with other clients for the same API key.
+    ///
+    /// Pass a clone of the same limiter to
+    /// [`KrakenStream::set_trading_limiter`](crate::spot::ws::KrakenStream::set_trading_limiter)
+    /// so REST and WebSocket order entry draw from one budget.
+    pub fn with_trading_limiter(mut self, limiter: SharedTradingLimiter) -> Self {
+        self.trading_limiter = limiter;
+        self
+    }
+
+    /// Get the trading limiter used by this client.
+    pub fn trading_limiter(&self) -> &SharedTradingLimiter {
+        &self.trading_limiter
+    }
+
+    /// Use custom futures budgets.
+    ///
+    /// Clones of the client share the same budgets.
+    pub fn with_futures_limiter(mut self, limiter: FuturesRateLimiter) -> Self {
+        self.futures_limiter = Arc::new(Mutex::new(limiter));
+        self
+    }
+
     /// Get a reference to the inner client.
     pub fn inner(&self) -> &C {
         &self.inner
@@ -139,7 +170,7 @@ impl<C> RateLimitedClient<C> {
     }
 
     /// Wait for the public rate limiter.
-    async fn wait_public(&self) -> Result<(), KrakenError> {
+    pub(super) async fn wait_public(&self) -> Result<(), KrakenError> {
         if !self.config.enabled {
             return Ok(());
         }
@@ -157,7 +188,7 @@ impl<C> RateLimitedClient<C> {
     }
 
     /// Wait for the private rate limiter.
-    async fn wait_private(&self) -> Result<(), KrakenError> {
+    pub(super) async fn wait_private(&self) -> Result<(), KrakenError> {
         if !self.config.enabled {
             return Ok(());
         }
@@ -174,6 +205,28 @@ impl<C> RateLimitedClient<C> {
         }
     }
 
+    /// Wait until a futures budget can cover `cost` points.
+    pub(super) async fn wait_futures(
+        &self,
+        budget: FuturesBudget,
+        cost: u32,
+    ) -> Result<(), KrakenError> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        loop {
+            let mut limiter = self.futures_limiter.lock().await;
+            match limiter.try_acquire(budget, cost) {
+                Ok(()) => return Ok(()),
+                Err(wait_time) => {
+                    drop(limiter);
+                    tokio::time::sleep(wait_time).await;
+                }
+            }
+        }
+    }
+
     /// Wait for the order book rate limiter (per-pair).
     async fn wait_orderbook(&self, pair: &str) -> Result<(), KrakenError> {
         if !self.config.enabled {
@@ -202,17 +255,9 @@ impl<C> RateLimitedClient<C> {
             return Ok(());
         }
 
-        loop {
-            let mut limiter = self.trading_limiter.lock().await;
-            let info = OrderTrackingInfo::new(pair);
-            match limiter.try_place_order(order_id, info) {
-                Ok(()) => return Ok(()),
-                Err(wait_time) => {
-                    drop(limiter);
-                    tokio::time::sleep(wait_time).await;
-                }
-            }
-        }
+        self.trading_limiter
+            .acquire_place(order_id, OrderTrackingInfo::new(pair), RateLimitBehavior::Wait)
+            .await
     }
 
     /// Wait for the trading rate limiter (order cancellation).
@@ -221,16 +266,10 @@ impl<C> RateLimitedClient<C> {
             return Ok(());
         }
 
-        loop {
-            let mut limiter = self.trading_limiter.lock().await;
-            match limiter.try_cancel_order(order_id) {
-                Ok(_penalty) => return Ok(()),
-                Err(wait_time) => {
-                    drop(limiter);
-                    tokio::time::sleep(wait_time).await;
-                }
-            }
-        }
+        self.trading_limiter
+            .acquire_cancel(order_id, RateLimitBehavior::Wait)
+            .await
+            .map(|_penalty| ())
     }
 }
 
@@ -252,6 +291,7 @@ impl<C: Clone> Clone for RateLimitedClient<C> {
             private_limiter: self.private_limiter.clone(),
             trading_limiter: self.trading_limiter.clone(),
             orderbook_limiter: self.orderbook_limiter.clone(),
+            futures_limiter: self.futures_limiter.clone(),
         }
     }
 }
@@ -582,12 +622,21 @@ impl<C: KrakenClient> KrakenClient for RateLimitedClient<C> {
             .as_nanos());
 
         self.wait_trading_order(&temp_id, &request.pair).await?;
-        let result = self.inner.add_order(request).await?;
-
-        // Update the trading limiter with the real order ID
-        if let Some(order_id) = result.txid.as_ref().and_then(|ids| ids.first()) {
-            let mut limiter = self.trading_limiter.lock().await;
-            limiter.track_order(order_id.to_string(), OrderTrackingInfo::new(&request.pair));
+        let result = match self.inner.add_order(request).await {
+            Ok(result) => result,
+            Err(e) => {
+                self.trading_limiter.order_closed(&temp_id);
+                return Err(e);
+            }
+        };
+
+        // Update the trading limiter with the real order ID, or stop tracking
+        // if nothing was placed (e.g. a validate-only request)
+        match result.txid.as_ref().and_then(|ids| ids.first()) {
+            Some(order_id) => self
+                .trading_limiter
+                .assign_order_id(&temp_id, order_id.to_string()),
+            None => self.trading_limiter.order_closed(&temp_id),
         }
 
         Ok(result)
@@ -599,7 +648,9 @@ impl<C: KrakenClient> KrakenClient for RateLimitedClient<C> {
     ) -> Result<CancelOrderResponse, KrakenError> {
         // Apply cancellation penalty based on order age
         self.wait_trading_cancel(&request.txid).await?;
-        self.inner.cancel_order(request).await
+        let result = self.inner.cancel_order(request).await?;
+        self.trading_limiter.order_closed(&request.txid);
+        Ok(result)
     }
 
     async fn cancel_all_orders(&self) -> Result<CancelOrderResponse, KrakenError> {
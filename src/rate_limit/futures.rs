@@ -0,0 +1,589 @@
+//! Futures rate limiting.
+//!
+//! Kraken Futures meters private `/derivatives` requests against a points
+//! budget of 500 per 10 seconds, with a cost per endpoint (see
+//! [`DERIVATIVES_COSTS`]). The history API has a separate budget of 100
+//! points per 10 minutes, charged by page size. Public endpoints are limited
+//! by IP and go through the public sliding window of [`RateLimitedClient`].
+//!
+//! [`RateLimitedClient`] wraps any [`FuturesClient`] and charges both budgets
+//! automatically.
+//!
+//! # Example
+//!
+//! ```rust
+//! use kraken_api_client::rate_limit::{FuturesBudget, FuturesRateLimiter, batch_order_cost};
+//!
+//! let mut limiter = FuturesRateLimiter::new();
+//! assert!(limiter.try_acquire(FuturesBudget::Derivatives, batch_order_cost(5)).is_ok());
+//! ```
+
+use std::time::{Duration, Instant};
+
+use crate::error::KrakenError;
+use crate::futures::rest::private;
+use crate::futures::rest::{
+    AccountLogRequest, AccountLogResponse, AccountsResponse, BatchOrderRequest, BatchOrderResponse,
+    CancelAllOrdersAfterResponse, CancelAllOrdersResponse, CancelOrderResponse, CandlesResponse,
+    EditOrderRequest, EditOrderResponse, ExecutionEvent, FillsRequest, FuturesClient, HistoryPage,
+    HistoryRequest, OrderEvent, OrderStatusInfo, OrderStatusRequest, SendOrderRequest,
+    SendOrderResponse, SetPreferenceResponse, TransferRequest, TransferResponse, WithdrawalRequest,
+    WithdrawalResponse,
+};
+use crate::futures::types::{
+    ChartResolution, FundingRateSeries, FuturesFill, FuturesInstrument, FuturesOrder,
+    FuturesOrderBook, FuturesPosition, FuturesTicker, FuturesTrade, LeveragePreference, MarginMode,
+    PnlCurrency, PnlPreference, TickType,
+};
+use crate::rate_limit::RateLimitedClient;
+
+/// Cost of each metered `/derivatives` endpoint, in budget points.
+///
+/// Batch orders are charged per element on top of the listed base cost; see
+/// [`batch_order_cost`]. Endpoints not listed cost [`DEFAULT_DERIVATIVES_COST`].
+pub const DERIVATIVES_COSTS: &[(&str, u32)] = &[
+    (private::SEND_ORDER, 10),
+    (private::EDIT_ORDER, 10),
+    (private::CANCEL_ORDER, 10),
+    (private::BATCH_ORDER, 9),
+    (private::ACCOUNTS, 2),
+    (private::OPEN_POSITIONS, 2),
+    (private::OPEN_ORDERS, 2),
+    (private::FILLS, 2),
+    (private::CANCEL_ALL_ORDERS, 25),
+    (private::CANCEL_ALL_ORDERS_AFTER, 25),
+    (private::TRANSFER, 100),
+    (private::WITHDRAWAL, 100),
+    (private::ORDER_STATUS, 1),
+];
+
+/// Cost of a `/derivatives` endpoint missing from [`DERIVATIVES_COSTS`].
+pub const DEFAULT_DERIVATIVES_COST: u32 = 1;
+
+/// Cost of a fills request with `lastFillTime` set.
+const FILLS_SINCE_COST: u32 = 25;
+
+/// Page size assumed when a history request does not set `count`.
+const DEFAULT_HISTORY_COUNT: u32 = 500;
+
+/// Points budget a futures endpoint draws from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FuturesBudget {
+    /// Private `/derivatives` endpoints: 500 points per 10 seconds
+    Derivatives,
+    /// History API endpoints: 100 points per 10 minutes
+    History,
+}
+
+/// Cost of a `/derivatives` endpoint, from [`DERIVATIVES_COSTS`].
+pub fn derivatives_cost(endpoint: &str) -> u32 {
+    DERIVATIVES_COSTS
+        .iter()
+        .find(|(path, _)| *path == endpoint)
+        .map_or(DEFAULT_DERIVATIVES_COST, |(_, cost)| *cost)
+}
+
+/// Cost of a batch order with `elements` place/edit/cancel instructions.
+pub fn batch_order_cost(elements: usize) -> u32 {
+    derivatives_cost(private::BATCH_ORDER).saturating_add(elements.try_into().unwrap_or(u32::MAX))
+}
+
+/// Cost of a fills request; filtering by `lastFillTime` is more expensive.
+pub fn fills_cost(request: Option<&FillsRequest>) -> u32 {
+    match request {
+        Some(request) if request.last_fill_time.is_some() => FILLS_SINCE_COST,
+        _ => derivatives_cost(private::FILLS),
+    }
+}
+
+/// Cost of a history API request returning up to `count` entries.
+pub fn history_cost(count: Option<u32>) -> u32 {
+    match count.unwrap_or(DEFAULT_HISTORY_COUNT) {
+        0..=25 => 1,
+        26..=50 => 2,
+        51..=1000 => 3,
+        1001..=5000 => 6,
+        _ => 10,
+    }
+}
+
+/// A points budget that refills continuously over a window.
+///
+/// The budget starts full; each request spends its cost and the budget
+/// regains `capacity` points per `window`.
+#[derive(Debug, Clone)]
+pub struct PointsBudget {
+    capacity: f64,
+    refill_per_sec: f64,
+    available: f64,
+    last_update: Instant,
+}
+
+impl PointsBudget {
+    /// Create a full budget of `capacity` points per `window`.
+    pub fn new(capacity: u32, window: Duration) -> Self {
+        Self {
+            capacity: capacity as f64,
+            refill_per_sec: capacity as f64 / window.as_secs_f64(),
+            available: capacity as f64,
+            last_update: Instant::now(),
+        }
+    }
+
+    /// The `/derivatives` budget: 500 points per 10 seconds.
+    pub fn derivatives() -> Self {
+        Self::new(500, Duration::from_secs(10))
+    }
+
+    /// The history API budget: 100 points per 10 minutes.
+    pub fn history() -> Self {
+        Self::new(100, Duration::from_secs(600))
+    }
+
+    /// Points currently available.
+    pub fn available(&mut self) -> u32 {
+        self.update();
+        self.available as u32
+    }
+
+    /// Try to spend `cost` points.
+    ///
+    /// Returns `Err(wait_time)` if the budget is short. Costs above the
+    /// capacity are charged as a full budget.
+    pub fn try_acquire(&mut self, cost: u32) -> Result<(), Duration> {
+        self.update();
+        let cost = (cost as f64).min(self.capacity);
+        if cost <= self.available {
+            self.available -= cost;
+            Ok(())
+        } else {
+            let missing = cost - self.available;
+            Err(Duration::from_secs_f64(missing / self.refill_per_sec))
+        }
+    }
+
+    fn update(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_update).as_secs_f64();
+        self.available = (self.available + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_update = now;
+    }
+}
+
+/// The `/derivatives` and history budgets for one futures API key.
+#[derive(Debug, Clone)]
+pub struct FuturesRateLimiter {
+    derivatives: PointsBudget,
+    history: PointsBudget,
+}
+
+impl FuturesRateLimiter {
+    /// Create a limiter with Kraken's default budgets.
+    pub fn new() -> Self {
+        Self::with_budgets(PointsBudget::derivatives(), PointsBudget::history())
+    }
+
+    /// Create a limiter with custom budgets.
+    pub fn with_budgets(derivatives: PointsBudget, history: PointsBudget) -> Self {
+        Self {
+            derivatives,
+            history,
+        }
+    }
+
+    /// Get a budget.
+    pub fn budget(&mut self, budget: FuturesBudget) -> &mut PointsBudget {
+        match budget {
+            FuturesBudget::Derivatives => &mut self.derivatives,
+            FuturesBudget::History => &mut self.history,
+        }
+    }
+
+    /// Try to spend `cost` points from a budget.
+    ///
+    /// Returns `Err(wait_time)` if the budget is short.
+    pub fn try_acquire(&mut self, budget: FuturesBudget, cost: u32) -> Result<(), Duration> {
+        self.budget(budget).try_acquire(cost)
+    }
+}
+
+impl Default for FuturesRateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+// FuturesClient Trait Implementation
+
+
+impl<C: FuturesClient> FuturesClient for RateLimitedClient<C> {
+    async fn get_tickers(&self) -> Result<Vec<FuturesTicker>, KrakenError> {
+        self.wait_public().await?;
+        self.inner().get_tickers().await
+    }
+
+    async fn get_ticker(&self, symbol: &str) -> Result<Option<FuturesTicker>, KrakenError> {
+        self.wait_public().await?;
+        self.inner().get_ticker(symbol).await
+    }
+
+    async fn get_orderbook(&self, symbol: &str) -> Result<FuturesOrderBook, KrakenError> {
+        self.wait_public().await?;
+        self.inner().get_orderbook(symbol).await
+    }
+
+    async fn get_trade_history(
+        &self,
+        symbol: &str,
+        last_time: Option<&str>,
+    ) -> Result<Vec<FuturesTrade>, KrakenError> {
+        self.wait_public().await?;
+        self.inner().get_trade_history(symbol, last_time).await
+    }
+
+    async fn get_instruments(&self) -> Result<Vec<FuturesInstrument>, KrakenError> {
+        self.wait_public().await?;
+        self.inner().get_instruments().await
+    }
+
+    async fn get_historical_funding_rates(
+        &self,
+        symbol: &str,
+    ) -> Result<FundingRateSeries, KrakenError> {
+        self.wait_public().await?;
+        self.inner().get_historical_funding_rates(symbol).await
+    }
+
+    async fn get_chart_tick_types(&self) -> Result<Vec<String>, KrakenError> {
+        self.wait_public().await?;
+        self.inner().get_chart_tick_types().await
+    }
+
+    async fn get_chart_symbols(&self, tick_type: TickType) -> Result<Vec<String>, KrakenError> {
+        self.wait_public().await?;
+        self.inner().get_chart_symbols(tick_type).await
+    }
+
+    async fn get_chart_resolutions(
+        &self,
+        tick_type: TickType,
+        symbol: &str,
+    ) -> Result<Vec<String>, KrakenError> {
+        self.wait_public().await?;
+        self.inner().get_chart_resolutions(tick_type, symbol).await
+    }
+
+    async fn get_candles(
+        &self,
+        tick_type: TickType,
+        symbol: &str,
+        resolution: ChartResolution,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> Result<CandlesResponse, KrakenError> {
+        self.wait_public().await?;
+        self.inner()
+            .get_candles(tick_type, symbol, resolution, from, to)
+            .await
+    }
+
+    async fn get_accounts(&self) -> Result<AccountsResponse, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::ACCOUNTS),
+        )
+        .await?;
+        self.inner().get_accounts().await
+    }
+
+    async fn get_open_positions(&self) -> Result<Vec<FuturesPosition>, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::OPEN_POSITIONS),
+        )
+        .await?;
+        self.inner().get_open_positions().await
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<FuturesOrder>, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::OPEN_ORDERS),
+        )
+        .await?;
+        self.inner().get_open_orders().await
+    }
+
+    async fn get_fills(
+        &self,
+        request: Option<&FillsRequest>,
+    ) -> Result<Vec<FuturesFill>, KrakenError> {
+        self.wait_futures(FuturesBudget::Derivatives, fills_cost(request))
+            .await?;
+        self.inner().get_fills(request).await
+    }
+
+    async fn send_order(
+        &self,
+        request: &SendOrderRequest,
+    ) -> Result<SendOrderResponse, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::SEND_ORDER),
+        )
+        .await?;
+        self.inner().send_order(request).await
+    }
+
+    async fn get_order_status(
+        &self,
+        request: &OrderStatusRequest,
+    ) -> Result<Vec<OrderStatusInfo>, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::ORDER_STATUS),
+        )
+        .await?;
+        self.inner().get_order_status(request).await
+    }
+
+    async fn edit_order(
+        &self,
+        request: &EditOrderRequest,
+    ) -> Result<EditOrderResponse, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::EDIT_ORDER),
+        )
+        .await?;
+        self.inner().edit_order(request).await
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<CancelOrderResponse, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::CANCEL_ORDER),
+        )
+        .await?;
+        self.inner().cancel_order(order_id).await
+    }
+
+    async fn cancel_order_by_cli_ord_id(
+        &self,
+        cli_ord_id: &str,
+    ) -> Result<CancelOrderResponse, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::CANCEL_ORDER),
+        )
+        .await?;
+        self.inner().cancel_order_by_cli_ord_id(cli_ord_id).await
+    }
+
+    async fn cancel_all_orders(&self) -> Result<CancelAllOrdersResponse, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::CANCEL_ALL_ORDERS),
+        )
+        .await?;
+        self.inner().cancel_all_orders().await
+    }
+
+    async fn cancel_all_orders_for_symbol(
+        &self,
+        symbol: &str,
+    ) -> Result<CancelAllOrdersResponse, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::CANCEL_ALL_ORDERS),
+        )
+        .await?;
+        self.inner().cancel_all_orders_for_symbol(symbol).await
+    }
+
+    async fn cancel_all_orders_after(
+        &self,
+        timeout_seconds: u32,
+    ) -> Result<CancelAllOrdersAfterResponse, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::CANCEL_ALL_ORDERS_AFTER),
+        )
+        .await?;
+        self.inner().cancel_all_orders_after(timeout_seconds).await
+    }
+
+    async fn batch_order(
+        &self,
+        request: &BatchOrderRequest,
+    ) -> Result<BatchOrderResponse, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            batch_order_cost(request.batch_order.len()),
+        )
+        .await?;
+        self.inner().batch_order(request).await
+    }
+
+    async fn get_leverage_preferences(&self) -> Result<Vec<LeveragePreference>, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::LEVERAGE_PREFERENCES),
+        )
+        .await?;
+        self.inner().get_leverage_preferences().await
+    }
+
+    async fn set_leverage_preference(
+        &self,
+        symbol: &str,
+        mode: MarginMode,
+    ) -> Result<SetPreferenceResponse, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::LEVERAGE_PREFERENCES),
+        )
+        .await?;
+        self.inner().set_leverage_preference(symbol, mode).await
+    }
+
+    async fn get_pnl_preferences(&self) -> Result<Vec<PnlPreference>, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::PNL_PREFERENCES),
+        )
+        .await?;
+        self.inner().get_pnl_preferences().await
+    }
+
+    async fn set_pnl_preference(
+        &self,
+        symbol: &str,
+        currency: PnlCurrency,
+    ) -> Result<SetPreferenceResponse, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::PNL_PREFERENCES),
+        )
+        .await?;
+        self.inner().set_pnl_preference(symbol, currency).await
+    }
+
+    async fn transfer(&self, request: &TransferRequest) -> Result<TransferResponse, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::TRANSFER),
+        )
+        .await?;
+        self.inner().transfer(request).await
+    }
+
+    async fn withdraw_to_spot(
+        &self,
+        request: &WithdrawalRequest,
+    ) -> Result<WithdrawalResponse, KrakenError> {
+        self.wait_futures(
+            FuturesBudget::Derivatives,
+            derivatives_cost(private::WITHDRAWAL),
+        )
+        .await?;
+        self.inner().withdraw_to_spot(request).await
+    }
+
+    async fn get_executions(
+        &self,
+        request: &HistoryRequest,
+    ) -> Result<HistoryPage<ExecutionEvent>, KrakenError> {
+        self.wait_futures(FuturesBudget::History, history_cost(request.count))
+            .await?;
+        self.inner().get_executions(request).await
+    }
+
+    async fn get_order_history(
+        &self,
+        request: &HistoryRequest,
+    ) -> Result<HistoryPage<OrderEvent>, KrakenError> {
+        self.wait_futures(FuturesBudget::History, history_cost(request.count))
+            .await?;
+        self.inner().get_order_history(request).await
+    }
+
+    async fn get_trigger_history(
+        &self,
+        request: &HistoryRequest,
+    ) -> Result<HistoryPage<OrderEvent>, KrakenError> {
+        self.wait_futures(FuturesBudget::History, history_cost(request.count))
+            .await?;
+        self.inner().get_trigger_history(request).await
+    }
+
+    async fn get_account_log(
+        &self,
+        request: &AccountLogRequest,
+    ) -> Result<AccountLogResponse, KrakenError> {
+        self.wait_futures(FuturesBudget::History, history_cost(request.count))
+            .await?;
+        self.inner().get_account_log(request).await
+    }
+
+    async fn get_account_log_csv(
+        &self,
+        request: &AccountLogRequest,
+    ) -> Result<String, KrakenError> {
+        self.wait_futures(FuturesBudget::History, history_cost(request.count))
+            .await?;
+        self.inner().get_account_log_csv(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_costs() {
+        assert_eq!(derivatives_cost(private::SEND_ORDER), 10);
+        assert_eq!(derivatives_cost(private::CANCEL_ALL_ORDERS), 25);
+        assert_eq!(derivatives_cost("/api/v3/unknown"), DEFAULT_DERIVATIVES_COST);
+        assert_eq!(batch_order_cost(5), 14);
+        assert_eq!(fills_cost(None), 2);
+
+        let since = FillsRequest {
+            symbol: None,
+            last_fill_time: Some("2024-01-01T00:00:00Z".to_string()),
+        };
+        assert_eq!(fills_cost(Some(&since)), 25);
+
+        assert_eq!(history_cost(Some(25)), 1);
+        assert_eq!(history_cost(None), 3);
+        assert_eq!(history_cost(Some(100_000)), 10);
+    }
+
+    #[test]
+    fn test_points_budget_blocks_and_refills() {
+        let mut budget = PointsBudget::derivatives();
+        for _ in 0..50 {
+            assert!(budget.try_acquire(10).is_ok());
+        }
+
+        // 25 points short at 50 points per second.
+        let wait = budget.try_acquire(25).unwrap_err();
+        assert!(wait > Duration::from_millis(400) && wait <= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_budgets_are_independent() {
+        let mut limiter = FuturesRateLimiter::with_budgets(
+            PointsBudget::new(10, Duration::from_secs(10)),
+            PointsBudget::new(5, Duration::from_secs(600)),
+        );
+        assert!(limiter.try_acquire(FuturesBudget::History, 5).is_ok());
+        assert!(limiter.try_acquire(FuturesBudget::History, 1).is_err());
+        assert!(limiter.try_acquire(FuturesBudget::Derivatives, 10).is_ok());
+
+        // Oversized costs are capped at the budget capacity.
+        let mut budget = PointsBudget::new(10, Duration::from_secs(10));
+        assert!(budget.try_acquire(1000).is_ok());
+    }
+}
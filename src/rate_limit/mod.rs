@@ -7,7 +7,10 @@
 //!
 //! - **Public endpoints**: Limited by IP address (sliding window)
 //! - **Private endpoints**: Limited by API key, varies by verification tier (token bucket)
-//! - **Trading endpoints**: Additional penalties for order placement/cancellation
+//! - **Trading endpoints**: Additional penalties for order placement/cancellation,
+//!   shared between REST and WebSocket via [`SharedTradingLimiter`]
+//! - **Futures endpoints**: Points budgets with a cost per endpoint, plus a
+//!   separate history budget ([`FuturesRateLimiter`])
 //!
 //! ## Example
 //!
@@ -46,12 +49,19 @@
 //! ```
 
 mod client;
+mod futures;
 mod keyed;
+mod shared;
 mod trading;
 mod ttl_cache;
 
 pub use client::RateLimitedClient;
+pub use futures::{
+    batch_order_cost, derivatives_cost, fills_cost, history_cost, FuturesBudget,
+    FuturesRateLimiter, PointsBudget, DEFAULT_DERIVATIVES_COST, DERIVATIVES_COSTS,
+};
 pub use keyed::{KeyedRateLimiter, SlidingWindow};
+pub use shared::{RateLimitBehavior, SharedTradingLimiter};
 pub use trading::{OrderTrackingInfo, PerPairTradingLimiter, TradingRateLimiter};
 pub use ttl_cache::TtlCache;
 
@@ -0,0 +1,502 @@
+//! Trading rate limiter shared between REST and WebSocket order entry.
+//!
+//! Kraken counts order placement and cancellation against a single budget per
+//! API key, regardless of whether the request was sent over REST or WebSocket.
+//! [`SharedTradingLimiter`] is a cloneable handle that can be given to both a
+//! [`RateLimitedClient`](crate::rate_limit::RateLimitedClient) and a
+//! [`KrakenStream`](crate::spot::ws::KrakenStream) so both paths draw from the
+//! same counter.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kraken_api_client::rate_limit::{
+//!     RateLimitBehavior, RateLimitedClient, SharedTradingLimiter,
+//! };
+//! use kraken_api_client::types::VerificationTier;
+//!
+//! let limiter = SharedTradingLimiter::for_tier(VerificationTier::Pro);
+//!
+//! let rest = RateLimitedClient::with_tier(rest_client, VerificationTier::Pro)
+//!     .with_trading_limiter(limiter.clone());
+//!
+//! let mut stream = ws_client.connect_private(&token).await?;
+//! stream.set_trading_limiter(limiter, RateLimitBehavior::Reject);
+//! ```
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::error::KrakenError;
+use crate::rate_limit::{OrderTrackingInfo, PerPairTradingLimiter, TradingRateLimiter, TtlCache};
+use crate::spot::ws::messages::{ExecutionData, ExecutionsMessage};
+use crate::types::VerificationTier;
+use crate::types::common::pair_ws_name;
+
+/// What to do when a trading request would exceed the rate limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateLimitBehavior {
+    /// Sleep until enough capacity is available, then send.
+    #[default]
+    Wait,
+    /// Fail immediately with [`KrakenError::RateLimitExceeded`].
+    Reject,
+}
+
+/// The limiter backing a [`SharedTradingLimiter`].
+#[derive(Debug, Clone)]
+enum Limiter {
+    /// One counter for all pairs.
+    Single(TradingRateLimiter),
+    /// One counter per pair.
+    PerPair(PerPairTradingLimiter),
+}
+
+impl Limiter {
+    /// Limiter charged for `pair`.
+    ///
+    /// REST names ("XBTUSD", "XXBTZUSD") and WebSocket symbols ("BTC/USD")
+    /// of one market share a per-pair counter.
+    fn for_pair(&mut self, pair: &str) -> &mut TradingRateLimiter {
+        match self {
+            Limiter::Single(limiter) => limiter,
+            Limiter::PerPair(limiters) => limiters.limiter_for(&pair_ws_name(pair)),
+        }
+    }
+}
+
+/// How long an order stays tracked without being closed.
+///
+/// Orders missed by both REST and the `executions` channel are forgotten
+/// after this, matching [`TradingRateLimiter`]'s own tracking.
+const ORDER_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone)]
+struct State {
+    limiter: Limiter,
+    /// Open orders keyed by order ID (or a provisional key until the ID is known).
+    orders: TtlCache<String, OrderTrackingInfo>,
+    /// Client order IDs and provisional keys mapped to the key used in `orders`.
+    aliases: TtlCache<String, String>,
+}
+
+impl State {
+    fn resolve(&self, id: &str) -> String {
+        self.aliases
+            .get(&id.to_string())
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    fn insert(&mut self, key: String, info: OrderTrackingInfo) {
+        self.orders.cleanup();
+        self.aliases.cleanup();
+        if let Some(cl_ord_id) = &info.client_order_id {
+            if *cl_ord_id != key {
+                self.aliases.insert(cl_ord_id.clone(), key.clone());
+            }
+        }
+        self.orders.insert(key, info);
+    }
+
+    /// Charge the cancellation of `id` and stop tracking it.
+    fn cancel(&mut self, id: &str) -> Result<u32, Duration> {
+        let key = self.resolve(id);
+        let penalty = match (self.orders.get(&key).cloned(), &mut self.limiter) {
+            (Some(info), limiter) => limiter
+                .for_pair(&info.pair)
+                .try_cancel_order_with_age(&key, info.age())?,
+            (None, Limiter::Single(limiter)) => limiter.try_cancel_order(&key)?,
+            (None, Limiter::PerPair(_)) => {
+                tracing::debug!("Not throttling cancel of untracked order {}", id);
+                0
+            }
+        };
+        self.remove(id);
+        Ok(penalty)
+    }
+
+    fn remove(&mut self, id: &str) -> Option<OrderTrackingInfo> {
+        let key = self.resolve(id);
+        self.aliases.remove(&id.to_string());
+        let info = self.orders.remove(&key)?;
+        // Drop every alias of the order, including provisional keys.
+        self.aliases.retain(|_, target| *target != key);
+        Some(info)
+    }
+}
+
+/// A cloneable trading rate limiter handle.
+///
+/// Tracks open orders so cancellation penalties are charged according to the
+/// real order age. Order ages can be fed from the `executions` channel with
+/// [`apply_executions`](Self::apply_executions), which also covers orders
+/// placed before this process started.
+#[derive(Debug, Clone)]
+pub struct SharedTradingLimiter {
+    state: Arc<Mutex<State>>,
+}
+
+impl SharedTradingLimiter {
+    /// Share a single limiter across all pairs.
+    pub fn new(limiter: TradingRateLimiter) -> Self {
+        Self::from_limiter(Limiter::Single(limiter))
+    }
+
+    /// Share a per-pair limiter.
+    ///
+    /// Cancellations of orders whose pair is unknown are not throttled.
+    pub fn per_pair(limiter: PerPairTradingLimiter) -> Self {
+        Self::from_limiter(Limiter::PerPair(limiter))
+    }
+
+    /// Create a single shared limiter using the limits of a verification tier.
+    pub fn for_tier(tier: VerificationTier) -> Self {
+        let (max_counter, decay_rate) = tier.rate_limit_params();
+        Self::new(TradingRateLimiter::new(max_counter, decay_rate))
+    }
+
+    fn from_limiter(limiter: Limiter) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                limiter,
+                orders: TtlCache::new(ORDER_TTL),
+                aliases: TtlCache::new(ORDER_TTL),
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // The state stays consistent even if a holder panicked.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Try to acquire capacity for placing an order under `key`.
+    ///
+    /// Returns `Err(wait_time)` if rate limited.
+    pub fn try_place_order(&self, key: &str, info: OrderTrackingInfo) -> Result<(), Duration> {
+        let mut state = self.lock();
+        state
+            .limiter
+            .for_pair(&info.pair)
+            .try_place_order(key, info.clone())?;
+        state.insert(key.to_string(), info);
+        Ok(())
+    }
+
+    /// Try to acquire capacity for cancelling an order.
+    ///
+    /// `id` may be an order ID, a client order ID or a provisional key.
+    /// Returns the penalty charged, or `Err(wait_time)` if rate limited.
+    pub fn try_cancel_order(&self, id: &str) -> Result<u32, Duration> {
+        self.lock().cancel(id)
+    }
+
+    /// Try to acquire capacity for cancelling several orders in one request.
+    ///
+    /// Either every cancellation is charged or none is. Returns the total
+    /// penalty charged, or `Err(wait_time)` if rate limited.
+    pub fn try_cancel_orders(&self, ids: &[&str]) -> Result<u32, Duration> {
+        let mut state = self.lock();
+        let before = state.clone();
+        let mut total = 0;
+        for id in ids {
+            match state.cancel(id) {
+                Ok(penalty) => total += penalty,
+                Err(wait_time) => {
+                    *state = before;
+                    return Err(wait_time);
+                }
+            }
+        }
+        Ok(total)
+    }
+
+    /// Wait for (or reject on) placement capacity.
+    pub async fn acquire_place(
+        &self,
+        key: &str,
+        info: OrderTrackingInfo,
+        behavior: RateLimitBehavior,
+    ) -> Result<(), KrakenError> {
+        loop {
+            match self.try_place_order(key, info.clone()) {
+                Ok(()) => return Ok(()),
+                Err(wait_time) => Self::back_off(wait_time, behavior).await?,
+            }
+        }
+    }
+
+    /// Wait for (or reject on) cancellation capacity.
+    pub async fn acquire_cancel(
+        &self,
+        id: &str,
+        behavior: RateLimitBehavior,
+    ) -> Result<u32, KrakenError> {
+        loop {
+            match self.try_cancel_order(id) {
+                Ok(penalty) => return Ok(penalty),
+                Err(wait_time) => Self::back_off(wait_time, behavior).await?,
+            }
+        }
+    }
+
+    /// Wait for (or reject on) capacity to cancel several orders at once.
+    ///
+    /// Nothing is charged if the request is rejected.
+    pub async fn acquire_cancels(
+        &self,
+        ids: &[&str],
+        behavior: RateLimitBehavior,
+    ) -> Result<u32, KrakenError> {
+        loop {
+            match self.try_cancel_orders(ids) {
+                Ok(penalty) => return Ok(penalty),
+                Err(wait_time) => Self::back_off(wait_time, behavior).await?,
+            }
+        }
+    }
+
+    async fn back_off(wait_time: Duration, behavior: RateLimitBehavior) -> Result<(), KrakenError> {
+        match behavior {
+            RateLimitBehavior::Wait => {
+                tokio::time::sleep(wait_time).await;
+                Ok(())
+            }
+            RateLimitBehavior::Reject => Err(KrakenError::RateLimitExceeded {
+                retry_after_ms: Some(wait_time.as_millis() as u64),
+            }),
+        }
+    }
+
+    /// Track an order that was placed without a rate limit check.
+    pub fn track_order(&self, order_id: impl Into<String>, info: OrderTrackingInfo) {
+        let order_id = order_id.into();
+        let mut state = self.lock();
+        state
+            .limiter
+            .for_pair(&info.pair)
+            .track_order(order_id.clone(), info.clone());
+        state.insert(order_id, info);
+    }
+
+    /// Re-key a tracked order once its exchange order ID is known.
+    ///
+    /// The original placement time is preserved.
+    pub fn assign_order_id(&self, key: &str, order_id: impl Into<String>) {
+        let order_id = order_id.into();
+        let mut state = self.lock();
+        if let Some(info) = state.remove(key) {
+            state.insert(order_id.clone(), info);
+            if key != order_id {
+                state.aliases.insert(key.to_string(), order_id);
+            }
+        }
+    }
+
+    /// Forget an order that was filled, cancelled or rejected.
+    pub fn order_closed(&self, id: &str) {
+        let mut state = self.lock();
+        let key = state.resolve(id);
+        if let Some(info) = state.remove(id) {
+            state.limiter.for_pair(&info.pair).order_filled(&key);
+        }
+    }
+
+    /// Update order ages from an `executions` channel message.
+    ///
+    /// Open orders not yet tracked are added with their age derived from the
+    /// execution timestamp. Orders reported as filled, cancelled or expired
+    /// are forgotten.
+    pub fn apply_executions(&self, msg: &ExecutionsMessage) {
+        for execution in &msg.data {
+            self.apply_execution(execution);
+        }
+    }
+
+    fn apply_execution(&self, execution: &ExecutionData) {
+        let closed = ["filled", "canceled", "expired"];
+        if closed.contains(&execution.order_status.as_str())
+            || execution
+                .exec_type
+                .as_deref()
+                .is_some_and(|exec_type| closed.contains(&exec_type))
+        {
+            self.order_closed(&execution.order_id);
+            return;
+        }
+
+        let mut state = self.lock();
+        if state.orders.contains(&execution.order_id) {
+            return;
+        }
+
+        // Orders placed through this limiter are tracked under their client
+        // order ID until the exchange ID is known.
+        if let Some(cl_ord_id) = &execution.cl_ord_id {
+            let key = state.resolve(cl_ord_id);
+            if let Some(info) = state.orders.remove(&key) {
+                state.aliases.insert(key, execution.order_id.clone());
+                state.insert(execution.order_id.clone(), info);
+                return;
+            }
+        }
+
+        let age = execution
+            .timestamp
+            .as_deref()
+            .and_then(|ts| OffsetDateTime::parse(ts, &Rfc3339).ok())
+            .and_then(|ts| Duration::try_from(OffsetDateTime::now_utc() - ts).ok())
+            .unwrap_or_default();
+        let info = OrderTrackingInfo {
+            created_at: Instant::now().checked_sub(age).unwrap_or_else(Instant::now),
+            pair: execution.symbol.clone(),
+            client_order_id: execution.cl_ord_id.clone(),
+        };
+        state.insert(execution.order_id.clone(), info);
+    }
+
+    /// Age of a tracked order, looked up by order ID or client order ID.
+    pub fn order_age(&self, id: &str) -> Option<Duration> {
+        let state = self.lock();
+        state
+            .orders
+            .get(&state.resolve(id))
+            .map(OrderTrackingInfo::age)
+    }
+
+    /// Number of orders currently tracked.
+    pub fn tracked_orders(&self) -> usize {
+        self.lock().orders.active_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn executions(value: serde_json::Value) -> ExecutionsMessage {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_cancel_uses_execution_age() {
+        let limiter = SharedTradingLimiter::new(TradingRateLimiter::new(20, 1.0));
+        let created = OffsetDateTime::now_utc() - time::Duration::seconds(120);
+        limiter.apply_executions(&executions(serde_json::json!({
+            "channel": "executions",
+            "type": "snapshot",
+            "data": [{
+                "order_id": "OABC",
+                "cl_ord_id": "my-1",
+                "symbol": "BTC/USD",
+                "side": "buy",
+                "order_type": "limit",
+                "order_status": "new",
+                "timestamp": created.format(&Rfc3339).unwrap()
+            }]
+        })));
+
+        assert!(limiter.order_age("my-1").unwrap() >= Duration::from_secs(119));
+        assert_eq!(limiter.try_cancel_order("my-1"), Ok(0));
+        assert_eq!(limiter.tracked_orders(), 0);
+    }
+
+    #[test]
+    fn test_untracked_cancel_charges_worst_case() {
+        let limiter = SharedTradingLimiter::new(TradingRateLimiter::new(20, 1.0));
+        assert_eq!(limiter.try_cancel_order("unknown"), Ok(8));
+    }
+
+    #[test]
+    fn test_rejected_batch_cancel_charges_nothing() {
+        let limiter = SharedTradingLimiter::new(TradingRateLimiter::new(20, 0.01));
+
+        // Three untracked cancels at 8 each exceed the budget.
+        assert!(limiter.try_cancel_orders(&["x", "y", "z"]).is_err());
+        assert_eq!(limiter.try_cancel_orders(&["x", "y"]), Ok(16));
+    }
+
+    #[test]
+    fn test_assign_order_id_and_close() {
+        let limiter = SharedTradingLimiter::for_tier(VerificationTier::Pro);
+        limiter
+            .try_place_order("ws-1", OrderTrackingInfo::new("BTC/USD"))
+            .unwrap();
+        limiter.assign_order_id("ws-1", "OABC");
+        assert!(limiter.order_age("OABC").is_some());
+        assert!(limiter.order_age("ws-1").is_some());
+
+        limiter.apply_executions(&executions(serde_json::json!({
+            "channel": "executions",
+            "type": "update",
+            "data": [{
+                "order_id": "OABC",
+                "symbol": "BTC/USD",
+                "side": "buy",
+                "order_type": "limit",
+                "order_status": "filled",
+                "exec_type": "filled"
+            }]
+        })));
+        assert_eq!(limiter.tracked_orders(), 0);
+        assert!(limiter.order_age("ws-1").is_none());
+        assert!(limiter.lock().aliases.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reject_behavior() {
+        let limiter = SharedTradingLimiter::new(TradingRateLimiter::new(1, 0.01));
+        let info = OrderTrackingInfo::new("BTC/USD");
+        limiter
+            .acquire_place("a", info.clone(), RateLimitBehavior::Reject)
+            .await
+            .unwrap();
+
+        let err = limiter
+            .acquire_place("b", info, RateLimitBehavior::Reject)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            KrakenError::RateLimitExceeded {
+                retry_after_ms: Some(_)
+            }
+        ));
+    }
+
+    #[test]
+    fn test_rest_and_ws_names_share_a_pair_budget() {
+        let limiter = SharedTradingLimiter::per_pair(PerPairTradingLimiter::new(2, 0.01));
+        // REST names the market "XBTUSD" or "XXBTZUSD", WebSocket "BTC/USD".
+        assert!(limiter
+            .try_place_order("rest", OrderTrackingInfo::new("XBTUSD"))
+            .is_ok());
+        assert!(limiter
+            .try_place_order("ws", OrderTrackingInfo::new("BTC/USD"))
+            .is_ok());
+        assert!(limiter
+            .try_place_order("rest-key", OrderTrackingInfo::new("XXBTZUSD"))
+            .is_err());
+        assert!(limiter
+            .try_place_order("eth", OrderTrackingInfo::new("ETH/USD"))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_per_pair_limits_are_independent() {
+        let limiter = SharedTradingLimiter::per_pair(PerPairTradingLimiter::new(1, 0.01));
+        assert!(limiter
+            .try_place_order("a", OrderTrackingInfo::new("BTC/USD"))
+            .is_ok());
+        assert!(limiter
+            .try_place_order("b", OrderTrackingInfo::new("BTC/USD"))
+            .is_err());
+        assert!(limiter
+            .try_place_order("c", OrderTrackingInfo::new("ETH/USD"))
+            .is_ok());
+    }
+}
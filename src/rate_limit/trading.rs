@@ -61,7 +61,7 @@ impl OrderTrackingInfo {
 ///
 /// Tracks orders and calculates appropriate rate limit penalties
 /// when they are cancelled based on their age.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TradingRateLimiter {
     /// Order tracking cache (orders expire after 5 minutes)
     orders: TtlCache<String, OrderTrackingInfo>,
@@ -165,6 +165,22 @@ impl TradingRateLimiter {
             trading::CANCEL_PENALTY_UNDER_5S
         };
 
+        self.charge_cancel(penalty)
+    }
+
+    /// Try to cancel an order whose age is known to the caller.
+    ///
+    /// Use this when the order age comes from an external source (e.g. the
+    /// `executions` channel) rather than from this limiter's own tracking.
+    pub fn try_cancel_order_with_age(&mut self, order_id: &str, age: Duration) -> Result<u32, Duration> {
+        self.update_counter();
+        let penalty = self.charge_cancel(Self::cancel_penalty(age))?;
+        self.orders.remove(&order_id.to_string());
+        Ok(penalty)
+    }
+
+    /// Charge a cancellation penalty against the counter.
+    fn charge_cancel(&mut self, penalty: u32) -> Result<u32, Duration> {
         let cost = (penalty as i64) * 100;
 
         if self.counter + cost <= self.max_counter {
@@ -233,7 +249,7 @@ impl Default for TradingRateLimiter {
 /// Per-pair trading rate limiter.
 ///
 /// Maintains separate rate limits for each trading pair.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct PerPairTradingLimiter {
     limiters: HashMap<String, TradingRateLimiter>,
     max_counter: u32,
@@ -324,6 +340,18 @@ mod tests {
         assert!(after < initial);
     }
 
+    #[test]
+    fn test_cancel_with_known_age() {
+        let mut limiter = TradingRateLimiter::new(20, 1.0);
+        limiter.track_order("order1", OrderTrackingInfo::new("BTC/USD"));
+
+        assert_eq!(
+            limiter.try_cancel_order_with_age("order1", Duration::from_secs(120)),
+            Ok(0)
+        );
+        assert_eq!(limiter.tracked_orders(), 0);
+    }
+
     #[test]
     fn test_order_info_age() {
         let info = OrderTrackingInfo::new("BTC/USD");
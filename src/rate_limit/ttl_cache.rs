@@ -31,7 +31,7 @@ use std::time::{Duration, Instant};
 ///
 /// This is useful for tracking order lifetimes in rate limiting, where
 /// orders cancelled within certain time windows incur different penalties.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TtlCache<K, V> {
     cache: HashMap<K, (V, Instant)>,
     ttl: Duration,
@@ -156,6 +156,15 @@ where
         self.cache.retain(|_, (_, timestamp)| timestamp.elapsed() < ttl);
     }
 
+    /// Keep only the entries for which `f` returns `true`.
+    ///
+    /// Expired entries are dropped regardless.
+    pub fn retain(&mut self, mut f: impl FnMut(&K, &V) -> bool) {
+        let ttl = self.ttl;
+        self.cache
+            .retain(|key, (value, timestamp)| timestamp.elapsed() < ttl && f(key, value));
+    }
+
     /// Get the number of entries in the cache (including expired ones).
     pub fn len(&self) -> usize {
         self.cache.len()
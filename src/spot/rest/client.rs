@@ -1,16 +1,20 @@
 //! Kraken Spot REST API client implementation.
 
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 
+use reqwest::StatusCode;
 use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue, USER_AGENT};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
 use reqwest_tracing::TracingMiddleware;
 use rust_decimal::Decimal;
 
-use crate::auth::{CredentialsProvider, IncreasingNonce, NonceProvider, sign_request};
-use crate::error::{ApiError, KrakenError};
+use crate::auth::{
+    Credentials, CredentialsFile, CredentialsProvider, IncreasingNonce, NonceProvider,
+    report_rejected_nonce, sign_request,
+};
+use crate::error::{ApiError, KrakenError, KrakenErrorCode};
 use crate::spot::rest::endpoints::KRAKEN_BASE_URL;
 use crate::spot::rest::private::{
     AddOrderRequest, AddOrderResponse, AllocationStatus, CancelOrderRequest, CancelOrderResponse,
@@ -29,12 +33,14 @@ use crate::spot::rest::public::{
     OrderBook, OrderBookRequest, RecentSpreadsRequest, RecentSpreadsResponse, RecentTradesRequest,
     RecentTradesResponse, ServerTime, SystemStatus, TickerInfo,
 };
+use crate::spot::rest::retry::{RetryPolicy, is_rate_limit};
 use crate::spot::rest::traits::KrakenClient;
 
 /// The Kraken Spot REST API client.
 ///
 /// This client provides access to all Kraken Spot trading REST endpoints.
-/// It handles authentication, rate limiting, and automatic retries.
+/// It handles authentication, rate limiting, and retries according to its
+/// [`RetryPolicy`].
 ///
 /// # Example
 ///
@@ -80,6 +86,7 @@ pub struct SpotRestClient {
     base_url: String,
     credentials: Option<Arc<dyn CredentialsProvider>>,
     nonce_provider: Arc<dyn NonceProvider>,
+    retry_policy: Arc<RetryPolicy>,
 }
 
 impl SpotRestClient {
@@ -96,14 +103,54 @@ impl SpotRestClient {
         SpotRestClientBuilder::new()
     }
 
+    /// The retry policy applied to requests.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Run a request, resending it while the retry policy allows.
+    ///
+    /// `send` is told whether an earlier attempt failed ambiguously, that is,
+    /// in a way that does not rule out its execution.
+    async fn with_retry<T, F, Fut>(&self, endpoint: &str, mut send: F) -> Result<T, KrakenError>
+    where
+        F: FnMut(bool) -> Fut,
+        Fut: Future<Output = Result<T, KrakenError>>,
+    {
+        let mut attempt = 0;
+        let mut ambiguous = false;
+        loop {
+            let error = match send(ambiguous).await {
+                Err(error) => error,
+                result => return result,
+            };
+            let Some(delay) = self.retry_policy.retry_delay(endpoint, &error, attempt) else {
+                return Err(error);
+            };
+            ambiguous |= !is_rate_limit(&error);
+            tracing::debug!(
+                "Retrying {} in {:?} after attempt {} failed: {}",
+                endpoint,
+                delay,
+                attempt + 1,
+                error
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
     /// Make a public GET request.
     pub(crate) async fn public_get<T>(&self, endpoint: &str) -> Result<T, KrakenError>
     where
         T: serde::de::DeserializeOwned,
     {
         let url = format!("{}{}", self.base_url, endpoint);
-        let response = self.http_client.get(&url).send().await?;
-        self.parse_response(response).await
+        self.with_retry(endpoint, |_| async {
+            let response = self.http_client.get(&url).send().await?;
+            self.parse_response(response).await
+        })
+        .await
     }
 
     /// Make a public GET request with query parameters.
@@ -123,11 +170,16 @@ impl SpotRestClient {
         } else {
             format!("{}{}?{}", self.base_url, endpoint, query_string)
         };
-        let response = self.http_client.get(&url).send().await?;
-        self.parse_response(response).await
+        self.with_retry(endpoint, |_| async {
+            let response = self.http_client.get(&url).send().await?;
+            self.parse_response(response).await
+        })
+        .await
     }
 
     /// Make an authenticated POST request.
+    ///
+    /// Each attempt is signed with a fresh nonce.
     pub(crate) async fn private_post<T, P>(
         &self,
         endpoint: &str,
@@ -137,14 +189,102 @@ impl SpotRestClient {
         T: serde::de::DeserializeOwned,
         P: serde::Serialize,
     {
-        let credentials = self
+        self.with_retry(endpoint, |_| self.private_post_checked(endpoint, params))
+            .await
+    }
+
+    /// Make an authenticated POST request that cancels orders.
+    ///
+    /// A resend that finds the order unknown after an ambiguous failure is
+    /// reported as one cancelled order, since the earlier attempt most likely
+    /// cancelled it.
+    pub(crate) async fn private_cancel<P>(
+        &self,
+        endpoint: &str,
+        params: &P,
+    ) -> Result<CancelOrderResponse, KrakenError>
+    where
+        P: serde::Serialize,
+    {
+        self.with_retry(endpoint, |ambiguous| async move {
+            match self.private_post_checked(endpoint, params).await {
+                Err(KrakenError::Api(error))
+                    if ambiguous && error.error_code() == KrakenErrorCode::UnknownOrder =>
+                {
+                    tracing::debug!(
+                        "Treating unknown order on resent {} as cancelled by an earlier attempt",
+                        endpoint
+                    );
+                    Ok(CancelOrderResponse {
+                        count: 1,
+                        pending: None,
+                    })
+                }
+                result => result,
+            }
+        })
+        .await
+    }
+
+    /// Send an authenticated POST request, recovering once from a rejected
+    /// nonce if the retry policy allows.
+    async fn private_post_checked<T, P>(&self, endpoint: &str, params: &P) -> Result<T, KrakenError>
+    where
+        T: serde::de::DeserializeOwned,
+        P: serde::Serialize,
+    {
+        // Fetch credentials before taking a nonce, so a slow provider cannot
+        // reorder nonces between concurrent requests.
+        let credentials = self.current_credentials().await?;
+        let nonce = self.nonce_provider.next_nonce();
+        match self
+            .private_post_once(endpoint, params, &credentials, nonce)
+            .await
+        {
+            Err(KrakenError::Api(error)) if error.is_invalid_nonce() => {
+                report_rejected_nonce(endpoint, nonce);
+                if !self.retry_policy.recovers_invalid_nonce() {
+                    return Err(KrakenError::Api(error));
+                }
+                // The request was rejected before execution, so resending
+                // with a higher nonce cannot duplicate it.
+                self.nonce_provider.advance_past(nonce);
+                let retry_nonce = self.nonce_provider.next_nonce();
+                let result = self
+                    .private_post_once(endpoint, params, &credentials, retry_nonce)
+                    .await;
+                if let Err(KrakenError::Api(error)) = &result {
+                    if error.is_invalid_nonce() {
+                        report_rejected_nonce(endpoint, retry_nonce);
+                    }
+                }
+                result
+            }
+            result => result,
+        }
+    }
+
+    /// Current credentials from the provider.
+    async fn current_credentials(&self) -> Result<Arc<Credentials>, KrakenError> {
+        let provider = self
             .credentials
             .as_ref()
             .ok_or(KrakenError::MissingCredentials)?;
+        provider.get_credentials().await
+    }
 
-        let nonce = self.nonce_provider.next_nonce();
-        let creds = credentials.get_credentials();
-
+    /// Sign and send one authenticated POST request with the given nonce.
+    async fn private_post_once<T, P>(
+        &self,
+        endpoint: &str,
+        params: &P,
+        creds: &Credentials,
+        nonce: u64,
+    ) -> Result<T, KrakenError>
+    where
+        T: serde::de::DeserializeOwned,
+        P: serde::Serialize,
+    {
         // Build the POST body with nonce.
         let mut form_data = serde_urlencoded::to_string(params)
             .map_err(|e| KrakenError::InvalidResponse(e.to_string()))?;
@@ -154,6 +294,11 @@ impl SpotRestClient {
         } else {
             form_data = format!("nonce={}&{}", nonce, form_data);
         }
+        if let Some(otp) = creds.expose_otp() {
+            let otp = serde_urlencoded::to_string([("otp", otp)])
+                .map_err(|e| KrakenError::InvalidResponse(e.to_string()))?;
+            form_data = format!("{}&{}", form_data, otp);
+        }
 
         // Sign the request.
         let signature = sign_request(creds, endpoint, nonce, &form_data)?;
@@ -178,12 +323,29 @@ impl SpotRestClient {
         T: serde::de::DeserializeOwned,
     {
         let status = response.status();
+
+        // Surface gateway failures and throttling as HTTP errors so the retry
+        // policy can classify them, unless Kraken explains them in the body.
+        let http_error = if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+            response.error_for_status_ref().err()
+        } else {
+            None
+        };
         let body = response.text().await?;
 
         // Kraken always returns 200 even for errors, so parse the JSON response.
-        let parsed: KrakenResponse<T> = serde_json::from_str(&body).map_err(|e| {
-            KrakenError::InvalidResponse(format!("Failed to parse response: {}. Body: {}", e, body))
-        })?;
+        let parsed: KrakenResponse<T> = match serde_json::from_str(&body) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                return Err(match http_error {
+                    Some(error) => error.into(),
+                    None => KrakenError::InvalidResponse(format!(
+                        "Failed to parse response: {}. Body: {}",
+                        e, body
+                    )),
+                });
+            }
+        };
 
         // Check for API errors.
         if !parsed.error.is_empty() {
@@ -196,6 +358,9 @@ impl SpotRestClient {
                 return Err(KrakenError::Api(api_error));
             }
         }
+        if let Some(error) = http_error {
+            return Err(error.into());
+        }
 
         // Return the result.
         parsed.result.ok_or_else(|| {
@@ -229,7 +394,7 @@ pub struct SpotRestClientBuilder {
     credentials: Option<Arc<dyn CredentialsProvider>>,
     nonce_provider: Option<Arc<dyn NonceProvider>>,
     user_agent: Option<String>,
-    max_retries: u32,
+    retry_policy: RetryPolicy,
 }
 
 impl SpotRestClientBuilder {
@@ -240,7 +405,7 @@ impl SpotRestClientBuilder {
             credentials: None,
             nonce_provider: None,
             user_agent: None,
-            max_retries: 3,
+            retry_policy: RetryPolicy::new(),
         }
     }
 
@@ -256,6 +421,15 @@ impl SpotRestClientBuilder {
         self
     }
 
+    /// Use the spot key of a profile from the default credentials file.
+    ///
+    /// See [`CredentialsFile`] for the file format and location.
+    pub fn profile(self, name: &str) -> Result<Self, KrakenError> {
+        let file = CredentialsFile::load_default()?;
+        let credentials = file.profile(name)?.spot()?;
+        Ok(self.credentials(Arc::new(credentials)))
+    }
+
     /// Set a custom nonce provider.
     pub fn nonce_provider(mut self, provider: Arc<dyn NonceProvider>) -> Self {
         self.nonce_provider = Some(provider);
@@ -270,7 +444,13 @@ impl SpotRestClientBuilder {
 
     /// Set the maximum number of retries for transient failures.
     pub fn max_retries(mut self, retries: u32) -> Self {
-        self.max_retries = retries;
+        self.retry_policy = self.retry_policy.max_retries(retries);
+        self
+    }
+
+    /// Set the retry policy.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
         self
     }
 
@@ -291,11 +471,9 @@ impl SpotRestClientBuilder {
             .build()
             .unwrap_or_else(|_| reqwest::Client::new());
 
-        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(self.max_retries);
-
+        // Retries are handled per endpoint by the client, not the middleware.
         let client = ClientBuilder::new(reqwest_client)
             .with(TracingMiddleware::default())
-            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
             .build();
 
         let nonce_provider = self
@@ -307,6 +485,7 @@ impl SpotRestClientBuilder {
             base_url: self.base_url,
             credentials: self.credentials,
             nonce_provider,
+            retry_policy: Arc::new(self.retry_policy),
         }
     }
 }
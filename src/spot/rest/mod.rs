@@ -23,8 +23,10 @@ mod client;
 mod endpoints;
 pub mod private;
 pub mod public;
+mod retry;
 mod traits;
 
 pub use client::{SpotRestClient, SpotRestClientBuilder};
 pub use endpoints::*;
+pub use retry::{EndpointRetry, RetryPolicy};
 pub use traits::{KrakenClient, KrakenClientExt};
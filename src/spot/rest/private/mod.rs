@@ -6,10 +6,18 @@ mod types;
 
 pub use types::*;
 
+use std::time::Duration;
+
+use time::OffsetDateTime;
+
 use crate::error::KrakenError;
 use crate::spot::rest::SpotRestClient;
 use crate::spot::rest::endpoints::private;
 
+/// Allowance for clock skew when querying closed orders opened since the
+/// first attempt.
+const SUBMIT_CLOCK_SLACK: Duration = Duration::from_secs(30);
+
 impl SpotRestClient {
     /// Get account balance.
     ///
@@ -342,6 +350,13 @@ impl SpotRestClient {
 
     /// Add a new order.
     ///
+    /// If the outcome is unknown (timeout, connection drop or a retryable
+    /// server error) and the order carries a `cl_ord_id`, the open and closed
+    /// orders are checked for it before resending, as the
+    /// [`RetryPolicy`](crate::spot::rest::RetryPolicy) allows. Orders without
+    /// one are never resent: a `userref` is shared by many orders, so it
+    /// cannot tell whether this one landed.
+    ///
     /// # Example
     ///
     /// ```rust,no_run
@@ -375,7 +390,75 @@ impl SpotRestClient {
         &self,
         request: &AddOrderRequest,
     ) -> Result<AddOrderResponse, KrakenError> {
-        self.private_post(private::ADD_ORDER, request).await
+        let Some(cl_ord_id) = request.cl_ord_id.as_deref() else {
+            return self.private_post(private::ADD_ORDER, request).await;
+        };
+
+        let submitted = OffsetDateTime::now_utc();
+        let mut attempt = 0;
+        loop {
+            let error = match self.private_post(private::ADD_ORDER, request).await {
+                Err(error) => error,
+                result => return result,
+            };
+            let Some(delay) = self
+                .retry_policy()
+                .verify_delay(private::ADD_ORDER, &error, attempt)
+            else {
+                return Err(error);
+            };
+            tokio::time::sleep(delay).await;
+
+            // If the lookup itself fails the outcome stays unknown, so report
+            // the original error rather than risk a duplicate.
+            match self.find_submitted_order(cl_ord_id, submitted).await {
+                Ok(Some(response)) => return Ok(response),
+                Ok(None) => {}
+                Err(_) => return Err(error),
+            }
+            tracing::debug!(
+                "Order not found after attempt {} failed, resending: {}",
+                attempt + 1,
+                error
+            );
+            attempt += 1;
+        }
+    }
+
+    /// Look for an order submitted by an earlier attempt with `cl_ord_id`.
+    async fn find_submitted_order(
+        &self,
+        cl_ord_id: &str,
+        submitted: OffsetDateTime,
+    ) -> Result<Option<AddOrderResponse>, KrakenError> {
+        let open = self
+            .get_open_orders(Some(&OpenOrdersRequest {
+                cl_ord_id: Some(cl_ord_id.to_string()),
+                ..Default::default()
+            }))
+            .await?;
+        let closed = self
+            .get_closed_orders(Some(&ClosedOrdersRequest {
+                cl_ord_id: Some(cl_ord_id.to_string()),
+                start: Some((submitted - SUBMIT_CLOCK_SLACK).unix_timestamp()),
+                ..Default::default()
+            }))
+            .await?;
+
+        // The server filters by client order ID already; check again in case
+        // it ignored the filter.
+        let found = open
+            .open
+            .into_iter()
+            .chain(closed.closed)
+            .find(|(_, order)| order.cl_ord_id.as_deref() == Some(cl_ord_id));
+        Ok(found.map(|(txid, order)| AddOrderResponse {
+            descr: AddOrderDescription {
+                order: order.descr.order,
+                close: order.descr.close,
+            },
+            txid: Some(vec![txid]),
+        }))
     }
 
     /// Cancel an order.
@@ -383,7 +466,7 @@ impl SpotRestClient {
         &self,
         request: &CancelOrderRequest,
     ) -> Result<CancelOrderResponse, KrakenError> {
-        self.private_post(private::CANCEL_ORDER, request).await
+        self.private_cancel(private::CANCEL_ORDER, request).await
     }
 
     /// Cancel all open orders.
@@ -404,3 +487,4 @@ impl SpotRestClient {
             .await
     }
 }
+
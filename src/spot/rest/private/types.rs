@@ -2,10 +2,17 @@
 
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-
-use crate::types::serde_helpers::{empty_string_as_none, maybe_decimal};
-use crate::types::{BuySell, LedgerType, OrderStatus, OrderType};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Duration;
+use time::OffsetDateTime;
+
+use crate::error::KrakenError;
+use crate::types::serde_helpers::{empty_string_as_none, maybe_decimal, optional_comma_separated};
+use crate::types::{
+    BuySell, LedgerType, OrderFlag, OrderStatus, OrderType, SelfTradePrevent, TimeInForce,
+    TriggerType,
+};
+use crate::validation::OrderViolation;
 
 /// Extended balance information.
 #[derive(Debug, Clone, Deserialize)]
@@ -77,6 +84,9 @@ pub struct OpenOrdersRequest {
     /// Restrict to given user reference ID.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub userref: Option<i64>,
+    /// Restrict to given client order ID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cl_ord_id: Option<String>,
 }
 
 /// Open orders response.
@@ -95,6 +105,9 @@ pub struct ClosedOrdersRequest {
     /// Restrict to given user reference ID.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub userref: Option<i64>,
+    /// Restrict to given client order ID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cl_ord_id: Option<String>,
     /// Start timestamp.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub start: Option<i64>,
@@ -151,6 +164,9 @@ pub struct Order {
     /// User reference ID.
     #[serde(default)]
     pub userref: Option<i64>,
+    /// Client order ID.
+    #[serde(default)]
+    pub cl_ord_id: Option<String>,
     /// Status of order.
     pub status: OrderStatus,
     /// Open timestamp.
@@ -222,6 +238,15 @@ pub struct OrderDescription {
     pub close: Option<String>,
 }
 
+impl OrderDescription {
+    /// Conditional close attached to the order, if any.
+    pub fn conditional_close(&self) -> Option<ConditionalClose> {
+        self.close
+            .as_deref()
+            .and_then(ConditionalClose::from_description)
+    }
+}
+
 /// Request for trades history.
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct TradesHistoryRequest {
@@ -447,6 +472,203 @@ pub struct FeeInfo {
     pub tiervolume: Option<Decimal>,
 }
 
+/// Scheduled start or expiration time of an order.
+///
+/// Sent as `0` (now), `+<seconds>` (relative to when the order is received)
+/// or a Unix timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderTime {
+    /// Immediately.
+    Now,
+    /// At an absolute time.
+    At(OffsetDateTime),
+    /// A whole number of seconds after the order is received.
+    After(Duration),
+}
+
+impl std::fmt::Display for OrderTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderTime::Now => write!(f, "0"),
+            OrderTime::At(time) => write!(f, "{}", time.unix_timestamp()),
+            OrderTime::After(offset) => write!(f, "+{}", offset.as_secs()),
+        }
+    }
+}
+
+impl std::str::FromStr for OrderTime {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "0" {
+            return Ok(OrderTime::Now);
+        }
+        if let Some(seconds) = s.strip_prefix('+') {
+            let seconds: u64 = seconds
+                .parse()
+                .map_err(|_| format!("invalid relative time: {}", s))?;
+            return Ok(OrderTime::After(Duration::from_secs(seconds)));
+        }
+        let timestamp: i64 = s.parse().map_err(|_| format!("invalid time: {}", s))?;
+        OffsetDateTime::from_unix_timestamp(timestamp)
+            .map(OrderTime::At)
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl Serialize for OrderTime {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for OrderTime {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Conditional close order attached to an opening order.
+///
+/// The constructors only allow close types Kraken accepts, each with the
+/// prices it needs. Trailing-stop closes take relative offsets and are not
+/// modelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConditionalClose {
+    ordertype: OrderType,
+    price: Decimal,
+    price2: Option<Decimal>,
+}
+
+impl ConditionalClose {
+    /// Close with a limit order at `price`.
+    pub fn limit(price: Decimal) -> Self {
+        Self {
+            ordertype: OrderType::Limit,
+            price,
+            price2: None,
+        }
+    }
+
+    /// Close with a market order once `trigger` is reached.
+    pub fn stop_loss(trigger: Decimal) -> Self {
+        Self {
+            ordertype: OrderType::StopLoss,
+            price: trigger,
+            price2: None,
+        }
+    }
+
+    /// Close with a market order once the profit target `trigger` is reached.
+    pub fn take_profit(trigger: Decimal) -> Self {
+        Self {
+            ordertype: OrderType::TakeProfit,
+            price: trigger,
+            price2: None,
+        }
+    }
+
+    /// Close with a limit order at `limit` once `trigger` is reached.
+    pub fn stop_loss_limit(trigger: Decimal, limit: Decimal) -> Self {
+        Self {
+            ordertype: OrderType::StopLossLimit,
+            price: trigger,
+            price2: Some(limit),
+        }
+    }
+
+    /// Close with a limit order at `limit` once the profit target `trigger`
+    /// is reached.
+    pub fn take_profit_limit(trigger: Decimal, limit: Decimal) -> Self {
+        Self {
+            ordertype: OrderType::TakeProfitLimit,
+            price: trigger,
+            price2: Some(limit),
+        }
+    }
+
+    /// Build a close from loose parts, checking that the combination is valid.
+    ///
+    /// Fails with [`KrakenError::InvalidOrder`] for unsupported close types or
+    /// a wrong number of prices.
+    pub fn new(
+        ordertype: OrderType,
+        price: Decimal,
+        price2: Option<Decimal>,
+    ) -> Result<Self, KrakenError> {
+        match (ordertype, price2) {
+            (OrderType::Limit, None) => Ok(Self::limit(price)),
+            (OrderType::StopLoss, None) => Ok(Self::stop_loss(price)),
+            (OrderType::TakeProfit, None) => Ok(Self::take_profit(price)),
+            (OrderType::StopLossLimit, Some(limit)) => Ok(Self::stop_loss_limit(price, limit)),
+            (OrderType::TakeProfitLimit, Some(limit)) => Ok(Self::take_profit_limit(price, limit)),
+            _ => Err(KrakenError::InvalidOrder(vec![
+                OrderViolation::InvalidClose { ordertype, price2 },
+            ])),
+        }
+    }
+
+    /// Order type of the close.
+    pub fn ordertype(&self) -> OrderType {
+        self.ordertype
+    }
+
+    /// Limit price, or trigger price for stop-loss and take-profit closes.
+    pub fn price(&self) -> Decimal {
+        self.price
+    }
+
+    /// Limit price for stop-loss-limit and take-profit-limit closes.
+    pub fn price2(&self) -> Option<Decimal> {
+        self.price2
+    }
+
+    /// Parse a close description such as
+    /// `"close position @ stop loss 27000.0 -> limit 26900.0"`.
+    ///
+    /// Returns `None` for empty descriptions, relative prices and close types
+    /// that are not modelled.
+    pub fn from_description(description: &str) -> Option<Self> {
+        let (_, order) = description.split_once(" @ ")?;
+        let order = match order.find(" with ") {
+            Some(end) => &order[..end],
+            None => order,
+        };
+
+        let (primary, secondary) = match order.split_once(" -> ") {
+            Some((primary, secondary)) => (primary, Some(secondary)),
+            None => (order, None),
+        };
+        let limit = match secondary {
+            Some(secondary) => Some(parse_price(secondary.trim().strip_prefix("limit ")?)?),
+            None => None,
+        };
+
+        let primary = primary.trim();
+        let (kind, price) = primary.rsplit_once(' ')?;
+        let price = parse_price(price)?;
+        match (kind, limit) {
+            ("limit", None) => Some(Self::limit(price)),
+            ("stop loss", None) => Some(Self::stop_loss(price)),
+            ("take profit", None) => Some(Self::take_profit(price)),
+            ("stop loss", Some(limit)) => Some(Self::stop_loss_limit(price, limit)),
+            ("take profit", Some(limit)) => Some(Self::take_profit_limit(price, limit)),
+            _ => None,
+        }
+    }
+}
+
+/// Parse an absolute price, rejecting relative (`+`, `-`, `#`, `%`) forms.
+fn parse_price(s: &str) -> Option<Decimal> {
+    let s = s.trim();
+    if !s.starts_with(|c: char| c.is_ascii_digit()) || s.ends_with('%') {
+        return None;
+    }
+    s.parse().ok()
+}
+
 /// Request to add an order.
 #[derive(Debug, Clone, Serialize)]
 pub struct AddOrderRequest {
@@ -470,31 +692,37 @@ pub struct AddOrderRequest {
     pub price2: Option<Decimal>,
     /// Price type for triggered orders.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub trigger: Option<String>,
+    pub trigger: Option<TriggerType>,
     /// Leverage.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub leverage: Option<String>,
+    pub leverage: Option<u32>,
     /// Reduce only flag.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reduce_only: Option<bool>,
     /// Self trade prevention.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub stptype: Option<String>,
-    /// Order flags (comma-separated).
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub oflags: Option<String>,
+    pub stptype: Option<SelfTradePrevent>,
+    /// Order flags (sent comma-separated).
+    #[serde(
+        with = "optional_comma_separated",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub oflags: Option<BTreeSet<OrderFlag>>,
     /// Time in force.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub timeinforce: Option<String>,
+    pub timeinforce: Option<TimeInForce>,
     /// Scheduled start time.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub starttm: Option<String>,
+    pub starttm: Option<OrderTime>,
     /// Expiration time.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub expiretm: Option<String>,
+    pub expiretm: Option<OrderTime>,
     /// User reference ID.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub userref: Option<i64>,
+    /// Client order ID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cl_ord_id: Option<String>,
     /// Validate only (don't submit).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub validate: Option<bool>,
@@ -507,6 +735,11 @@ pub struct AddOrderRequest {
     /// Close order secondary price.
     #[serde(rename = "close[price2]", skip_serializing_if = "Option::is_none")]
     pub close_price2: Option<Decimal>,
+    /// Extra parameters sent as-is, for options not modelled above.
+    ///
+    /// Keys must not repeat a typed field.
+    #[serde(flatten)]
+    pub raw: BTreeMap<String, String>,
 }
 
 impl AddOrderRequest {
@@ -534,10 +767,12 @@ impl AddOrderRequest {
             starttm: None,
             expiretm: None,
             userref: None,
+            cl_ord_id: None,
             validate: None,
             close_ordertype: None,
             close_price: None,
             close_price2: None,
+            raw: BTreeMap::new(),
         }
     }
 
@@ -553,9 +788,27 @@ impl AddOrderRequest {
         self
     }
 
+    /// Set the price type that triggers the order.
+    pub fn trigger(mut self, trigger: TriggerType) -> Self {
+        self.trigger = Some(trigger);
+        self
+    }
+
     /// Set leverage.
-    pub fn leverage(mut self, leverage: impl Into<String>) -> Self {
-        self.leverage = Some(leverage.into());
+    pub fn leverage(mut self, leverage: u32) -> Self {
+        self.leverage = Some(leverage);
+        self
+    }
+
+    /// Set as reduce only.
+    pub fn reduce_only(mut self, reduce_only: bool) -> Self {
+        self.reduce_only = Some(reduce_only);
+        self
+    }
+
+    /// Set self trade prevention.
+    pub fn self_trade_prevention(mut self, stp: SelfTradePrevent) -> Self {
+        self.stptype = Some(stp);
         self
     }
 
@@ -571,21 +824,58 @@ impl AddOrderRequest {
         self
     }
 
-    /// Set order flags.
-    pub fn oflags(mut self, flags: impl Into<String>) -> Self {
-        self.oflags = Some(flags.into());
+    /// Set client order ID.
+    pub fn cl_ord_id(mut self, cl_ord_id: impl Into<String>) -> Self {
+        self.cl_ord_id = Some(cl_ord_id.into());
         self
     }
 
-    /// Set as post-only order.
-    pub fn post_only(mut self) -> Self {
-        self.oflags = Some("post".to_string());
+    /// Set order flags, replacing any already set.
+    pub fn oflags(mut self, flags: impl IntoIterator<Item = OrderFlag>) -> Self {
+        self.oflags = Some(flags.into_iter().collect());
         self
     }
 
+    /// Add an order flag.
+    pub fn flag(mut self, flag: OrderFlag) -> Self {
+        self.oflags.get_or_insert_with(BTreeSet::new).insert(flag);
+        self
+    }
+
+    /// Set as post-only order.
+    pub fn post_only(self) -> Self {
+        self.flag(OrderFlag::Post)
+    }
+
     /// Set time in force.
-    pub fn time_in_force(mut self, tif: impl Into<String>) -> Self {
-        self.timeinforce = Some(tif.into());
+    pub fn time_in_force(mut self, tif: TimeInForce) -> Self {
+        self.timeinforce = Some(tif);
+        self
+    }
+
+    /// Set the scheduled start time.
+    pub fn start_time(mut self, time: OrderTime) -> Self {
+        self.starttm = Some(time);
+        self
+    }
+
+    /// Set the expiration time (use with [`TimeInForce::GTD`]).
+    pub fn expire_time(mut self, time: OrderTime) -> Self {
+        self.expiretm = Some(time);
+        self
+    }
+
+    /// Attach a conditional close order.
+    pub fn close(mut self, close: ConditionalClose) -> Self {
+        self.close_ordertype = Some(close.ordertype);
+        self.close_price = Some(close.price);
+        self.close_price2 = close.price2;
+        self
+    }
+
+    /// Send an extra parameter as-is.
+    pub fn raw_param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.raw.insert(key.into(), value.into());
         self
     }
 }
@@ -610,6 +900,15 @@ pub struct AddOrderDescription {
     pub close: Option<String>,
 }
 
+impl AddOrderDescription {
+    /// Conditional close attached to the order, if any.
+    pub fn conditional_close(&self) -> Option<ConditionalClose> {
+        self.close
+            .as_deref()
+            .and_then(ConditionalClose::from_description)
+    }
+}
+
 /// Request to cancel an order.
 #[derive(Debug, Clone, Serialize)]
 pub struct CancelOrderRequest {
@@ -1432,3 +1731,161 @@ pub struct EarnAmount {
     /// Native amount.
     pub native: Decimal,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn form(request: &AddOrderRequest) -> HashMap<String, String> {
+        let encoded = serde_urlencoded::to_string(request).unwrap();
+        serde_urlencoded::from_str(&encoded).unwrap()
+    }
+
+    #[test]
+    fn test_add_order_typed_fields() {
+        let request = AddOrderRequest::new(
+            "XBTUSD",
+            BuySell::Buy,
+            OrderType::StopLossLimit,
+            Decimal::new(1, 3),
+        )
+        .price(Decimal::from(50000))
+        .price2(Decimal::from(49900))
+        .trigger(TriggerType::Index)
+        .self_trade_prevention(SelfTradePrevent::CancelBoth)
+        .flag(OrderFlag::NoMarketPriceProtection)
+        .post_only()
+        .time_in_force(TimeInForce::GTD)
+        .start_time(OrderTime::Now)
+        .expire_time(OrderTime::After(Duration::from_secs(90)))
+        .leverage(2);
+
+        let form = form(&request);
+        assert_eq!(form["oflags"], "post,nompp");
+        assert_eq!(form["trigger"], "index");
+        assert_eq!(form["stptype"], "cancel-both");
+        assert_eq!(form["timeinforce"], "GTD");
+        assert_eq!(form["starttm"], "0");
+        assert_eq!(form["expiretm"], "+90");
+        assert_eq!(form["leverage"], "2");
+        assert_eq!(form["ordertype"], "stop-loss-limit");
+    }
+
+    #[test]
+    fn test_add_order_round_trip() {
+        let request = AddOrderRequest::new("XBTUSD", BuySell::Sell, OrderType::Limit, Decimal::ONE)
+            .oflags([OrderFlag::FeeInQuote, OrderFlag::Post])
+            .expire_time(OrderTime::At(datetime!(2024-01-15 10:00 UTC)));
+        let form = form(&request);
+
+        let flags: BTreeSet<OrderFlag> = form["oflags"]
+            .split(',')
+            .map(|flag| flag.parse().unwrap())
+            .collect();
+        assert_eq!(request.oflags, Some(flags));
+        assert_eq!(form["expiretm"], "1705312800");
+        assert_eq!(
+            form["expiretm"].parse::<OrderTime>().unwrap(),
+            request.expiretm.unwrap()
+        );
+        assert!(!form.contains_key("starttm"));
+
+        for time in [
+            OrderTime::Now,
+            OrderTime::After(Duration::from_secs(30)),
+            OrderTime::At(datetime!(2030-06-01 00:00 UTC)),
+        ] {
+            let json = serde_json::to_string(&time).unwrap();
+            assert_eq!(serde_json::from_str::<OrderTime>(&json).unwrap(), time);
+        }
+    }
+
+    #[test]
+    fn test_add_order_raw_params() {
+        let request = AddOrderRequest::new("XBTUSD", BuySell::Buy, OrderType::Market, Decimal::ONE)
+            .raw_param("deadline", "2024-01-15T10:00:00Z")
+            .raw_param("asset_class", "tokenized_asset");
+
+        let form = form(&request);
+        assert_eq!(form["deadline"], "2024-01-15T10:00:00Z");
+        assert_eq!(form["asset_class"], "tokenized_asset");
+        assert_eq!(form["pair"], "XBTUSD");
+    }
+
+    #[test]
+    fn test_conditional_close_form_fields() {
+        let request = AddOrderRequest::new("XBTUSD", BuySell::Buy, OrderType::Limit, Decimal::ONE)
+            .price(Decimal::from(50000))
+            .close(ConditionalClose::stop_loss_limit(
+                Decimal::from(48000),
+                Decimal::from(47900),
+            ));
+
+        let form = form(&request);
+        assert_eq!(form["close[ordertype]"], "stop-loss-limit");
+        assert_eq!(form["close[price]"], "48000");
+        assert_eq!(form["close[price2]"], "47900");
+
+        let err =
+            ConditionalClose::new(OrderType::TakeProfitLimit, Decimal::ONE, None).unwrap_err();
+        assert!(matches!(
+            &err,
+            KrakenError::InvalidOrder(violations)
+                if violations == &[OrderViolation::InvalidClose {
+                    ordertype: OrderType::TakeProfitLimit,
+                    price2: None,
+                }]
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Invalid order: take-profit-limit close needs a trigger and a limit price"
+        );
+        assert!(ConditionalClose::new(OrderType::Limit, Decimal::ONE, Some(Decimal::TWO)).is_err());
+        assert!(ConditionalClose::new(OrderType::Market, Decimal::ONE, None).is_err());
+        assert_eq!(
+            ConditionalClose::new(OrderType::TakeProfit, Decimal::TEN, None).unwrap(),
+            ConditionalClose::take_profit(Decimal::TEN)
+        );
+    }
+
+    #[test]
+    fn test_conditional_close_from_description() {
+        let parse = ConditionalClose::from_description;
+        assert_eq!(
+            parse("close position @ stop loss 27000.0 -> limit 26900.0"),
+            Some(ConditionalClose::stop_loss_limit(
+                "27000.0".parse().unwrap(),
+                "26900.0".parse().unwrap()
+            ))
+        );
+        assert_eq!(
+            parse("close position @ limit 30000.5"),
+            Some(ConditionalClose::limit("30000.5".parse().unwrap()))
+        );
+        assert_eq!(
+            parse("close position @ take profit 31000 with 2:1 leverage"),
+            Some(ConditionalClose::take_profit(Decimal::from(31000)))
+        );
+        assert_eq!(parse("close position @ trailing stop +50.0"), None);
+        assert_eq!(parse(""), None);
+
+        let descr: OrderDescription = serde_json::from_value(serde_json::json!({
+            "pair": "XBTUSD",
+            "type": "buy",
+            "ordertype": "limit",
+            "price": "29000.0",
+            "price2": "0",
+            "leverage": "none",
+            "order": "buy 0.10000000 XBTUSD @ limit 29000.0",
+            "close": "close position @ take profit 31000.0 -> limit 30900.0"
+        }))
+        .unwrap();
+        let close = descr.conditional_close().unwrap();
+        assert_eq!(close.ordertype(), OrderType::TakeProfitLimit);
+        assert_eq!(close.price2(), Some("30900.0".parse().unwrap()));
+    }
+}
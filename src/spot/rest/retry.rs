@@ -0,0 +1,295 @@
+//! Per-endpoint retry policy for the spot REST client.
+//!
+//! Reads and cancels are resent on transient failures. A resent
+//! `CancelOrder` that finds the order unknown reports success, since the
+//! first attempt most likely cancelled it. Writes that place orders or move
+//! funds are not resent: a timeout after the server accepted the request
+//! would turn a blind resend into a duplicate. `AddOrder` is the one
+//! exception, and only when the order carries a `cl_ord_id`: the client
+//! first looks the order up and resends only if it did not land.
+//!
+//! Rate-limit rejections are definitive (nothing was executed), so they are
+//! retried on every endpoint not marked [`EndpointRetry::Never`], but only
+//! when enabled with [`RetryPolicy::rate_limit_retries`].
+//!
+//! Invalid-nonce rejections are not retried with backoff. With
+//! [`RetryPolicy::recover_invalid_nonce`] the client instead advances its
+//! [`NonceProvider`](crate::auth::NonceProvider) a margin past the rejected
+//! value and the current time, re-signs and resends once. This recovers from
+//! another client on the same key whose nonces run slightly ahead; the
+//! resend is rejected again if they run further ahead than the margin.
+//! Rejections are always reported as a `warn` tracing event and the
+//! `kraken_nonce_rejections_total` counter.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use kraken_api_client::spot::rest::{EndpointRetry, RetryPolicy, SpotRestClient};
+//!
+//! let policy = RetryPolicy::new()
+//!     .max_retries(5)
+//!     .rate_limit_retries(2)
+//!     .rate_limit_backoff(Duration::from_secs(2))
+//!     .endpoint("/0/private/CancelAll", EndpointRetry::Never);
+//!
+//! let client = SpotRestClient::builder().retry_policy(policy).build();
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::error::{ErrorClass, KrakenError};
+use crate::spot::rest::endpoints::private;
+
+/// How an endpoint may be resent after an ambiguous failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointRetry {
+    /// Safe to resend (reads, cancels)
+    Idempotent,
+    /// Resend only after confirming the original request did not land
+    Verified,
+    /// Never resend
+    Never,
+}
+
+/// Retry policy for the spot REST client.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    rate_limit_retries: u32,
+    rate_limit_backoff: Duration,
+    recover_invalid_nonce: bool,
+    overrides: HashMap<String, EndpointRetry>,
+}
+
+impl RetryPolicy {
+    /// Create the default policy: three retries with exponential backoff from
+    /// 200ms up to 5s, and no rate-limit retries.
+    pub fn new() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            rate_limit_retries: 0,
+            rate_limit_backoff: Duration::from_secs(1),
+            recover_invalid_nonce: false,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self::new().max_retries(0)
+    }
+
+    /// Set the maximum number of retries for transient failures.
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Set the exponential backoff bounds for transient failures.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Set how many times a rate-limited request is retried (default 0).
+    pub fn rate_limit_retries(mut self, retries: u32) -> Self {
+        self.rate_limit_retries = retries;
+        self
+    }
+
+    /// Set the wait before retrying a rate-limited request, used when the
+    /// error carries no retry-after hint.
+    pub fn rate_limit_backoff(mut self, backoff: Duration) -> Self {
+        self.rate_limit_backoff = backoff;
+        self
+    }
+
+    /// Resend once with a fresh nonce after `EAPI:Invalid nonce` (default
+    /// off).
+    ///
+    /// Applies to every private endpoint, since a nonce rejection means the
+    /// request was not executed.
+    pub fn recover_invalid_nonce(mut self, recover: bool) -> Self {
+        self.recover_invalid_nonce = recover;
+        self
+    }
+
+    /// Whether invalid-nonce recovery is enabled.
+    pub fn recovers_invalid_nonce(&self) -> bool {
+        self.recover_invalid_nonce
+    }
+
+    /// Override the retry behavior of one endpoint path.
+    pub fn endpoint(mut self, endpoint: impl Into<String>, retry: EndpointRetry) -> Self {
+        self.overrides.insert(endpoint.into(), retry);
+        self
+    }
+
+    /// Retry behavior for an endpoint path.
+    pub fn endpoint_retry(&self, endpoint: &str) -> EndpointRetry {
+        if let Some(retry) = self.overrides.get(endpoint) {
+            return *retry;
+        }
+        match endpoint {
+            private::ADD_ORDER => EndpointRetry::Verified,
+            private::ADD_ORDER_BATCH
+            | private::AMEND_ORDER
+            | private::EDIT_ORDER
+            | private::WITHDRAW
+            | private::WALLET_TRANSFER
+            | private::ACCOUNT_TRANSFER
+            | private::CREATE_SUBACCOUNT
+            | private::EARN_ALLOCATE
+            | private::EARN_DEALLOCATE
+            | private::ADD_EXPORT => EndpointRetry::Never,
+            _ => EndpointRetry::Idempotent,
+        }
+    }
+
+    /// Delay before resending an idempotent request, or `None` to give up.
+    ///
+    /// `attempt` counts earlier retries of the same request.
+    pub(crate) fn retry_delay(
+        &self,
+        endpoint: &str,
+        error: &KrakenError,
+        attempt: u32,
+    ) -> Option<Duration> {
+        let retry = self.endpoint_retry(endpoint);
+        if is_rate_limit(error) {
+            return (retry != EndpointRetry::Never && attempt < self.rate_limit_retries)
+                .then(|| rate_limit_hint(error).unwrap_or(self.rate_limit_backoff));
+        }
+        if retry == EndpointRetry::Idempotent && self.is_transient(error) {
+            return (attempt < self.max_retries).then(|| self.backoff_delay(attempt));
+        }
+        None
+    }
+
+    /// Delay before checking whether an unconfirmed write landed, or `None`
+    /// to give up.
+    pub(crate) fn verify_delay(
+        &self,
+        endpoint: &str,
+        error: &KrakenError,
+        attempt: u32,
+    ) -> Option<Duration> {
+        (self.endpoint_retry(endpoint) == EndpointRetry::Verified
+            && self.is_transient(error)
+            && attempt < self.max_retries)
+            .then(|| self.backoff_delay(attempt))
+    }
+
+    fn is_transient(&self, error: &KrakenError) -> bool {
+        let nonce = matches!(error, KrakenError::Api(e) if e.is_invalid_nonce());
+        !nonce && error.class() == ErrorClass::Retryable
+    }
+
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) fn is_rate_limit(error: &KrakenError) -> bool {
+    match error {
+        KrakenError::RateLimitExceeded { .. } => true,
+        KrakenError::Api(e) => e.is_rate_limit(),
+        KrakenError::Http(e) => e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS),
+        _ => false,
+    }
+}
+
+fn rate_limit_hint(error: &KrakenError) -> Option<Duration> {
+    match error {
+        KrakenError::RateLimitExceeded {
+            retry_after_ms: Some(ms),
+        } => Some(Duration::from_millis(*ms)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ApiError;
+    use crate::spot::rest::endpoints::public;
+
+    #[test]
+    fn test_endpoint_classification() {
+        let policy = RetryPolicy::new().endpoint(private::CANCEL_ALL, EndpointRetry::Never);
+        assert_eq!(
+            policy.endpoint_retry(public::TICKER),
+            EndpointRetry::Idempotent
+        );
+        assert_eq!(
+            policy.endpoint_retry(private::OPEN_ORDERS),
+            EndpointRetry::Idempotent
+        );
+        assert_eq!(
+            policy.endpoint_retry(private::ADD_ORDER),
+            EndpointRetry::Verified
+        );
+        assert_eq!(
+            policy.endpoint_retry(private::WITHDRAW),
+            EndpointRetry::Never
+        );
+        assert_eq!(
+            policy.endpoint_retry(private::CANCEL_ALL),
+            EndpointRetry::Never
+        );
+    }
+
+    #[test]
+    fn test_retry_decisions() {
+        let policy = RetryPolicy::new().max_retries(2).rate_limit_retries(1);
+        let busy = KrakenError::Api(ApiError::new("EService", "Busy"));
+        let nonce = KrakenError::Api(ApiError::new("EAPI", "Invalid nonce"));
+        let limited = KrakenError::RateLimitExceeded {
+            retry_after_ms: Some(1500),
+        };
+
+        assert_eq!(
+            policy.retry_delay(public::TICKER, &busy, 0),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(
+            policy.retry_delay(public::TICKER, &busy, 1),
+            Some(Duration::from_millis(400))
+        );
+        assert_eq!(policy.retry_delay(public::TICKER, &busy, 2), None);
+
+        // Writes are never blindly resent, but may be verified.
+        assert_eq!(policy.retry_delay(private::ADD_ORDER, &busy, 0), None);
+        assert!(policy.verify_delay(private::ADD_ORDER, &busy, 0).is_some());
+        assert_eq!(policy.verify_delay(private::WITHDRAW, &busy, 0), None);
+
+        // Nonce errors are left to the caller.
+        assert_eq!(policy.retry_delay(private::BALANCE, &nonce, 0), None);
+        assert_eq!(policy.verify_delay(private::ADD_ORDER, &nonce, 0), None);
+
+        // Rate limits use their own budget and hint.
+        assert_eq!(
+            policy.retry_delay(private::ADD_ORDER, &limited, 0),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(policy.retry_delay(private::ADD_ORDER, &limited, 1), None);
+        assert_eq!(policy.retry_delay(private::WITHDRAW, &limited, 0), None);
+    }
+}
@@ -0,0 +1,662 @@
+//! Running balance state built from the `balances` channel.
+//!
+//! [`BalanceBook`] applies the initial snapshot and the ledger-style updates
+//! that follow it, keeping a balance per asset and wallet. Every update is
+//! recorded as a [`LedgerChange`] so the history of a balance can be traced
+//! back to the ledger entries that produced it.
+//!
+//! [`BalanceReconciler`] periodically compares the book against
+//! `BalanceEx` via [`KrakenClient::get_extended_balance`] and reports any
+//! drift as a [`BalanceEvent::Drift`].
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rust_decimal::Decimal;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::error::KrakenError;
+use crate::spot::rest::private::ExtendedBalances;
+use crate::spot::rest::KrakenClient;
+use crate::spot::ws::messages::{channels, BalanceData, BalancesMessage};
+use crate::types::common::asset_ws_name;
+
+/// Default number of ledger changes retained by a [`BalanceBook`].
+const DEFAULT_HISTORY_LIMIT: usize = 1000;
+
+/// Wallet type assumed when an update does not name one.
+const DEFAULT_WALLET_TYPE: &str = "spot";
+
+/// Wallet ID assumed when an update does not name one.
+const DEFAULT_WALLET_ID: &str = "main";
+
+/// Identifies a wallet holding an asset.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WalletKey {
+    /// Wallet type (e.g. "spot", "earn").
+    pub wallet_type: String,
+    /// Wallet ID (e.g. "main", "flex").
+    pub wallet_id: String,
+}
+
+impl WalletKey {
+    /// Create a new wallet key.
+    pub fn new(wallet_type: impl Into<String>, wallet_id: impl Into<String>) -> Self {
+        Self {
+            wallet_type: wallet_type.into(),
+            wallet_id: wallet_id.into(),
+        }
+    }
+
+    /// The main spot wallet.
+    pub fn spot() -> Self {
+        Self::new(DEFAULT_WALLET_TYPE, DEFAULT_WALLET_ID)
+    }
+}
+
+/// A single balance change applied from a ledger update.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerChange {
+    /// Ledger entry ID.
+    pub ledger_id: Option<String>,
+    /// Reference ID of the originating trade, deposit, etc.
+    pub ref_id: Option<String>,
+    /// Ledger entry type (e.g. "trade", "deposit").
+    pub entry_type: Option<String>,
+    /// Ledger entry subtype.
+    pub subtype: Option<String>,
+    /// Ledger entry category.
+    pub category: Option<String>,
+    /// Asset.
+    pub asset: String,
+    /// Affected wallet.
+    pub wallet: WalletKey,
+    /// Amount credited or debited.
+    pub amount: Option<Decimal>,
+    /// Fee charged.
+    pub fee: Option<Decimal>,
+    /// Wallet balance before the change, if known.
+    pub previous_balance: Option<Decimal>,
+    /// Wallet balance after the change.
+    pub balance: Decimal,
+    /// Timestamp of the ledger entry.
+    pub timestamp: Option<String>,
+    /// Sequence number of the message carrying the change.
+    pub sequence: Option<u64>,
+}
+
+/// A difference between the book and the REST balance of an asset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceDrift {
+    /// Asset, using the WebSocket naming.
+    pub asset: String,
+    /// Spot balance according to the book.
+    pub book_balance: Decimal,
+    /// Balance reported by `BalanceEx`.
+    pub rest_balance: Decimal,
+}
+
+impl BalanceDrift {
+    /// Difference between the REST balance and the book balance.
+    pub fn difference(&self) -> Decimal {
+        self.rest_balance - self.book_balance
+    }
+}
+
+/// Events produced while maintaining a [`BalanceBook`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BalanceEvent {
+    /// A snapshot replaced the book contents.
+    Snapshot {
+        /// Number of assets in the snapshot.
+        assets: usize,
+    },
+    /// A ledger update changed a wallet balance.
+    Changed(Box<LedgerChange>),
+    /// The book disagrees with the REST balance of an asset.
+    Drift(BalanceDrift),
+    /// A consistency check could not be completed.
+    CheckFailed(String),
+}
+
+/// Running per-asset, per-wallet balances from the `balances` channel.
+///
+/// Updates received before the first snapshot are ignored, since they cannot
+/// be applied to a known starting point. Updates repeating an already applied
+/// ledger ID (for example after a reconnect) are skipped.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kraken_api_client::spot::ws::{BalanceBook, BalanceEvent, WsMessageEvent};
+///
+/// let mut book = BalanceBook::new();
+/// while let Some(msg) = stream.next().await {
+///     if let WsMessageEvent::ChannelData(value) = msg? {
+///         for event in book.apply_value(&value)? {
+///             if let BalanceEvent::Changed(change) = event {
+///                 println!("{} {:?} -> {}", change.asset, change.ledger_id, change.balance);
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BalanceBook {
+    balances: HashMap<String, HashMap<WalletKey, Decimal>>,
+    history: VecDeque<LedgerChange>,
+    applied_ledger_ids: HashSet<String>,
+    history_limit: usize,
+    has_snapshot: bool,
+    last_sequence: Option<u64>,
+    revision: u64,
+}
+
+impl Default for BalanceBook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BalanceBook {
+    /// Create an empty balance book.
+    pub fn new() -> Self {
+        Self::with_history_limit(DEFAULT_HISTORY_LIMIT)
+    }
+
+    /// Create an empty balance book retaining at most `limit` ledger changes.
+    pub fn with_history_limit(limit: usize) -> Self {
+        Self {
+            balances: HashMap::new(),
+            history: VecDeque::new(),
+            applied_ledger_ids: HashSet::new(),
+            history_limit: limit,
+            has_snapshot: false,
+            last_sequence: None,
+            revision: 0,
+        }
+    }
+
+    /// Apply a raw channel message.
+    ///
+    /// Messages from other channels are ignored and yield no events.
+    pub fn apply_value(
+        &mut self,
+        value: &serde_json::Value,
+    ) -> Result<Vec<BalanceEvent>, KrakenError> {
+        if value.get("channel").and_then(|c| c.as_str()) != Some(channels::BALANCES) {
+            return Ok(Vec::new());
+        }
+        let msg: BalancesMessage = serde_json::from_value(value.clone())?;
+        Ok(self.apply(&msg))
+    }
+
+    /// Apply a balances message (snapshot or update).
+    pub fn apply(&mut self, msg: &BalancesMessage) -> Vec<BalanceEvent> {
+        match msg.msg_type.as_str() {
+            "snapshot" => {
+                self.apply_snapshot(&msg.data);
+                self.last_sequence = msg.sequence;
+                vec![BalanceEvent::Snapshot {
+                    assets: msg.data.len(),
+                }]
+            }
+            "update" if self.has_snapshot => {
+                let events = msg
+                    .data
+                    .iter()
+                    .filter_map(|data| self.apply_update(data, msg.sequence))
+                    .map(|change| BalanceEvent::Changed(Box::new(change)))
+                    .collect();
+                if msg.sequence.is_some() {
+                    self.last_sequence = msg.sequence;
+                }
+                events
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn apply_snapshot(&mut self, data: &[BalanceData]) {
+        self.balances.clear();
+        for entry in data {
+            let wallets = self.balances.entry(entry.asset.clone()).or_default();
+            if entry.wallets.is_empty() {
+                wallets.insert(WalletKey::spot(), entry.balance);
+            } else {
+                for wallet in &entry.wallets {
+                    wallets.insert(
+                        WalletKey::new(&wallet.wallet_type, &wallet.id),
+                        wallet.balance,
+                    );
+                }
+            }
+        }
+        self.has_snapshot = true;
+        self.revision += 1;
+    }
+
+    fn apply_update(&mut self, data: &BalanceData, sequence: Option<u64>) -> Option<LedgerChange> {
+        if let Some(ledger_id) = &data.ledger_id {
+            if !self.applied_ledger_ids.insert(ledger_id.clone()) {
+                return None;
+            }
+        }
+
+        let wallet = WalletKey::new(
+            data.wallet_type.as_deref().unwrap_or(DEFAULT_WALLET_TYPE),
+            data.wallet_id.as_deref().unwrap_or(DEFAULT_WALLET_ID),
+        );
+        let previous_balance = self
+            .balances
+            .entry(data.asset.clone())
+            .or_default()
+            .insert(wallet.clone(), data.balance);
+        self.revision += 1;
+
+        let change = LedgerChange {
+            ledger_id: data.ledger_id.clone(),
+            ref_id: data.ref_id.clone(),
+            entry_type: data.entry_type.clone(),
+            subtype: data.subtype.clone(),
+            category: data.category.clone(),
+            asset: data.asset.clone(),
+            wallet,
+            amount: data.amount,
+            fee: data.fee,
+            previous_balance,
+            balance: data.balance,
+            timestamp: data.timestamp.clone(),
+            sequence,
+        };
+        self.record(change.clone());
+        Some(change)
+    }
+
+    fn record(&mut self, change: LedgerChange) {
+        self.history.push_back(change);
+        while self.history.len() > self.history_limit {
+            if let Some(evicted) = self.history.pop_front() {
+                if let Some(ledger_id) = evicted.ledger_id {
+                    self.applied_ledger_ids.remove(&ledger_id);
+                }
+            }
+        }
+    }
+
+    /// Whether a snapshot has been applied.
+    pub fn has_snapshot(&self) -> bool {
+        self.has_snapshot
+    }
+
+    /// Sequence number of the last applied message.
+    pub fn last_sequence(&self) -> Option<u64> {
+        self.last_sequence
+    }
+
+    /// Counter incremented on every applied snapshot or change.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Total balance of an asset across all wallets.
+    pub fn balance(&self, asset: &str) -> Decimal {
+        self.balances
+            .get(asset)
+            .map(|wallets| wallets.values().copied().sum())
+            .unwrap_or_default()
+    }
+
+    /// Balance of an asset in a specific wallet.
+    pub fn wallet_balance(&self, asset: &str, wallet: &WalletKey) -> Option<Decimal> {
+        self.balances.get(asset)?.get(wallet).copied()
+    }
+
+    /// Balance of an asset summed over all wallets of the given type.
+    pub fn wallet_type_balance(&self, asset: &str, wallet_type: &str) -> Decimal {
+        self.balances
+            .get(asset)
+            .map(|wallets| {
+                wallets
+                    .iter()
+                    .filter(|(key, _)| key.wallet_type == wallet_type)
+                    .map(|(_, balance)| *balance)
+                    .sum()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Per-wallet balances of an asset.
+    pub fn wallets(&self, asset: &str) -> Option<&HashMap<WalletKey, Decimal>> {
+        self.balances.get(asset)
+    }
+
+    /// Assets tracked by the book.
+    pub fn assets(&self) -> impl Iterator<Item = &str> {
+        self.balances.keys().map(String::as_str)
+    }
+
+    /// Recorded ledger changes, oldest first.
+    pub fn changes(&self) -> impl Iterator<Item = &LedgerChange> {
+        self.history.iter()
+    }
+
+    /// Recorded ledger changes for one asset, oldest first.
+    pub fn changes_for<'a>(&'a self, asset: &'a str) -> impl Iterator<Item = &'a LedgerChange> {
+        self.history
+            .iter()
+            .filter(move |change| change.asset == asset)
+    }
+
+    /// Compare the spot wallets of the book against `BalanceEx` results.
+    ///
+    /// REST asset codes are normalized to the WebSocket naming (`XXBT` and
+    /// `XBT` become `BTC`, `ZUSD` becomes `USD`, ...). Suffixed REST entries
+    /// such as `XBT.F` describe non-spot wallets and are skipped. Differences
+    /// whose absolute value does not exceed `tolerance` are not reported.
+    pub fn check_consistency(
+        &self,
+        rest: &ExtendedBalances,
+        tolerance: Decimal,
+    ) -> Vec<BalanceDrift> {
+        let mut rest_balances: HashMap<String, Decimal> = HashMap::new();
+        for (asset, balance) in &rest.balances {
+            if let Some(asset) = normalize_rest_asset(asset) {
+                *rest_balances.entry(asset).or_default() += balance.balance;
+            }
+        }
+
+        let mut assets: Vec<&String> = self.balances.keys().chain(rest_balances.keys()).collect();
+        assets.sort();
+        assets.dedup();
+
+        assets
+            .into_iter()
+            .filter_map(|asset| {
+                let book_balance = self.wallet_type_balance(asset, DEFAULT_WALLET_TYPE);
+                let rest_balance = rest_balances.get(asset).copied().unwrap_or_default();
+                ((rest_balance - book_balance).abs() > tolerance).then(|| BalanceDrift {
+                    asset: asset.clone(),
+                    book_balance,
+                    rest_balance,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Map a REST asset code to the WebSocket naming.
+///
+/// Returns `None` for suffixed codes (e.g. `XBT.F`, `ETH2.S`) that do not
+/// describe the spot wallet.
+fn normalize_rest_asset(asset: &str) -> Option<String> {
+    if asset.contains('.') {
+        return None;
+    }
+    Some(asset_ws_name(asset))
+}
+
+/// Periodically checks a [`BalanceBook`] against `BalanceEx`.
+///
+/// A check is skipped when the book has no snapshot yet, or when the book
+/// changed while the REST request was in flight, since the two views would
+/// not describe the same point in time.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use tokio::sync::Mutex;
+/// use kraken_api_client::spot::ws::{BalanceBook, BalanceReconciler};
+///
+/// let book = Arc::new(Mutex::new(BalanceBook::new()));
+/// let (_task, mut events) = BalanceReconciler::new(rest_client, Duration::from_secs(60))
+///     .spawn(book.clone());
+///
+/// while let Some(event) = events.recv().await {
+///     println!("{:?}", event);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BalanceReconciler<C> {
+    client: C,
+    interval: Duration,
+    tolerance: Decimal,
+}
+
+impl<C> BalanceReconciler<C>
+where
+    C: KrakenClient + 'static,
+{
+    /// Create a reconciler checking every `interval`.
+    pub fn new(client: C, interval: Duration) -> Self {
+        Self {
+            client,
+            interval,
+            tolerance: Decimal::ZERO,
+        }
+    }
+
+    /// Set the largest difference that is not reported as drift.
+    pub fn tolerance(mut self, tolerance: Decimal) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Run a single consistency check.
+    ///
+    /// Returns `Ok(None)` if the check was skipped.
+    pub async fn check(
+        &self,
+        book: &Mutex<BalanceBook>,
+    ) -> Result<Option<Vec<BalanceDrift>>, KrakenError> {
+        let revision = {
+            let book = book.lock().await;
+            if !book.has_snapshot() {
+                return Ok(None);
+            }
+            book.revision()
+        };
+
+        let rest = self.client.get_extended_balance().await?;
+
+        let book = book.lock().await;
+        if book.revision() != revision {
+            return Ok(None);
+        }
+        Ok(Some(book.check_consistency(&rest, self.tolerance)))
+    }
+
+    /// Spawn a background task running [`check`](Self::check) every interval.
+    ///
+    /// Drift and failed checks are sent on the returned channel. The task
+    /// stops when the receiver is dropped.
+    pub fn spawn(
+        self,
+        book: Arc<Mutex<BalanceBook>>,
+    ) -> (JoinHandle<()>, mpsc::UnboundedReceiver<BalanceEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if tx.is_closed() {
+                    break;
+                }
+                let events = match self.check(&book).await {
+                    Ok(Some(drifts)) => drifts.into_iter().map(BalanceEvent::Drift).collect(),
+                    Ok(None) => Vec::new(),
+                    Err(e) => {
+                        tracing::warn!("Balance consistency check failed: {}", e);
+                        vec![BalanceEvent::CheckFailed(e.to_string())]
+                    }
+                };
+                for event in events {
+                    if tx.send(event).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        (handle, rx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn snapshot() -> BalancesMessage {
+        serde_json::from_value(serde_json::json!({
+            "channel": "balances",
+            "type": "snapshot",
+            "data": [
+                {
+                    "asset": "BTC",
+                    "asset_class": "currency",
+                    "balance": "1.5",
+                    "wallets": [
+                        { "type": "spot", "id": "main", "balance": "1.0" },
+                        { "type": "earn", "id": "flex", "balance": "0.5" }
+                    ]
+                },
+                { "asset": "USD", "asset_class": "currency", "balance": "1000" }
+            ],
+            "sequence": 1
+        }))
+        .unwrap()
+    }
+
+    fn update(ledger_id: &str, amount: &str, balance: &str) -> BalancesMessage {
+        serde_json::from_value(serde_json::json!({
+            "channel": "balances",
+            "type": "update",
+            "data": [{
+                "ledger_id": ledger_id,
+                "ref_id": "TREF-1",
+                "timestamp": "2024-01-01T00:00:00.000000Z",
+                "type": "trade",
+                "asset": "BTC",
+                "asset_class": "currency",
+                "category": "trade",
+                "wallet_type": "spot",
+                "wallet_id": "main",
+                "amount": amount,
+                "fee": "0",
+                "balance": balance
+            }],
+            "sequence": 2
+        }))
+        .unwrap()
+    }
+
+    fn rest(entries: &[(&str, &str)]) -> ExtendedBalances {
+        let map: serde_json::Map<String, serde_json::Value> = entries
+            .iter()
+            .map(|(asset, balance)| {
+                (
+                    asset.to_string(),
+                    serde_json::json!({ "balance": balance, "hold_trade": "0" }),
+                )
+            })
+            .collect();
+        serde_json::from_value(serde_json::Value::Object(map)).unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_then_update() {
+        let mut book = BalanceBook::new();
+        let events = book.apply(&snapshot());
+        assert_eq!(events, vec![BalanceEvent::Snapshot { assets: 2 }]);
+        assert_eq!(book.balance("BTC"), dec("1.5"));
+        assert_eq!(book.wallet_type_balance("BTC", "spot"), dec("1.0"));
+        assert_eq!(
+            book.wallet_balance("USD", &WalletKey::spot()),
+            Some(dec("1000"))
+        );
+
+        let events = book.apply(&update("L1", "0.25", "1.25"));
+        assert_eq!(events.len(), 1);
+        let BalanceEvent::Changed(change) = &events[0] else {
+            panic!("expected change event");
+        };
+        assert_eq!(change.ledger_id.as_deref(), Some("L1"));
+        assert_eq!(change.entry_type.as_deref(), Some("trade"));
+        assert_eq!(change.previous_balance, Some(dec("1.0")));
+        assert_eq!(change.balance, dec("1.25"));
+        assert_eq!(book.balance("BTC"), dec("1.75"));
+        assert_eq!(book.changes_for("BTC").count(), 1);
+        assert_eq!(book.last_sequence(), Some(2));
+    }
+
+    #[test]
+    fn test_updates_before_snapshot_and_duplicates_ignored() {
+        let mut book = BalanceBook::new();
+        assert!(book.apply(&update("L1", "0.25", "1.25")).is_empty());
+
+        book.apply(&snapshot());
+        assert_eq!(book.apply(&update("L1", "0.25", "1.25")).len(), 1);
+        assert!(book.apply(&update("L1", "0.25", "1.25")).is_empty());
+        assert_eq!(book.changes().count(), 1);
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let mut book = BalanceBook::with_history_limit(2);
+        book.apply(&snapshot());
+        book.apply(&update("L1", "0.1", "1.1"));
+        book.apply(&update("L2", "0.1", "1.2"));
+        book.apply(&update("L3", "0.1", "1.3"));
+        let ids: Vec<_> = book
+            .changes()
+            .filter_map(|c| c.ledger_id.as_deref())
+            .collect();
+        assert_eq!(ids, vec!["L2", "L3"]);
+    }
+
+    #[test]
+    fn test_apply_value_ignores_other_channels() {
+        let mut book = BalanceBook::new();
+        let value = serde_json::json!({ "channel": "ticker", "type": "update", "data": [] });
+        assert!(book.apply_value(&value).unwrap().is_empty());
+        assert!(!book.has_snapshot());
+    }
+
+    #[test]
+    fn test_check_consistency() {
+        let mut book = BalanceBook::new();
+        book.apply(&snapshot());
+
+        let matching = rest(&[("XXBT", "1.0"), ("ZUSD", "1000"), ("XBT.F", "0.5")]);
+        assert!(book.check_consistency(&matching, Decimal::ZERO).is_empty());
+
+        let drifted = rest(&[("XXBT", "0.9"), ("ZUSD", "1000"), ("XETH", "2")]);
+        let drifts = book.check_consistency(&drifted, Decimal::ZERO);
+        assert_eq!(drifts.len(), 2);
+        assert_eq!(drifts[0].asset, "BTC");
+        assert_eq!(drifts[0].difference(), dec("-0.1"));
+        assert_eq!(drifts[1].asset, "ETH");
+        assert_eq!(drifts[1].book_balance, Decimal::ZERO);
+
+        assert_eq!(book.check_consistency(&drifted, dec("0.1")).len(), 1);
+    }
+
+    #[test]
+    fn test_normalize_rest_asset() {
+        assert_eq!(normalize_rest_asset("XXBT").as_deref(), Some("BTC"));
+        assert_eq!(normalize_rest_asset("XBT").as_deref(), Some("BTC"));
+        assert_eq!(normalize_rest_asset("XXDG").as_deref(), Some("DOGE"));
+        assert_eq!(normalize_rest_asset("ZUSD").as_deref(), Some("USD"));
+        assert_eq!(normalize_rest_asset("USDT").as_deref(), Some("USDT"));
+        assert_eq!(normalize_rest_asset("ZEUS").as_deref(), Some("ZEUS"));
+        assert_eq!(normalize_rest_asset("DOT.S"), None);
+    }
+}
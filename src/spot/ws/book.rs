@@ -0,0 +1,229 @@
+//! Local order book state for the spot `book` channel.
+
+use crate::error::KrakenError;
+use crate::pairs::IntoWsSymbol;
+use crate::spot::ws::messages::{channels, BookData, BookMessage};
+use crate::types::book::BookSide;
+use crate::types::{BookView, PriceLevel};
+
+/// Default number of levels kept per side.
+const DEFAULT_DEPTH: usize = 10;
+
+/// Order book state for one spot symbol, built from the v2 `book` channel.
+///
+/// Applies the snapshot and subsequent updates, drops zero-quantity levels and
+/// keeps at most `depth` levels per side (matching the subscribed depth).
+/// Checksums are recorded but not verified.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use kraken_api_client::spot::ws::{SpotOrderBook, WsMessageEvent};
+/// use kraken_api_client::types::BookView;
+///
+/// let mut book = SpotOrderBook::new("BTC/USD");
+/// while let Some(msg) = stream.next().await {
+///     if let WsMessageEvent::ChannelData(value) = msg? {
+///         if book.apply_value(&value)? {
+///             println!("mid: {:?}", book.mid_price());
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpotOrderBook {
+    symbol: String,
+    depth: usize,
+    bids: BookSide,
+    asks: BookSide,
+    checksum: Option<u32>,
+    timestamp: Option<String>,
+    synced: bool,
+}
+
+impl SpotOrderBook {
+    /// Create an empty book for a symbol, keeping the default depth of 10.
+    pub fn new(symbol: impl IntoWsSymbol) -> Self {
+        Self::with_depth(symbol, DEFAULT_DEPTH)
+    }
+
+    /// Create an empty book keeping `depth` levels per side.
+    pub fn with_depth(symbol: impl IntoWsSymbol, depth: usize) -> Self {
+        Self {
+            symbol: symbol.into_ws_symbol(),
+            depth,
+            bids: BookSide::bids(),
+            asks: BookSide::asks(),
+            checksum: None,
+            timestamp: None,
+            synced: false,
+        }
+    }
+
+    /// Number of levels kept per side.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Checksum reported with the last applied message.
+    pub fn checksum(&self) -> Option<u32> {
+        self.checksum
+    }
+
+    /// Timestamp of the last applied update.
+    pub fn timestamp(&self) -> Option<&str> {
+        self.timestamp.as_deref()
+    }
+
+    /// Apply a raw channel message, if it is book data for this symbol.
+    ///
+    /// Returns `Ok(true)` if the book changed.
+    pub fn apply_value(&mut self, value: &serde_json::Value) -> Result<bool, KrakenError> {
+        if value.get("channel").and_then(|c| c.as_str()) != Some(channels::BOOK) {
+            return Ok(false);
+        }
+        let msg: BookMessage = serde_json::from_value(value.clone())?;
+        Ok(self.apply(&msg))
+    }
+
+    /// Apply a book message (snapshot or update).
+    ///
+    /// Updates received before the first snapshot are ignored. Returns `true`
+    /// if the book changed.
+    pub fn apply(&mut self, msg: &BookMessage) -> bool {
+        let mut changed = false;
+        for data in &msg.data {
+            if data.symbol != self.symbol {
+                continue;
+            }
+            match msg.msg_type.as_str() {
+                "snapshot" => {
+                    self.bids.clear();
+                    self.asks.clear();
+                    self.apply_data(data);
+                    self.synced = true;
+                    changed = true;
+                }
+                "update" if self.synced => {
+                    self.apply_data(data);
+                    changed = true;
+                }
+                _ => {}
+            }
+        }
+        changed
+    }
+
+    fn apply_data(&mut self, data: &BookData) {
+        for level in &data.bids {
+            self.bids.apply(level.price, level.qty);
+        }
+        for level in &data.asks {
+            self.asks.apply(level.price, level.qty);
+        }
+        self.bids.truncate(self.depth);
+        self.asks.truncate(self.depth);
+        self.checksum = data.checksum;
+        if data.timestamp.is_some() {
+            self.timestamp = data.timestamp.clone();
+        }
+    }
+}
+
+impl BookView for SpotOrderBook {
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    fn bids(&self, depth: usize) -> Vec<PriceLevel> {
+        self.bids.top(depth)
+    }
+
+    fn asks(&self, depth: usize) -> Vec<PriceLevel> {
+        self.asks.top(depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use serde_json::json;
+
+    use super::*;
+
+    fn d(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_update_and_truncation() {
+        let mut book = SpotOrderBook::with_depth("BTC/USD", 2);
+
+        let update = json!({
+            "channel": "book",
+            "type": "update",
+            "data": [{
+                "symbol": "BTC/USD",
+                "bids": [{ "price": 45283.4, "qty": 0.5 }],
+                "asks": [],
+                "checksum": 1,
+                "timestamp": "2023-10-06T17:35:55.440295Z"
+            }]
+        });
+        assert!(!book.apply_value(&update).unwrap());
+
+        let snapshot = json!({
+            "channel": "book",
+            "type": "snapshot",
+            "data": [{
+                "symbol": "BTC/USD",
+                "bids": [
+                    { "price": 45283.5, "qty": 0.1 },
+                    { "price": 45283.4, "qty": 4.6 }
+                ],
+                "asks": [
+                    { "price": 45285.2, "qty": 0.00100000 },
+                    { "price": 45286.4, "qty": 1.5 }
+                ],
+                "checksum": 3310070434u32
+            }]
+        });
+        assert!(book.apply_value(&snapshot).unwrap());
+        assert!(book.is_synced());
+        assert_eq!(book.spread(), Some(d("1.7")));
+        assert_eq!(book.checksum(), Some(3310070434));
+
+        let update = json!({
+            "channel": "book",
+            "type": "update",
+            "data": [{
+                "symbol": "BTC/USD",
+                "bids": [{ "price": 45284.0, "qty": 1.0 }],
+                "asks": [{ "price": 45285.2, "qty": 0 }],
+                "checksum": 2,
+                "timestamp": "2023-10-06T17:35:55.440295Z"
+            }]
+        });
+        assert!(book.apply_value(&update).unwrap());
+        assert_eq!(book.best_bid().unwrap().price, d("45284.0"));
+        assert_eq!(book.bids(10).len(), 2);
+        assert_eq!(book.asks(10), vec![PriceLevel::new(d("45286.4"), d("1.5"))]);
+    }
+
+    #[test]
+    fn test_other_symbols_and_channels_ignored() {
+        let mut book = SpotOrderBook::new("ETH/USD");
+        let snapshot = json!({
+            "channel": "book",
+            "type": "snapshot",
+            "data": [{ "symbol": "BTC/USD", "bids": [], "asks": [] }]
+        });
+        assert!(!book.apply_value(&snapshot).unwrap());
+        assert!(!book.apply_value(&json!({ "channel": "ticker" })).unwrap());
+        assert!(!book.is_synced());
+    }
+}
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::pairs::IntoWsSymbol;
+
 /// WebSocket request message.
 #[derive(Debug, Clone, Serialize)]
 pub struct WsRequest<T> {
@@ -86,6 +88,15 @@ impl SubscribeParams {
         }
     }
 
+    /// Create a subscription for a public channel from symbols or
+    /// [`Pair`](crate::pairs::Pair)s.
+    pub fn public_symbols<S: IntoWsSymbol>(
+        channel: impl Into<String>,
+        symbols: impl IntoIterator<Item = S>,
+    ) -> Self {
+        Self::public(channel, symbols.into_iter().map(S::into_ws_symbol).collect())
+    }
+
     /// Create a subscription for a private channel.
     pub fn private(channel: impl Into<String>, token: impl Into<String>) -> Self {
         Self {
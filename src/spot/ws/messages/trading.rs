@@ -3,6 +3,7 @@
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+use crate::pairs::IntoWsSymbol;
 use crate::types::{BuySell, OrderType, TimeInForce};
 
 /// Add order request parameters.
@@ -53,13 +54,13 @@ impl AddOrderParams {
     pub fn new(
         order_type: OrderType,
         side: BuySell,
-        symbol: impl Into<String>,
+        symbol: impl IntoWsSymbol,
         token: impl Into<String>,
     ) -> Self {
         Self {
             order_type,
             side,
-            symbol: symbol.into(),
+            symbol: symbol.into_ws_symbol(),
             order_qty: None,
             limit_price: None,
             time_in_force: None,
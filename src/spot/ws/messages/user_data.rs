@@ -131,6 +131,10 @@ pub struct BalancesMessage {
 }
 
 /// Single balance data.
+///
+/// Snapshot entries carry the per-wallet breakdown in `wallets`. Update
+/// entries describe a single ledger entry, with `balance` holding the
+/// resulting balance of the affected wallet.
 #[derive(Debug, Clone, Deserialize)]
 pub struct BalanceData {
     /// Asset.
@@ -140,6 +144,42 @@ pub struct BalanceData {
     /// Amount on hold (in open orders).
     #[serde(default)]
     pub hold_trade: Option<Decimal>,
+    /// Asset class.
+    #[serde(default)]
+    pub asset_class: Option<String>,
+    /// Per-wallet balances (snapshot only).
+    #[serde(default)]
+    pub wallets: Vec<WalletBalance>,
+    /// Ledger entry ID (update only).
+    #[serde(default)]
+    pub ledger_id: Option<String>,
+    /// Reference ID of the originating trade, deposit, etc. (update only).
+    #[serde(default)]
+    pub ref_id: Option<String>,
+    /// Ledger entry type, e.g. "trade" or "deposit" (update only).
+    #[serde(rename = "type", default)]
+    pub entry_type: Option<String>,
+    /// Ledger entry subtype (update only).
+    #[serde(default)]
+    pub subtype: Option<String>,
+    /// Ledger entry category (update only).
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Type of the affected wallet, e.g. "spot" or "earn" (update only).
+    #[serde(default)]
+    pub wallet_type: Option<String>,
+    /// ID of the affected wallet (update only).
+    #[serde(default)]
+    pub wallet_id: Option<String>,
+    /// Amount credited or debited (update only).
+    #[serde(default)]
+    pub amount: Option<Decimal>,
+    /// Fee charged (update only).
+    #[serde(default)]
+    pub fee: Option<Decimal>,
+    /// Timestamp (update only).
+    #[serde(default)]
+    pub timestamp: Option<String>,
 }
 
 impl BalanceData {
@@ -158,3 +198,15 @@ impl BalanceData {
         self.hold_trade.unwrap_or_default()
     }
 }
+
+/// Balance of a single wallet in a balances snapshot.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WalletBalance {
+    /// Wallet type (e.g. "spot", "earn").
+    #[serde(rename = "type")]
+    pub wallet_type: String,
+    /// Wallet ID (e.g. "main", "flex", "bonded").
+    pub id: String,
+    /// Wallet balance.
+    pub balance: Decimal,
+}
@@ -38,9 +38,15 @@
 //! }
 //! ```
 
+mod balance_book;
+mod book;
 mod client;
 pub mod messages;
 mod stream;
 
+pub use balance_book::{
+    BalanceBook, BalanceDrift, BalanceEvent, BalanceReconciler, LedgerChange, WalletKey,
+};
+pub use book::SpotOrderBook;
 pub use client::{SpotWsClient, WsConfig, WsConfigBuilder};
 pub use stream::{KrakenStream, WsMessageEvent};
@@ -15,17 +15,22 @@ use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 
 use crate::error::KrakenError;
+use crate::rate_limit::{OrderTrackingInfo, RateLimitBehavior, SharedTradingLimiter, TtlCache};
 use crate::spot::ws::client::WsConfig;
 use crate::spot::ws::messages::{
     channels, AddOrderParams, AddOrderResult, CancelAllParams, CancelAllResult, CancelOrderParams,
-    CancelOrderResult, EditOrderParams, EditOrderResult, Heartbeat, PingRequest, PongResponse,
-    SubscribeParams, SubscriptionResult, SystemStatusMessage, WsRequest,
+    CancelOrderResult, EditOrderParams, EditOrderResult, ExecutionsMessage, Heartbeat, PingRequest,
+    PongResponse, SubscribeParams, SubscriptionResult, SystemStatusMessage, WsRequest,
 };
 
 type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 type WsSink = SplitSink<WsStream, WsMessage>;
 type WsReceiver = SplitStream<WsStream>;
 
+/// How long an order awaits its `add_order` response before it is forgotten,
+/// matching how long [`SharedTradingLimiter`] tracks orders.
+const PENDING_ORDER_TTL: Duration = Duration::from_secs(300);
+
 /// A message received from the WebSocket connection.
 #[derive(Debug, Clone)]
 pub enum WsMessageEvent {
@@ -150,6 +155,12 @@ pub struct KrakenStream {
     connected: bool,
     /// Whether we're currently reconnecting.
     reconnecting: bool,
+    /// Optional trading rate limiter for order entry.
+    trading_limiter: Option<SharedTradingLimiter>,
+    /// Behavior when the trading rate limit is reached.
+    rate_limit_behavior: RateLimitBehavior,
+    /// Provisional limiter keys of orders awaiting an `add_order` response, by request ID.
+    pending_orders: TtlCache<u64, String>,
 }
 
 impl std::fmt::Debug for KrakenStream {
@@ -205,9 +216,36 @@ impl KrakenStream {
             req_id: 0,
             connected: true,
             reconnecting: false,
+            trading_limiter: None,
+            rate_limit_behavior: RateLimitBehavior::default(),
+            pending_orders: TtlCache::new(PENDING_ORDER_TTL),
         })
     }
 
+    /// Throttle order entry with a trading rate limiter.
+    ///
+    /// `add_order` and `cancel_order` acquire capacity before sending, either
+    /// waiting or failing with [`KrakenError::RateLimitExceeded`] depending on
+    /// `behavior`. Order ages are taken from `executions` channel messages, so
+    /// subscribe to that channel for accurate cancellation penalties.
+    ///
+    /// Pass a clone of the limiter used by a
+    /// [`RateLimitedClient`](crate::rate_limit::RateLimitedClient) to share one
+    /// budget between REST and WebSocket order entry.
+    pub fn set_trading_limiter(
+        &mut self,
+        limiter: SharedTradingLimiter,
+        behavior: RateLimitBehavior,
+    ) {
+        self.trading_limiter = Some(limiter);
+        self.rate_limit_behavior = behavior;
+    }
+
+    /// Get the trading rate limiter, if one is set.
+    pub fn trading_limiter(&self) -> Option<&SharedTradingLimiter> {
+        self.trading_limiter.as_ref()
+    }
+
     /// Subscribe to a channel.
     pub async fn subscribe(&mut self, params: SubscribeParams) -> Result<(), KrakenError> {
         let key = subscription_key(&params);
@@ -281,6 +319,25 @@ impl KrakenStream {
     pub async fn add_order(&mut self, params: AddOrderParams) -> Result<u64, KrakenError> {
         self.ensure_private()?;
         let req_id = self.next_req_id();
+        if let Some(limiter) = self.trading_limiter.clone() {
+            if params.validate != Some(true) {
+                let (key, info) = match &params.cl_ord_id {
+                    Some(cl_ord_id) => (
+                        cl_ord_id.clone(),
+                        OrderTrackingInfo::with_client_id(&params.symbol, cl_ord_id),
+                    ),
+                    None => (
+                        format!("ws-req-{}", req_id),
+                        OrderTrackingInfo::new(&params.symbol),
+                    ),
+                };
+                limiter
+                    .acquire_place(&key, info, self.rate_limit_behavior)
+                    .await?;
+                self.pending_orders.cleanup();
+                self.pending_orders.insert(req_id, key);
+            }
+        }
         let req = WsRequest::new("add_order", params).with_req_id(req_id);
         self.send_json(&req).await?;
         Ok(req_id)
@@ -311,6 +368,16 @@ impl KrakenStream {
     /// ```
     pub async fn cancel_order(&mut self, params: CancelOrderParams) -> Result<u64, KrakenError> {
         self.ensure_private()?;
+        if let Some(limiter) = &self.trading_limiter {
+            let ids: Vec<&str> = params
+                .order_id
+                .iter()
+                .chain(params.cl_ord_id.iter())
+                .flatten()
+                .map(String::as_str)
+                .collect();
+            limiter.acquire_cancels(&ids, self.rate_limit_behavior).await?;
+        }
         let req_id = self.next_req_id();
         let req = WsRequest::new("cancel_order", params).with_req_id(req_id);
         self.send_json(&req).await?;
@@ -385,6 +452,16 @@ impl KrakenStream {
             .map_err(|e| KrakenError::WebSocketMsg(format!("Failed to send message: {}", e)))
     }
 
+    /// Mark the connection as lost.
+    ///
+    /// Responses to requests sent on the old connection never arrive, so
+    /// pending orders are forgotten here; the limiter still tracks them under
+    /// their provisional keys until its own TTL expires.
+    fn mark_disconnected(&mut self) {
+        self.connected = false;
+        self.pending_orders.clear();
+    }
+
     /// Get the next request ID.
     fn next_req_id(&mut self) -> u64 {
         self.req_id += 1;
@@ -413,7 +490,7 @@ impl KrakenStream {
     #[allow(dead_code)]
     async fn reconnect(&mut self) -> Result<(), KrakenError> {
         self.reconnect_attempt += 1;
-        self.connected = false;
+        self.mark_disconnected();
         self.reconnecting = true;
 
         // Close existing connection
@@ -545,6 +622,11 @@ impl KrakenStream {
                 if success {
                     if let Some(result) = value.get("result") {
                         if let Ok(order_result) = serde_json::from_value::<AddOrderResult>(result.clone()) {
+                            if let Some(key) = req_id.and_then(|id| self.pending_orders.remove(&id)) {
+                                if let Some(limiter) = &self.trading_limiter {
+                                    limiter.assign_order_id(&key, order_result.order_id.clone());
+                                }
+                            }
                             return Some(WsMessageEvent::OrderAdded {
                                 req_id,
                                 result: order_result,
@@ -552,6 +634,11 @@ impl KrakenStream {
                         }
                     }
                 } else {
+                    if let Some(key) = req_id.and_then(|id| self.pending_orders.remove(&id)) {
+                        if let Some(limiter) = &self.trading_limiter {
+                            limiter.order_closed(&key);
+                        }
+                    }
                     let error = value.get("error").and_then(|e| e.as_str()).unwrap_or("Unknown error");
                     return Some(WsMessageEvent::Error {
                         method: method.to_string(),
@@ -646,6 +733,14 @@ impl KrakenStream {
                     return Some(WsMessageEvent::Heartbeat(heartbeat));
                 }
             }
+            channels::EXECUTIONS => {
+                if let Some(limiter) = &self.trading_limiter {
+                    if let Ok(executions) = serde_json::from_value::<ExecutionsMessage>(value.clone()) {
+                        limiter.apply_executions(&executions);
+                    }
+                }
+                return Some(WsMessageEvent::ChannelData(value));
+            }
             _ => {
                 // Market data or user data channel
                 return Some(WsMessageEvent::ChannelData(value));
@@ -674,7 +769,7 @@ impl KrakenStream {
             let _ = sink.send(WsMessage::Close(None)).await;
         }
         self.receiver = None;
-        self.connected = false;
+        self.mark_disconnected();
         Ok(())
     }
 
@@ -711,7 +806,7 @@ impl Stream for KrakenStream {
         // Check connection health
         if !self.check_connection_health() && self.connected {
             let this = self.as_mut().get_mut();
-            this.connected = false;
+            this.mark_disconnected();
 
             if this.should_reconnect() {
                 return Poll::Ready(Some(Ok(WsMessageEvent::Reconnecting {
@@ -752,7 +847,7 @@ impl Stream for KrakenStream {
                             return Poll::Pending;
                         }
                         WsMessage::Close(_) => {
-                            this.connected = false;
+                            this.mark_disconnected();
                             if this.should_reconnect() {
                                 return Poll::Ready(Some(Ok(WsMessageEvent::Reconnecting {
                                     attempt: this.reconnect_attempt + 1,
@@ -769,7 +864,7 @@ impl Stream for KrakenStream {
                 }
                 Poll::Ready(Some(Err(e))) => {
                     let this = self.as_mut().get_mut();
-                    this.connected = false;
+                    this.mark_disconnected();
                     tracing::warn!("WebSocket error: {}", e);
 
                     if this.should_reconnect() {
@@ -782,7 +877,7 @@ impl Stream for KrakenStream {
                 }
                 Poll::Ready(None) => {
                     let this = self.as_mut().get_mut();
-                    this.connected = false;
+                    this.mark_disconnected();
 
                     if this.should_reconnect() {
                         return Poll::Ready(Some(Ok(WsMessageEvent::Reconnecting {
@@ -0,0 +1,159 @@
+//! Venue-agnostic order book view.
+//!
+//! Both the spot ([`SpotOrderBook`](crate::spot::ws::SpotOrderBook)) and the
+//! futures ([`FuturesBookState`](crate::futures::ws::FuturesBookState)) book
+//! state implement [`BookView`], so strategy code can read either without
+//! knowing which venue it came from.
+
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+
+/// A single aggregated price level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceLevel {
+    /// Price.
+    pub price: Decimal,
+    /// Quantity resting at this price.
+    pub qty: Decimal,
+}
+
+impl PriceLevel {
+    /// Create a new price level.
+    pub fn new(price: Decimal, qty: Decimal) -> Self {
+        Self { price, qty }
+    }
+}
+
+/// Read access to a locally maintained order book.
+pub trait BookView {
+    /// Symbol or product ID of the book.
+    fn symbol(&self) -> &str;
+
+    /// Whether the book reflects a consistent snapshot plus all updates since.
+    ///
+    /// A book that is not synced should not be used for trading decisions.
+    fn is_synced(&self) -> bool;
+
+    /// Up to `depth` bid levels, best (highest) price first.
+    fn bids(&self, depth: usize) -> Vec<PriceLevel>;
+
+    /// Up to `depth` ask levels, best (lowest) price first.
+    fn asks(&self, depth: usize) -> Vec<PriceLevel>;
+
+    /// Best bid level.
+    fn best_bid(&self) -> Option<PriceLevel> {
+        self.bids(1).into_iter().next()
+    }
+
+    /// Best ask level.
+    fn best_ask(&self) -> Option<PriceLevel> {
+        self.asks(1).into_iter().next()
+    }
+
+    /// Midpoint between best bid and best ask.
+    fn mid_price(&self) -> Option<Decimal> {
+        let bid = self.best_bid()?;
+        let ask = self.best_ask()?;
+        Some((bid.price + ask.price) / Decimal::TWO)
+    }
+
+    /// Difference between best ask and best bid.
+    fn spread(&self) -> Option<Decimal> {
+        Some(self.best_ask()?.price - self.best_bid()?.price)
+    }
+}
+
+/// One side of an order book, keyed by price.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BookSide {
+    levels: BTreeMap<Decimal, Decimal>,
+    descending: bool,
+}
+
+impl BookSide {
+    /// Bid side: best price is the highest.
+    pub(crate) fn bids() -> Self {
+        Self {
+            levels: BTreeMap::new(),
+            descending: true,
+        }
+    }
+
+    /// Ask side: best price is the lowest.
+    pub(crate) fn asks() -> Self {
+        Self {
+            levels: BTreeMap::new(),
+            descending: false,
+        }
+    }
+
+    /// Set the quantity at a price, removing the level if the quantity is zero.
+    pub(crate) fn apply(&mut self, price: Decimal, qty: Decimal) {
+        if qty.is_zero() {
+            self.levels.remove(&price);
+        } else {
+            self.levels.insert(price, qty);
+        }
+    }
+
+    /// Remove all levels.
+    pub(crate) fn clear(&mut self) {
+        self.levels.clear();
+    }
+
+    /// Keep only the best `depth` levels.
+    pub(crate) fn truncate(&mut self, depth: usize) {
+        while self.levels.len() > depth {
+            if self.descending {
+                self.levels.pop_first();
+            } else {
+                self.levels.pop_last();
+            }
+        }
+    }
+
+    /// Up to `depth` levels, best first.
+    pub(crate) fn top(&self, depth: usize) -> Vec<PriceLevel> {
+        let levels = self
+            .levels
+            .iter()
+            .map(|(price, qty)| PriceLevel::new(*price, *qty));
+        if self.descending {
+            levels.rev().take(depth).collect()
+        } else {
+            levels.take(depth).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_book_side_ordering_and_removal() {
+        let mut bids = BookSide::bids();
+        bids.apply(d("100"), d("1"));
+        bids.apply(d("101"), d("2"));
+        bids.apply(d("99"), d("3"));
+        assert_eq!(bids.top(1), vec![PriceLevel::new(d("101"), d("2"))]);
+
+        bids.apply(d("101"), d("0"));
+        assert_eq!(bids.top(10).len(), 2);
+        assert_eq!(bids.top(1)[0].price, d("100"));
+
+        bids.truncate(1);
+        assert_eq!(bids.top(10), vec![PriceLevel::new(d("100"), d("1"))]);
+
+        let mut asks = BookSide::asks();
+        asks.apply(d("102"), d("1"));
+        asks.apply(d("103"), d("1"));
+        asks.truncate(1);
+        assert_eq!(asks.top(10)[0].price, d("102"));
+    }
+}
@@ -109,7 +109,7 @@ pub enum TimeInForce {
 }
 
 /// Order flags for special order behavior.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OrderFlag {
     /// Post-only order - will only make liquidity, not take it
@@ -128,6 +128,34 @@ pub enum OrderFlag {
     VolumeInQuote,
 }
 
+impl std::fmt::Display for OrderFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            OrderFlag::Post => "post",
+            OrderFlag::FeeInBase => "fcib",
+            OrderFlag::FeeInQuote => "fciq",
+            OrderFlag::NoMarketPriceProtection => "nompp",
+            OrderFlag::VolumeInQuote => "viqc",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for OrderFlag {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "post" => Ok(OrderFlag::Post),
+            "fcib" => Ok(OrderFlag::FeeInBase),
+            "fciq" => Ok(OrderFlag::FeeInQuote),
+            "nompp" => Ok(OrderFlag::NoMarketPriceProtection),
+            "viqc" => Ok(OrderFlag::VolumeInQuote),
+            other => Err(format!("unknown order flag: {}", other)),
+        }
+    }
+}
+
 /// Trigger type for conditional orders.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -162,6 +190,82 @@ pub enum AssetClass {
     Cryptocurrency,
 }
 
+/// Legacy X/Z-prefixed REST asset codes and their alternate names.
+///
+/// Only these codes carry the prefix; newer assets such as `ZEUS` or `XCN`
+/// are named as-is.
+const LEGACY_ASSET_CODES: [(&str, &str); 18] = [
+    ("XETC", "ETC"),
+    ("XETH", "ETH"),
+    ("XLTC", "LTC"),
+    ("XMLN", "MLN"),
+    ("XREP", "REP"),
+    ("XXBT", "XBT"),
+    ("XXDG", "XDG"),
+    ("XXLM", "XLM"),
+    ("XXMR", "XMR"),
+    ("XXRP", "XRP"),
+    ("XZEC", "ZEC"),
+    ("ZAUD", "AUD"),
+    ("ZCAD", "CAD"),
+    ("ZCHF", "CHF"),
+    ("ZEUR", "EUR"),
+    ("ZGBP", "GBP"),
+    ("ZJPY", "JPY"),
+    ("ZUSD", "USD"),
+];
+
+/// Alternate name of a REST asset code ("XXBT" -> "XBT", "ZUSD" -> "USD").
+///
+/// Codes outside the legacy set are returned upper-cased but otherwise
+/// unchanged.
+pub(crate) fn asset_altname(code: &str) -> String {
+    let upper = code.to_ascii_uppercase();
+    LEGACY_ASSET_CODES
+        .iter()
+        .find(|(legacy, _)| *legacy == upper)
+        .map(|(_, altname)| altname.to_string())
+        .unwrap_or(upper)
+}
+
+/// WebSocket v2 name of a REST asset code or alternate name
+/// ("XXBT" and "XBT" -> "BTC", "XXDG" -> "DOGE", "ZUSD" -> "USD").
+pub(crate) fn asset_ws_name(code: &str) -> String {
+    let altname = asset_altname(code);
+    match altname.as_str() {
+        "XBT" => "BTC".to_string(),
+        "XDG" => "DOGE".to_string(),
+        _ => altname,
+    }
+}
+
+/// Quote currencies recognised at the end of spot REST pair names, longest
+/// first so "USDT" wins over "USD".
+const QUOTE_CODES: [&str; 25] = [
+    "PYUSD", "ZUSD", "ZEUR", "ZGBP", "ZCAD", "ZJPY", "ZAUD", "ZCHF", "XXBT", "XETH", "USDT",
+    "USDC", "USD", "EUR", "GBP", "CAD", "JPY", "AUD", "CHF", "XBT", "BTC", "ETH", "DAI", "DOT",
+    "POL",
+];
+
+/// Canonical "BASE/QUOTE" name of a spot pair given in any of Kraken's forms
+/// ("XXBTZUSD", "XBTUSD" and "XBT/USD" -> "BTC/USD").
+///
+/// Names without a slash are split at a known quote currency; names that
+/// cannot be split are returned upper-cased.
+pub(crate) fn pair_ws_name(pair: &str) -> String {
+    let upper = pair.trim().to_ascii_uppercase();
+    if let Some((base, quote)) = upper.split_once('/') {
+        return format!("{}/{}", asset_ws_name(base), asset_ws_name(quote));
+    }
+    QUOTE_CODES
+        .iter()
+        .find_map(|quote| {
+            let base = upper.strip_suffix(quote).filter(|base| !base.is_empty())?;
+            Some(format!("{}/{}", asset_ws_name(base), asset_ws_name(quote)))
+        })
+        .unwrap_or(upper)
+}
+
 /// Ledger entry type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -313,4 +417,30 @@ mod tests {
         assert_eq!(OhlcInterval::try_from(1440).unwrap(), OhlcInterval::Day1);
         assert!(OhlcInterval::try_from(999).is_err());
     }
+
+    #[test]
+    fn test_asset_names() {
+        assert_eq!(asset_altname("XXBT"), "XBT");
+        assert_eq!(asset_altname("zusd"), "USD");
+        assert_eq!(asset_ws_name("XXBT"), "BTC");
+        assert_eq!(asset_ws_name("XBT"), "BTC");
+        assert_eq!(asset_ws_name("XXDG"), "DOGE");
+        assert_eq!(asset_ws_name("ZEUR"), "EUR");
+        // Four-letter codes starting with X or Z are not necessarily legacy.
+        assert_eq!(asset_ws_name("ZEUS"), "ZEUS");
+        assert_eq!(asset_ws_name("ZETA"), "ZETA");
+        assert_eq!(asset_ws_name("USDT"), "USDT");
+    }
+
+    #[test]
+    fn test_pair_ws_name() {
+        assert_eq!(pair_ws_name("XXBTZUSD"), "BTC/USD");
+        assert_eq!(pair_ws_name("XBTUSD"), "BTC/USD");
+        assert_eq!(pair_ws_name("xbt/usd"), "BTC/USD");
+        assert_eq!(pair_ws_name("BTC/USD"), "BTC/USD");
+        assert_eq!(pair_ws_name("ETHUSDT"), "ETH/USDT");
+        assert_eq!(pair_ws_name("XETHXXBT"), "ETH/BTC");
+        assert_eq!(pair_ws_name("ZEUSUSD"), "ZEUS/USD");
+        assert_eq!(pair_ws_name("USD"), "USD");
+    }
 }
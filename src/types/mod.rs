@@ -1,8 +1,10 @@
 //! Common types used across the Kraken client library.
 
+pub mod book;
 pub mod common;
 pub mod last_and_data;
 pub mod serde_helpers;
 
+pub use book::{BookView, PriceLevel};
 pub use common::*;
 pub use last_and_data::{LastAndData, LastAndDataWithKey};
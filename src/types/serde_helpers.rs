@@ -458,4 +458,32 @@ mod tests {
         let test: Test = serde_json::from_str(json).unwrap();
         assert_eq!(test.refid.unwrap(), "ABC123");
     }
+
+    #[test]
+    fn test_decimal_from_json_number_is_exact() {
+        let expected = Decimal::from_str("0.123456789012345678901").unwrap();
+
+        let from_text: Decimal = serde_json::from_str("0.123456789012345678901").unwrap();
+        assert_eq!(from_text, expected);
+        let value: serde_json::Value = serde_json::from_str("0.123456789012345678901").unwrap();
+        assert_eq!(serde_json::from_value::<Decimal>(value).unwrap(), expected);
+
+        let from_string: Decimal = serde_json::from_str(r#""0.123456789012345678901""#).unwrap();
+        assert_eq!(from_string, expected);
+        assert_eq!(
+            serde_json::to_string(&expected).unwrap(),
+            r#""0.123456789012345678901""#
+        );
+    }
+
+    #[test]
+    fn test_small_decimal_from_json_value() {
+        // Numbers that round-trip through f64 are handed to `Decimal` as
+        // floats, which format in exponent form below 1e-5.
+        let value: serde_json::Value = serde_json::from_str("-0.00000322").unwrap();
+        assert_eq!(
+            serde_json::from_value::<Decimal>(value).unwrap(),
+            Decimal::from_str("-0.00000322").unwrap()
+        );
+    }
 }
@@ -0,0 +1,576 @@
+//! Pre-flight order validation and rounding.
+//!
+//! Kraken rejects orders whose price is off the pair's tick, whose volume has
+//! too many decimals, or which fall below the pair's minimum volume or cost,
+//! and every rejection still costs rate-limit points. [`OrderRules`] captures
+//! those constraints from [`AssetPair`] or [`FuturesInstrument`] metadata, and
+//! [`OrderValidator`] applies them to REST, WebSocket and futures orders
+//! before they are sent.
+//!
+//! Validation is opt-in: the clients never call it themselves.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use kraken_api_client::validation::{OrderValidator, Rounding};
+//!
+//! let validator = OrderValidator::new(registry.snapshot().await?);
+//!
+//! let mut order = AddOrderRequest::new("XBTUSD", BuySell::Buy, OrderType::Limit, volume)
+//!     .price(price);
+//! validator.normalize_add_order(&mut order, Rounding::Down)?;
+//! validator.validate_add_order(&order)?;
+//! client.add_order(&order).await?;
+//! ```
+
+use std::sync::Arc;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::error::KrakenError;
+use crate::futures::rest::SendOrderRequest;
+use crate::futures::types::FuturesInstrument;
+use crate::pairs::PairSnapshot;
+use crate::spot::rest::private::AddOrderRequest;
+use crate::spot::rest::public::AssetPair;
+use crate::spot::ws::messages::AddOrderParams;
+use crate::types::common::OrderType;
+
+/// How values are moved onto an allowed increment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round towards negative infinity
+    #[default]
+    Down,
+    /// Round to the nearest increment, halves away from zero
+    Nearest,
+}
+
+/// Round `value` to a multiple of `increment`.
+///
+/// Values are returned unchanged if `increment` is not positive.
+pub fn round_to_increment(value: Decimal, increment: Decimal, rounding: Rounding) -> Decimal {
+    if increment <= Decimal::ZERO {
+        return value;
+    }
+    let steps = (value / increment).round_dp_with_strategy(0, strategy(rounding));
+    (steps * increment).normalize()
+}
+
+/// Round `value` to `decimals` decimal places.
+pub fn round_to_decimals(value: Decimal, decimals: u32, rounding: Rounding) -> Decimal {
+    value
+        .round_dp_with_strategy(decimals, strategy(rounding))
+        .normalize()
+}
+
+fn strategy(rounding: Rounding) -> RoundingStrategy {
+    match rounding {
+        Rounding::Down => RoundingStrategy::ToNegativeInfinity,
+        Rounding::Nearest => RoundingStrategy::MidpointAwayFromZero,
+    }
+}
+
+/// Smallest step for a number of decimal places (negative for tens).
+fn decimal_step(decimals: i32) -> Decimal {
+    if decimals >= 0 {
+        Decimal::new(1, decimals.unsigned_abs())
+    } else {
+        Decimal::from(10u64.pow(decimals.unsigned_abs()))
+    }
+}
+
+/// A constraint an order breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderViolation {
+    /// Price is not a multiple of the tick size
+    PriceIncrement {
+        /// Offending price
+        price: Decimal,
+        /// Allowed price increment
+        increment: Decimal,
+    },
+    /// Volume is not a multiple of the lot step
+    VolumeIncrement {
+        /// Offending volume
+        volume: Decimal,
+        /// Allowed volume increment
+        increment: Decimal,
+    },
+    /// Volume is below the minimum order size
+    VolumeBelowMinimum {
+        /// Offending volume
+        volume: Decimal,
+        /// Minimum volume
+        minimum: Decimal,
+    },
+    /// Price times volume is below the minimum order cost
+    CostBelowMinimum {
+        /// Order cost
+        cost: Decimal,
+        /// Minimum cost
+        minimum: Decimal,
+    },
+    /// Conditional close type is unsupported or has the wrong prices
+    InvalidClose {
+        /// Requested close order type
+        ordertype: OrderType,
+        /// Secondary (limit) price, if given
+        price2: Option<Decimal>,
+    },
+}
+
+impl std::fmt::Display for OrderViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderViolation::PriceIncrement { price, increment } => {
+                write!(f, "price {price} is not a multiple of {increment}")
+            }
+            OrderViolation::VolumeIncrement { volume, increment } => {
+                write!(f, "volume {volume} is not a multiple of {increment}")
+            }
+            OrderViolation::VolumeBelowMinimum { volume, minimum } => {
+                write!(f, "volume {volume} is below the minimum of {minimum}")
+            }
+            OrderViolation::CostBelowMinimum { cost, minimum } => {
+                write!(f, "cost {cost} is below the minimum of {minimum}")
+            }
+            OrderViolation::InvalidClose { ordertype, price2 } => match (ordertype, price2) {
+                (OrderType::Limit | OrderType::StopLoss | OrderType::TakeProfit, Some(_)) => {
+                    write!(f, "{ordertype} close takes a single price")
+                }
+                (OrderType::StopLossLimit | OrderType::TakeProfitLimit, None) => {
+                    write!(f, "{ordertype} close needs a trigger and a limit price")
+                }
+                _ => write!(f, "unsupported close order type: {ordertype}"),
+            },
+        }
+    }
+}
+
+/// Price and size constraints of one market.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrderRules {
+    /// Allowed price increment
+    pub price_increment: Option<Decimal>,
+    /// Allowed volume increment
+    pub volume_increment: Option<Decimal>,
+    /// Minimum order volume
+    pub min_volume: Option<Decimal>,
+    /// Minimum order cost (price times volume)
+    pub min_cost: Option<Decimal>,
+}
+
+impl OrderRules {
+    /// Rules of a spot pair: `tick_size` (or `pair_decimals`), `lot_decimals`,
+    /// `ordermin` and `costmin`.
+    pub fn from_asset_pair(pair: &AssetPair) -> Self {
+        Self {
+            price_increment: pair
+                .tick_size
+                .or_else(|| Some(decimal_step(pair.pair_decimals.into()))),
+            volume_increment: Some(decimal_step(pair.lot_decimals.into())),
+            min_volume: pair.ordermin,
+            min_cost: pair.costmin,
+        }
+    }
+
+    /// Rules of a futures instrument: `tickSize` and
+    /// `contractValueTradePrecision`.
+    pub fn from_instrument(instrument: &FuturesInstrument) -> Self {
+        Self {
+            price_increment: instrument.tick_size,
+            volume_increment: instrument.contract_value_trade_precision.map(decimal_step),
+            min_volume: None,
+            min_cost: None,
+        }
+    }
+
+    /// Round a price onto the price increment.
+    pub fn round_price(&self, price: Decimal, rounding: Rounding) -> Decimal {
+        match self.price_increment {
+            Some(increment) => round_to_increment(price, increment, rounding),
+            None => price,
+        }
+    }
+
+    /// Round a volume onto the volume increment.
+    pub fn round_volume(&self, volume: Decimal, rounding: Rounding) -> Decimal {
+        match self.volume_increment {
+            Some(increment) => round_to_increment(volume, increment, rounding),
+            None => volume,
+        }
+    }
+
+    /// Check prices and a volume against the rules.
+    ///
+    /// Volume checks are skipped if `volume` is `None`. `cost_price` is the
+    /// price the minimum cost is checked at, if the order has an absolute one.
+    pub fn check(
+        &self,
+        prices: &[Decimal],
+        volume: Option<Decimal>,
+        cost_price: Option<Decimal>,
+    ) -> Vec<OrderViolation> {
+        let mut violations = Vec::new();
+        if let Some(increment) = self.price_increment.filter(|i| *i > Decimal::ZERO) {
+            for &price in prices {
+                if !(price % increment).is_zero() {
+                    violations.push(OrderViolation::PriceIncrement { price, increment });
+                }
+            }
+        }
+        let Some(volume) = volume else {
+            return violations;
+        };
+        if let Some(increment) = self.volume_increment.filter(|i| *i > Decimal::ZERO) {
+            if !(volume % increment).is_zero() {
+                violations.push(OrderViolation::VolumeIncrement { volume, increment });
+            }
+        }
+        if let Some(minimum) = self.min_volume {
+            if volume < minimum {
+                violations.push(OrderViolation::VolumeBelowMinimum { volume, minimum });
+            }
+        }
+        if let (Some(minimum), Some(price)) = (self.min_cost, cost_price) {
+            let cost = price * volume;
+            if cost < minimum {
+                violations.push(OrderViolation::CostBelowMinimum { cost, minimum });
+            }
+        }
+        violations
+    }
+
+    /// Check prices and a volume, failing with [`KrakenError::InvalidOrder`].
+    pub fn validate(
+        &self,
+        prices: &[Decimal],
+        volume: Option<Decimal>,
+        cost_price: Option<Decimal>,
+    ) -> Result<(), KrakenError> {
+        let violations = self.check(prices, volume, cost_price);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(KrakenError::InvalidOrder(violations))
+        }
+    }
+}
+
+/// Checks and normalizes orders against market metadata.
+#[derive(Debug, Clone)]
+pub struct OrderValidator {
+    snapshot: Arc<PairSnapshot>,
+}
+
+impl OrderValidator {
+    /// Create a validator from loaded market metadata.
+    pub fn new(snapshot: Arc<PairSnapshot>) -> Self {
+        Self { snapshot }
+    }
+
+    /// Rules of a spot pair, by any of its names.
+    pub fn spot_rules(&self, pair: &str) -> Result<OrderRules, KrakenError> {
+        self.snapshot
+            .pair(pair)
+            .and_then(|p| self.snapshot.asset_pair(p))
+            .map(OrderRules::from_asset_pair)
+            .ok_or_else(|| KrakenError::UnknownSymbol(pair.to_string()))
+    }
+
+    /// Rules of a futures instrument, by symbol.
+    pub fn futures_rules(&self, symbol: &str) -> Result<OrderRules, KrakenError> {
+        self.snapshot
+            .instrument(symbol)
+            .map(OrderRules::from_instrument)
+            .ok_or_else(|| KrakenError::UnknownSymbol(symbol.to_string()))
+    }
+
+    /// Check a REST order, including the prices of its conditional close.
+    pub fn validate_add_order(&self, order: &AddOrderRequest) -> Result<(), KrakenError> {
+        let close_price = order
+            .close_ordertype
+            .map_or(order.close_price, |t| tick_price(t, order.close_price));
+        let prices: Vec<Decimal> = [
+            tick_price(order.ordertype, order.price),
+            order.price2,
+            close_price,
+            order.close_price2,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let cost_price = absolute_price(order.ordertype, order.price);
+        self.spot_rules(&order.pair)?
+            .validate(&prices, Some(order.volume), cost_price)
+    }
+
+    /// Round a REST order's prices and volumes onto the pair's increments.
+    pub fn normalize_add_order(
+        &self,
+        order: &mut AddOrderRequest,
+        rounding: Rounding,
+    ) -> Result<(), KrakenError> {
+        let rules = self.spot_rules(&order.pair)?;
+        round_prices(
+            &rules,
+            [
+                &mut order.price,
+                &mut order.price2,
+                &mut order.close_price,
+                &mut order.close_price2,
+            ],
+            rounding,
+        );
+        order.volume = rules.round_volume(order.volume, rounding);
+        order.displayvol = order.displayvol.map(|v| rules.round_volume(v, rounding));
+        Ok(())
+    }
+
+    /// Check a WebSocket order.
+    pub fn validate_ws_order(&self, order: &AddOrderParams) -> Result<(), KrakenError> {
+        let prices: Vec<Decimal> = [order.limit_price, order.trigger_price]
+            .into_iter()
+            .flatten()
+            .collect();
+        let cost_price = absolute_price(order.order_type, order.limit_price);
+        self.spot_rules(&order.symbol)?
+            .validate(&prices, order.order_qty, cost_price)
+    }
+
+    /// Round a WebSocket order's prices and quantities onto the pair's increments.
+    pub fn normalize_ws_order(
+        &self,
+        order: &mut AddOrderParams,
+        rounding: Rounding,
+    ) -> Result<(), KrakenError> {
+        let rules = self.spot_rules(&order.symbol)?;
+        round_prices(
+            &rules,
+            [&mut order.limit_price, &mut order.trigger_price],
+            rounding,
+        );
+        order.order_qty = order.order_qty.map(|q| rules.round_volume(q, rounding));
+        order.display_qty = order.display_qty.map(|q| rules.round_volume(q, rounding));
+        Ok(())
+    }
+
+    /// Check a futures order.
+    pub fn validate_futures_order(&self, order: &SendOrderRequest) -> Result<(), KrakenError> {
+        let prices: Vec<Decimal> = [order.limit_price, order.stop_price]
+            .into_iter()
+            .flatten()
+            .collect();
+        self.futures_rules(&order.symbol)?
+            .validate(&prices, Some(order.size), order.limit_price)
+    }
+
+    /// Round a futures order's prices and size onto the instrument's increments.
+    pub fn normalize_futures_order(
+        &self,
+        order: &mut SendOrderRequest,
+        rounding: Rounding,
+    ) -> Result<(), KrakenError> {
+        let rules = self.futures_rules(&order.symbol)?;
+        round_prices(
+            &rules,
+            [&mut order.limit_price, &mut order.stop_price],
+            rounding,
+        );
+        order.size = rules.round_volume(order.size, rounding);
+        Ok(())
+    }
+}
+
+/// Price the order cost is checked at; trailing offsets and market orders
+/// have none.
+fn absolute_price(order_type: OrderType, price: Option<Decimal>) -> Option<Decimal> {
+    match order_type {
+        OrderType::Market | OrderType::TrailingStop | OrderType::TrailingStopLimit => None,
+        _ => price,
+    }
+}
+
+/// Price checked against the tick size; a trailing order's `price` is an
+/// offset from the market rather than a price level.
+fn tick_price(order_type: OrderType, price: Option<Decimal>) -> Option<Decimal> {
+    match order_type {
+        OrderType::TrailingStop | OrderType::TrailingStopLimit => None,
+        _ => price,
+    }
+}
+
+fn round_prices<const N: usize>(
+    rules: &OrderRules,
+    prices: [&mut Option<Decimal>; N],
+    rounding: Rounding,
+) {
+    for price in prices {
+        *price = price.map(|p| rules.round_price(p, rounding));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::spot::rest::private::ConditionalClose;
+    use crate::types::common::BuySell;
+
+    fn d(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn validator() -> OrderValidator {
+        let asset_pairs = serde_json::from_value(serde_json::json!({
+            "XXBTZUSD": {
+                "altname": "XBTUSD", "wsname": "XBT/USD", "aclass_base": "currency",
+                "base": "XXBT", "aclass_quote": "currency", "quote": "ZUSD",
+                "cost_decimals": 5, "pair_decimals": 1, "lot_decimals": 8, "lot_multiplier": 1,
+                "ordermin": "0.0001", "costmin": "0.5", "tick_size": "0.1"
+            }
+        }))
+        .unwrap();
+        let instruments = serde_json::from_value(serde_json::json!([
+            { "symbol": "PF_XBTUSD", "pair": "XBT:USD", "tickSize": 1, "contractValueTradePrecision": 4 }
+        ]))
+        .unwrap();
+        OrderValidator::new(Arc::new(PairSnapshot::new(
+            asset_pairs,
+            HashMap::new(),
+            instruments,
+        )))
+    }
+
+    #[test]
+    fn test_rounding_helpers() {
+        assert_eq!(
+            round_to_increment(d("50000.37"), d("0.5"), Rounding::Down),
+            d("50000")
+        );
+        assert_eq!(
+            round_to_increment(d("50000.37"), d("0.5"), Rounding::Nearest),
+            d("50000.5")
+        );
+        assert_eq!(
+            round_to_decimals(d("0.123456789"), 8, Rounding::Down),
+            d("0.12345678")
+        );
+        assert_eq!(
+            round_to_decimals(d("0.125"), 2, Rounding::Nearest),
+            d("0.13")
+        );
+        assert_eq!(decimal_step(-1), d("10"));
+    }
+
+    #[test]
+    fn test_rest_order_violations() {
+        let validator = validator();
+        let order = AddOrderRequest::new("BTC/USD", BuySell::Buy, OrderType::Limit, d("0.00001"))
+            .price(d("50000.05"));
+
+        let err = validator.validate_add_order(&order).unwrap_err();
+        let KrakenError::InvalidOrder(violations) = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(
+            violations,
+            vec![
+                OrderViolation::PriceIncrement {
+                    price: d("50000.05"),
+                    increment: d("0.1"),
+                },
+                OrderViolation::VolumeBelowMinimum {
+                    volume: d("0.00001"),
+                    minimum: d("0.0001"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalize_then_validate() {
+        let validator = validator();
+        let mut order = AddOrderRequest::new(
+            "XXBTZUSD",
+            BuySell::Sell,
+            OrderType::Limit,
+            d("0.123456789"),
+        )
+        .price(d("50000.06"));
+        validator
+            .normalize_add_order(&mut order, Rounding::Nearest)
+            .unwrap();
+        assert_eq!(order.price, Some(d("50000.1")));
+        assert_eq!(order.volume, d("0.12345679"));
+        validator.validate_add_order(&order).unwrap();
+
+        let mut ws = AddOrderParams::new(OrderType::Limit, BuySell::Buy, "BTC/USD", "token")
+            .order_qty(d("0.5"))
+            .limit_price(d("100.04"));
+        assert!(validator.validate_ws_order(&ws).is_err());
+        validator
+            .normalize_ws_order(&mut ws, Rounding::Down)
+            .unwrap();
+        assert_eq!(ws.limit_price, Some(d("100")));
+        validator.validate_ws_order(&ws).unwrap();
+    }
+
+    #[test]
+    fn test_rest_order_close_prices() {
+        let validator = validator();
+        let mut order = AddOrderRequest::new("XBTUSD", BuySell::Buy, OrderType::Limit, d("0.5"))
+            .price(d("50000"))
+            .close(ConditionalClose::stop_loss_limit(
+                d("48000.04"),
+                d("47900.06"),
+            ));
+
+        let err = validator.validate_add_order(&order).unwrap_err();
+        let KrakenError::InvalidOrder(violations) = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(violations.len(), 2);
+
+        validator
+            .normalize_add_order(&mut order, Rounding::Nearest)
+            .unwrap();
+        assert_eq!(order.close_price, Some(d("48000")));
+        assert_eq!(order.close_price2, Some(d("47900.1")));
+        validator.validate_add_order(&order).unwrap();
+    }
+
+    #[test]
+    fn test_trailing_offset_skips_tick_check() {
+        let validator = validator();
+        let order =
+            AddOrderRequest::new("XBTUSD", BuySell::Sell, OrderType::TrailingStop, d("0.5"))
+                .price(d("12.25"));
+        validator.validate_add_order(&order).unwrap();
+
+        let limit = order.clone().price2(d("100.05"));
+        assert!(validator.validate_add_order(&limit).is_err());
+    }
+
+    #[test]
+    fn test_futures_order() {
+        let validator = validator();
+        let mut order =
+            SendOrderRequest::limit("PF_XBTUSD", BuySell::Buy, d("0.00015"), d("50000.4"));
+        assert!(validator.validate_futures_order(&order).is_err());
+        validator
+            .normalize_futures_order(&mut order, Rounding::Down)
+            .unwrap();
+        assert_eq!(order.limit_price, Some(d("50000")));
+        assert_eq!(order.size, d("0.0001"));
+        validator.validate_futures_order(&order).unwrap();
+
+        let unknown = SendOrderRequest::market("PF_NOPE", BuySell::Buy, d("1"));
+        assert!(matches!(
+            validator.validate_futures_order(&unknown),
+            Err(KrakenError::UnknownSymbol(_))
+        ));
+    }
+}
@@ -1,14 +1,31 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use base64::{Engine as _, engine::general_purpose::STANDARD};
-use wiremock::matchers::{header, method, path, query_param};
+use futures_util::TryStreamExt;
+use rust_decimal::Decimal;
+use wiremock::matchers::{body_string_contains, header, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
+use kraken_api_client::BuySell;
 use kraken_api_client::auth::NonceProvider;
 use kraken_api_client::auth::{Credentials, StaticCredentials};
 use kraken_api_client::error::KrakenError;
-use kraken_api_client::futures::rest::FuturesRestClient;
+use kraken_api_client::futures::rest::{
+    AccountLogRequest, BatchAction, BatchBuilder, EditOrderRequest, FuturesClient,
+    FuturesRestClient, HistoryRequest, OrderStatusRequest, SendOrderRequest, TransferRequest,
+    WithdrawalRequest,
+};
 use kraken_api_client::futures::sign_futures_request;
+use kraken_api_client::futures::transfer::CollateralMover;
+use kraken_api_client::futures::{
+    ChartResolution, FuturesOrderStatus, FuturesOrderType, FuturesWallet, MarginMode, PnlCurrency,
+    TickType,
+};
+use kraken_api_client::rate_limit::{
+    FuturesRateLimiter, PointsBudget, RateLimitConfig, RateLimitedClient,
+};
+use kraken_api_client::spot::rest::SpotRestClient;
 
 fn build_public_client(server: &MockServer) -> FuturesRestClient {
     FuturesRestClient::builder().base_url(server.uri()).build()
@@ -70,6 +87,137 @@ async fn test_get_orderbook_with_symbol_param() {
     assert_eq!(book.bids.len(), 1);
 }
 
+#[tokio::test]
+async fn test_get_ticker_uses_symbol_path() {
+    let server = MockServer::start().await;
+    let response = serde_json::json!({
+        "result": "success",
+        "ticker": { "symbol": "PF_XBTUSD", "last": 50000.5, "fundingRate": -1.2e-7 },
+        "serverTime": "2024-01-01T00:00:00.000Z"
+    });
+
+    Mock::given(method("GET"))
+        .and(path("/api/v3/tickers/PF_XBTUSD"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = build_public_client(&server);
+    let ticker = client.get_ticker("PF_XBTUSD").await.unwrap().unwrap();
+    assert_eq!(ticker.symbol, "PF_XBTUSD");
+    assert_eq!(ticker.last, "50000.5".parse().unwrap());
+}
+
+#[tokio::test]
+async fn test_get_historical_funding_rates() {
+    let server = MockServer::start().await;
+    let body = r#"{
+        "result": "success",
+        "rates": [
+            {
+                "timestamp": "2024-01-01T01:00:00.000Z",
+                "fundingRate": -8.15861558e-10,
+                "relativeFundingRate": -0.000016898883333333
+            },
+            {
+                "timestamp": "2024-01-01T00:00:00.000Z",
+                "fundingRate": 0.6,
+                "relativeFundingRate": 0.0000125
+            }
+        ],
+        "serverTime": "2024-01-01T02:00:00.000Z"
+    }"#;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v3/historical-funding-rates"))
+        .and(query_param("symbol", "PF_XBTUSD"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+        .mount(&server)
+        .await;
+
+    let client = build_public_client(&server);
+    let series = client
+        .get_historical_funding_rates("PF_XBTUSD")
+        .await
+        .unwrap();
+    assert_eq!(series.symbol(), "PF_XBTUSD");
+    assert_eq!(series.len(), 2);
+    assert_eq!(series.rates()[0].funding_rate, "0.6".parse().unwrap());
+    assert_eq!(
+        series.latest().unwrap().funding_rate,
+        "-0.000000000815861558".parse().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_get_candle_range_follows_pages() {
+    let server = MockServer::start().await;
+    let candle = |time: i64, close: &str| {
+        serde_json::json!({
+            "time": time * 1000,
+            "open": "100",
+            "high": "110",
+            "low": "90",
+            "close": close,
+            "volume": 5
+        })
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/api/charts/v1/mark/PF_XBTUSD/1h"))
+        .and(query_param("from", "0"))
+        .and(query_param("to", "14400"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "candles": [candle(0, "101"), candle(3600, "102")],
+            "more_candles": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/charts/v1/mark/PF_XBTUSD/1h"))
+        .and(query_param("from", "3601"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "candles": [candle(7200, "103"), candle(10800, "104")],
+            "more_candles": false
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = build_public_client(&server);
+    let candles = client
+        .get_candle_range(
+            TickType::Mark,
+            "PF_XBTUSD",
+            ChartResolution::Hour1,
+            0,
+            14_400,
+        )
+        .await
+        .unwrap();
+    let times: Vec<i64> = candles.iter().map(|c| c.time).collect();
+    assert_eq!(times, vec![0, 3600, 7200, 10800]);
+    assert_eq!(candles[3].close, "104".parse().unwrap());
+}
+
+#[tokio::test]
+async fn test_get_chart_symbols() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/charts/v1/trade"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!(["PF_XBTUSD", "PI_XBTUSD"])),
+        )
+        .mount(&server)
+        .await;
+
+    let client = build_public_client(&server);
+    let symbols = client.get_chart_symbols(TickType::Trade).await.unwrap();
+    assert_eq!(symbols, vec!["PF_XBTUSD", "PI_XBTUSD"]);
+}
+
 #[tokio::test]
 async fn test_private_get_accounts_signs_request() {
     let server = MockServer::start().await;
@@ -133,3 +281,823 @@ async fn test_futures_error_response_maps_to_api_error() {
         other => panic!("unexpected error: {other:?}"),
     }
 }
+
+#[tokio::test]
+async fn test_executions_stream_follows_continuation_token() {
+    let server = MockServer::start().await;
+    let secret = STANDARD.encode("test_secret");
+    let credentials = Arc::new(StaticCredentials::new("test_key", &secret));
+    let nonce = 777;
+    let query = "tradeable=PF_XBTUSD&continuation_token=page2";
+    let signature = sign_futures_request(
+        &Credentials::new("test_key", secret),
+        "/api/history/v3/executions",
+        nonce,
+        query,
+    )
+    .unwrap();
+
+    let element = |uid: &str| {
+        let order = |uid: &str| {
+            serde_json::json!({
+                "uid": uid,
+                "tradeable": "PF_XBTUSD",
+                "direction": "Buy",
+                "quantity": "1",
+                "timestamp": 1700000000000i64,
+                "orderType": "Limit"
+            })
+        };
+        serde_json::json!({
+            "uid": uid,
+            "timestamp": 1700000000000i64,
+            "event": {
+                "Execution": {
+                    "execution": {
+                        "uid": uid,
+                        "makerOrder": order("maker"),
+                        "takerOrder": order("taker"),
+                        "timestamp": 1700000000000i64,
+                        "quantity": "1",
+                        "price": "37000.5"
+                    }
+                }
+            }
+        })
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/api/history/v3/executions"))
+        .and(query_param("continuation_token", "page2"))
+        .and(header("Authent", signature))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "accountUid": "acc",
+            "elements": [element("e3")],
+            "len": 1
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/history/v3/executions"))
+        .and(query_param("tradeable", "PF_XBTUSD"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "accountUid": "acc",
+            "continuationToken": "page2",
+            "elements": [element("e1"), element("e2")],
+            "len": 2
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = FuturesRestClient::builder()
+        .base_url(format!("{}/derivatives", server.uri()))
+        .credentials(credentials)
+        .nonce_provider(Arc::new(FixedNonce(nonce)))
+        .build();
+
+    let request = HistoryRequest {
+        tradeable: Some("PF_XBTUSD".into()),
+        ..Default::default()
+    };
+    let elements: Vec<_> = client
+        .executions_stream(request)
+        .try_collect()
+        .await
+        .unwrap();
+    let uids: Vec<&str> = elements.iter().map(|e| e.uid.as_str()).collect();
+    assert_eq!(uids, vec!["e1", "e2", "e3"]);
+    let execution = elements[0].event.execution.as_ref().unwrap();
+    assert_eq!(execution.price, "37000.5".parse().unwrap());
+}
+
+#[tokio::test]
+async fn test_account_log_stream_pages_by_id() {
+    let server = MockServer::start().await;
+    let secret = STANDARD.encode("test_secret");
+    let credentials = Arc::new(StaticCredentials::new("test_key", &secret));
+
+    let entry = |id: u64| {
+        serde_json::json!({
+            "id": id,
+            "date": "2024-01-01T00:00:00.000Z",
+            "asset": "usd",
+            "info": "funding rate change",
+            "realized_funding": -0.00000322
+        })
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/api/history/v2/account-log"))
+        .and(query_param("to", "8"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "accountUid": "acc",
+            "logs": [entry(8), entry(7)]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/history/v2/account-log"))
+        .and(query_param("to", "6"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "accountUid": "acc",
+            "logs": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/history/v2/account-log"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "accountUid": "acc",
+            "logs": [entry(10), entry(9)]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = FuturesRestClient::builder()
+        .base_url(server.uri())
+        .credentials(credentials)
+        .build();
+
+    let ids: Vec<u64> = client
+        .account_log_stream(AccountLogRequest::default())
+        .map_ok(|entry| entry.id)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![10, 9, 8, 7]);
+}
+
+#[tokio::test]
+async fn test_get_account_log_csv() {
+    let server = MockServer::start().await;
+    let secret = STANDARD.encode("test_secret");
+    let credentials = Arc::new(StaticCredentials::new("test_key", &secret));
+    let csv = "uid,dateTime,account,type\n1,2024-01-01,flex,funding rate change\n";
+
+    Mock::given(method("GET"))
+        .and(path("/api/history/v2/accountlogcsv"))
+        .and(query_param("since", "1700000000000"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(csv, "text/csv"))
+        .mount(&server)
+        .await;
+
+    let client = FuturesRestClient::builder()
+        .base_url(server.uri())
+        .credentials(credentials)
+        .build();
+
+    let request = AccountLogRequest {
+        since: Some(1_700_000_000_000),
+        ..Default::default()
+    };
+    assert_eq!(client.get_account_log_csv(&request).await.unwrap(), csv);
+}
+
+#[tokio::test]
+async fn test_leverage_preferences_get_and_set() {
+    let server = MockServer::start().await;
+    let secret = STANDARD.encode("test_secret");
+    let credentials = Arc::new(StaticCredentials::new("test_key", &secret));
+    let nonce = 777;
+    let signature = sign_futures_request(
+        &Credentials::new("test_key", secret),
+        "/api/v3/leveragepreferences",
+        nonce,
+        "symbol=PF_XBTUSD&maxLeverage=10",
+    )
+    .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/api/v3/leveragepreferences"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "result": "success",
+            "serverTime": "2024-01-01T00:00:00.000Z",
+            "leveragePreferences": [{ "symbol": "PF_XBTUSD", "maxLeverage": 10.0 }]
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/leveragepreferences"))
+        .and(query_param("symbol", "PF_XBTUSD"))
+        .and(query_param("maxLeverage", "10"))
+        .and(header("Authent", signature))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "result": "success",
+            "serverTime": "2024-01-01T00:00:00.000Z"
+        })))
+        .mount(&server)
+        .await;
+
+    let client = FuturesRestClient::builder()
+        .base_url(server.uri())
+        .credentials(credentials)
+        .nonce_provider(Arc::new(FixedNonce(nonce)))
+        .build();
+
+    let preferences = client.get_leverage_preferences().await.unwrap();
+    assert_eq!(preferences.len(), 1);
+    assert_eq!(
+        preferences[0].margin_mode(),
+        MarginMode::Isolated {
+            max_leverage: Decimal::from(10)
+        }
+    );
+
+    let response = client
+        .set_leverage_preference(
+            "PF_XBTUSD",
+            MarginMode::Isolated {
+                max_leverage: Decimal::from(10),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.result, "success");
+}
+
+#[tokio::test]
+async fn test_pnl_preferences_get_and_set() {
+    let server = MockServer::start().await;
+    let secret = STANDARD.encode("test_secret");
+    let credentials = Arc::new(StaticCredentials::new("test_key", &secret));
+
+    Mock::given(method("GET"))
+        .and(path("/api/v3/pnlpreferences"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "result": "success",
+            "preferences": [
+                { "symbol": "PF_XBTUSD", "pnlCurrency": "BTC" },
+                { "symbol": "PF_ETHUSD", "pnlCurrency": "CHF" }
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("PUT"))
+        .and(path("/api/v3/pnlpreferences"))
+        .and(query_param("symbol", "PF_ETHUSD"))
+        .and(query_param("pnlPreference", "USDC"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "result": "success" })),
+        )
+        .mount(&server)
+        .await;
+
+    let client = FuturesRestClient::builder()
+        .base_url(server.uri())
+        .credentials(credentials)
+        .build();
+
+    let preferences = client.get_pnl_preferences().await.unwrap();
+    assert_eq!(preferences[0].pnl_currency, PnlCurrency::Btc);
+    assert_eq!(
+        preferences[1].pnl_currency,
+        PnlCurrency::Other("CHF".to_string())
+    );
+
+    let response = client
+        .set_pnl_preference("PF_ETHUSD", PnlCurrency::Usdc)
+        .await
+        .unwrap();
+    assert_eq!(response.result, "success");
+}
+
+#[tokio::test]
+async fn test_transfer_and_withdraw_to_spot() {
+    let server = MockServer::start().await;
+    let secret = STANDARD.encode("test_secret");
+    let credentials = Arc::new(StaticCredentials::new("test_key", &secret));
+
+    Mock::given(method("POST"))
+        .and(path("/api/v3/transfer"))
+        .and(body_string_contains("fromAccount=cash"))
+        .and(body_string_contains("toAccount=flex"))
+        .and(body_string_contains("unit=usd"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "result": "success" })),
+        )
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/v3/withdrawal"))
+        .and(body_string_contains("currency=usd"))
+        .and(body_string_contains("sourceWallet=flex"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "result": "success",
+            "uid": "w-1"
+        })))
+        .mount(&server)
+        .await;
+
+    let client = FuturesRestClient::builder()
+        .base_url(server.uri())
+        .credentials(credentials)
+        .build();
+
+    let request = TransferRequest::new(
+        FuturesWallet::Cash,
+        FuturesWallet::Flex,
+        "usd",
+        Decimal::from(100),
+    );
+    assert_eq!(client.transfer(&request).await.unwrap().result, "success");
+
+    let request =
+        WithdrawalRequest::new("usd", Decimal::from(50)).source_wallet(FuturesWallet::Flex);
+    let response = client.withdraw_to_spot(&request).await.unwrap();
+    assert_eq!(response.uid.as_deref(), Some("w-1"));
+}
+
+#[tokio::test]
+async fn test_collateral_mover_tracks_both_sides() {
+    let server = MockServer::start().await;
+    let secret = STANDARD.encode("test_secret");
+
+    Mock::given(method("GET"))
+        .and(path("/api/history/v2/account-log"))
+        .and(query_param("count", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "logs": [{
+                "id": 41,
+                "date": "2024-01-01T00:00:00.000Z",
+                "asset": "usd",
+                "info": "futures trade"
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/0/private/WalletTransfer"))
+        .and(body_string_contains("from=Spot+Wallet"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": { "refid": "BOG5AE5-KSCNR4-VPNPEV" }
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/0/private/Ledgers"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": {
+                "ledger": {
+                    "L1": {
+                        "refid": "BOG5AE5-KSCNR4-VPNPEV",
+                        "time": 1700000000.0,
+                        "type": "transfer",
+                        "subtype": "spottofutures",
+                        "aclass": "currency",
+                        "asset": "ZUSD",
+                        "amount": "-100.0000",
+                        "fee": "0.0000",
+                        "balance": "0.0000"
+                    }
+                },
+                "count": 1
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/history/v2/account-log"))
+        .and(query_param("from", "42"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "logs": [{
+                "id": 42,
+                "date": "2024-01-01T00:00:01.000Z",
+                "asset": "usd",
+                "info": "cross-exchange transfer",
+                "old_balance": 5,
+                "new_balance": 105
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    let spot = SpotRestClient::builder()
+        .base_url(server.uri())
+        .credentials(Arc::new(StaticCredentials::new("test_key", &secret)))
+        .build();
+    let futures = FuturesRestClient::builder()
+        .base_url(server.uri())
+        .credentials(Arc::new(StaticCredentials::new("test_key", &secret)))
+        .build();
+
+    let transfer = CollateralMover::new(&spot, &futures)
+        .poll_interval(Duration::from_millis(10))
+        .move_to_futures("USD", Decimal::from(100))
+        .await
+        .unwrap();
+    assert!(transfer.is_complete());
+    assert_eq!(
+        transfer.spot_refid.as_deref(),
+        Some("BOG5AE5-KSCNR4-VPNPEV")
+    );
+    assert_eq!(transfer.futures_entry.unwrap().id, 42);
+}
+
+#[tokio::test]
+async fn test_withdraw_to_spot_is_not_retried() {
+    let server = MockServer::start().await;
+    let secret = STANDARD.encode("test_secret");
+
+    Mock::given(method("POST"))
+        .and(path("/api/v3/withdrawal"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/accounts"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = FuturesRestClient::builder()
+        .base_url(server.uri())
+        .credentials(Arc::new(StaticCredentials::new("test_key", &secret)))
+        .max_retries(1)
+        .build();
+
+    let request = WithdrawalRequest::new("usd", Decimal::from(50));
+    assert!(client.withdraw_to_spot(&request).await.is_err());
+    assert!(client.get_accounts().await.is_err());
+}
+
+#[tokio::test]
+async fn test_collateral_mover_pages_both_sides() {
+    let server = MockServer::start().await;
+    let secret = STANDARD.encode("test_secret");
+
+    Mock::given(method("GET"))
+        .and(path("/api/history/v2/account-log"))
+        .and(query_param("count", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "logs": [{
+                "id": 41,
+                "date": "2024-01-01T00:00:00.000Z",
+                "asset": "usd",
+                "info": "futures trade"
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/v3/withdrawal"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "result": "success",
+            "uid": "w-1"
+        })))
+        .mount(&server)
+        .await;
+
+    // The second ledger page holds the transfer; the first holds another one.
+    Mock::given(method("POST"))
+        .and(path("/0/private/Ledgers"))
+        .and(body_string_contains("ofs=1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": {
+                "ledger": {
+                    "L2": {
+                        "refid": "R2",
+                        "time": 4102444800.0,
+                        "type": "transfer",
+                        "aclass": "currency",
+                        "asset": "ZUSD",
+                        "amount": "100.0000",
+                        "fee": "0.0000",
+                        "balance": "100.0000"
+                    }
+                },
+                "count": 2
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/0/private/Ledgers"))
+        .and(body_string_contains("asset=USD"))
+        .and(body_string_contains("type=transfer"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": {
+                "ledger": {
+                    "L3": {
+                        "refid": "R3",
+                        "time": 4102444801.0,
+                        "type": "transfer",
+                        "aclass": "currency",
+                        "asset": "ZUSD",
+                        "amount": "-7.0000",
+                        "fee": "0.0000",
+                        "balance": "93.0000"
+                    }
+                },
+                "count": 2
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // The second account log page holds the withdrawal.
+    Mock::given(method("GET"))
+        .and(path("/api/history/v2/account-log"))
+        .and(query_param("from", "42"))
+        .and(query_param("to", "43"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "logs": [{
+                "id": 43,
+                "date": "2024-01-01T00:00:01.000Z",
+                "asset": "usd",
+                "info": "cross-exchange transfer",
+                "booking_uid": "w-1"
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/history/v2/account-log"))
+        .and(query_param("from", "42"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "logs": [{
+                "id": 44,
+                "date": "2024-01-01T00:00:02.000Z",
+                "asset": "usd",
+                "info": "futures trade"
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let spot = SpotRestClient::builder()
+        .base_url(server.uri())
+        .credentials(Arc::new(StaticCredentials::new("test_key", &secret)))
+        .build();
+    let futures = FuturesRestClient::builder()
+        .base_url(server.uri())
+        .credentials(Arc::new(StaticCredentials::new("test_key", &secret)))
+        .build();
+
+    let mover = CollateralMover::new(&spot, &futures);
+    let mut transfer = mover
+        .start_futures_to_spot("USD", Decimal::from(100))
+        .await
+        .unwrap();
+    assert!(mover.poll(&mut transfer).await.unwrap());
+    assert_eq!(transfer.spot_entry.unwrap().refid, "R2");
+    assert_eq!(transfer.futures_entry.unwrap().id, 43);
+}
+
+#[tokio::test]
+async fn test_get_order_status_by_cli_ord_id() {
+    let server = MockServer::start().await;
+    let secret = STANDARD.encode("test_secret");
+    let credentials = Arc::new(StaticCredentials::new("test_key", &secret));
+
+    Mock::given(method("POST"))
+        .and(path("/api/v3/orders/status"))
+        .and(body_string_contains("cliOrdIds=my-order"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "result": "success",
+            "serverTime": "2024-01-15T10:00:01.000Z",
+            "orders": [{
+                "order": {
+                    "type": "ORDER",
+                    "orderId": "abc",
+                    "cliOrdId": "my-order",
+                    "symbol": "PF_XBTUSD",
+                    "side": "buy",
+                    "quantity": 2,
+                    "filled": 2,
+                    "limitPrice": 50000,
+                    "reduceOnly": false,
+                    "timestamp": "2024-01-15T10:00:00.000Z",
+                    "lastUpdateTimestamp": "2024-01-15T10:00:01.000Z"
+                },
+                "status": "FULLY_EXECUTED",
+                "updateReason": "LIMIT_FILLED",
+                "error": null
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    let client = FuturesRestClient::builder()
+        .base_url(server.uri())
+        .credentials(credentials)
+        .build();
+
+    let orders = client
+        .get_order_status(&OrderStatusRequest::by_cli_ord_id("my-order"))
+        .await
+        .unwrap();
+    assert_eq!(orders.len(), 1);
+    assert!(orders[0].lifecycle.is_final());
+    assert_eq!(orders[0].order.status, FuturesOrderStatus::Filled);
+    assert_eq!(orders[0].order.order_type, FuturesOrderType::Limit);
+    assert_eq!(orders[0].update_reason.as_deref(), Some("LIMIT_FILLED"));
+}
+
+#[tokio::test]
+async fn test_execute_batch_maps_results_to_intents() {
+    let server = MockServer::start().await;
+    let secret = STANDARD.encode("test_secret");
+    let credentials = Arc::new(StaticCredentials::new("test_key", &secret));
+
+    // `{"batchOrder":[...` form-encoded in the `json` field.
+    Mock::given(method("POST"))
+        .and(path("/api/v3/batchorder"))
+        .and(body_string_contains("json=%7B%22batchOrder%22"))
+        .and(body_string_contains("%22order%22%3A%22send%22"))
+        .and(body_string_contains("%22order_tag%22%3A%221%22"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "result": "success",
+            "serverTime": "2024-01-15T10:00:01.000Z",
+            "batchStatus": [
+                { "order_tag": "1", "order_id": "new-ask", "status": "placed" },
+                { "order_tag": "0", "status": "insufficientAvailableFunds" },
+                { "order_id": "resting", "status": "edited" },
+                { "order_id": "old-bid", "status": "cancelled" }
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let client = FuturesRestClient::builder()
+        .base_url(server.uri())
+        .credentials(credentials)
+        .build();
+
+    let batch = BatchBuilder::new()
+        .cancel("old-bid")
+        .place(SendOrderRequest::limit(
+            "PF_XBTUSD",
+            BuySell::Buy,
+            Decimal::ONE,
+            Decimal::from(49_000),
+        ))
+        .place(SendOrderRequest::limit(
+            "PF_XBTUSD",
+            BuySell::Sell,
+            Decimal::ONE,
+            Decimal::from(51_000),
+        ))
+        .edit(EditOrderRequest::by_order_id("resting").limit_price(Decimal::from(50_500)));
+
+    let results = client.execute_batch(&batch).await.unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].as_ref().unwrap().action, BatchAction::Cancel);
+    let failure = results[1].as_ref().unwrap_err();
+    assert_eq!(failure.action, BatchAction::Place);
+    assert_eq!(failure.status, "insufficientAvailableFunds");
+    assert_eq!(
+        results[2].as_ref().unwrap().order_id.as_deref(),
+        Some("new-ask")
+    );
+    assert_eq!(results[3].as_ref().unwrap().action, BatchAction::Edit);
+}
+
+async fn candle_count<C: FuturesClient>(client: &C) -> Result<usize, KrakenError> {
+    let candles = client
+        .get_candle_range(
+            TickType::Trade,
+            "PF_XBTUSD",
+            ChartResolution::Min1,
+            0,
+            1_000,
+        )
+        .await?;
+    Ok(candles.len())
+}
+
+#[tokio::test]
+async fn test_rate_limited_client_wraps_futures_client() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/charts/v1/trade/PF_XBTUSD/1m"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "candles": [
+                { "time": 60000, "open": "1", "high": "1", "low": "1", "close": "1", "volume": 1 },
+                { "time": 120000, "open": "1", "high": "1", "low": "1", "close": "1", "volume": 1 }
+            ],
+            "more_candles": false
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = build_public_client(&server);
+    assert_eq!(candle_count(&client).await.unwrap(), 2);
+
+    let limited = RateLimitedClient::new(client, RateLimitConfig::default());
+    assert_eq!(candle_count(&limited).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_rate_limited_client_charges_futures_budget() {
+    let server = MockServer::start().await;
+    let secret = STANDARD.encode("test_secret");
+
+    Mock::given(method("GET"))
+        .and(path("/api/v3/accounts"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "result": "success",
+            "accounts": {}
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = FuturesRestClient::builder()
+        .base_url(server.uri())
+        .credentials(Arc::new(StaticCredentials::new("test_key", &secret)))
+        .build();
+    // Room for exactly one accounts request (cost 2), refilling in 200ms.
+    let limiter = FuturesRateLimiter::with_budgets(
+        PointsBudget::new(2, Duration::from_millis(200)),
+        PointsBudget::history(),
+    );
+    let limited =
+        RateLimitedClient::new(client, RateLimitConfig::default()).with_futures_limiter(limiter);
+
+    let start = std::time::Instant::now();
+    limited.get_accounts().await.unwrap();
+    limited.get_accounts().await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn test_rate_limited_client_charges_history_streams() {
+    let server = MockServer::start().await;
+    let secret = STANDARD.encode("test_secret");
+
+    let entry = |id: u64| {
+        serde_json::json!({
+            "id": id,
+            "date": "2024-01-01T00:00:00.000Z",
+            "asset": "usd",
+            "info": "funding rate change"
+        })
+    };
+    Mock::given(method("GET"))
+        .and(path("/api/history/v2/account-log"))
+        .and(query_param("to", "8"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "accountUid": "acc",
+            "logs": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/history/v2/account-log"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "accountUid": "acc",
+            "logs": [entry(10), entry(9)]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = FuturesRestClient::builder()
+        .base_url(server.uri())
+        .credentials(Arc::new(StaticCredentials::new("test_key", &secret)))
+        .build();
+    // Room for exactly one default-sized history page (cost 3), refilling in 200ms.
+    let limiter = FuturesRateLimiter::with_budgets(
+        PointsBudget::derivatives(),
+        PointsBudget::new(3, Duration::from_millis(200)),
+    );
+    let limited =
+        RateLimitedClient::new(client, RateLimitConfig::default()).with_futures_limiter(limiter);
+
+    async fn log_ids<C: FuturesClient>(client: &C) -> Result<Vec<u64>, KrakenError> {
+        client
+            .account_log_stream(AccountLogRequest::default())
+            .map_ok(|entry| entry.id)
+            .try_collect()
+            .await
+    }
+
+    let start = std::time::Instant::now();
+    assert_eq!(log_ids(&limited).await.unwrap(), vec![10, 9]);
+    assert!(start.elapsed() >= Duration::from_millis(150));
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use kraken_api_client::error::KrakenError;
+use kraken_api_client::futures::rest::FuturesRestClient;
+use kraken_api_client::pairs::PairRegistry;
+use kraken_api_client::spot::rest::SpotRestClient;
+
+async fn mount_metadata(spot: &MockServer, futures: &MockServer, loads: u64) {
+    Mock::given(method("GET"))
+        .and(path("/0/public/AssetPairs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": {
+                "XXBTZUSD": {
+                    "altname": "XBTUSD",
+                    "wsname": "XBT/USD",
+                    "aclass_base": "currency",
+                    "base": "XXBT",
+                    "aclass_quote": "currency",
+                    "quote": "ZUSD",
+                    "cost_decimals": 5,
+                    "pair_decimals": 1,
+                    "lot_decimals": 8,
+                    "lot_multiplier": 1,
+                    "ordermin": "0.0001",
+                    "tick_size": "0.1"
+                }
+            }
+        })))
+        .expect(loads)
+        .mount(spot)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/0/public/Assets"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": {
+                "XXBT": { "aclass": "currency", "altname": "XBT", "decimals": 10, "display_decimals": 5 },
+                "ZUSD": { "aclass": "currency", "altname": "USD", "decimals": 4, "display_decimals": 2 }
+            }
+        })))
+        .expect(loads)
+        .mount(spot)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v3/instruments"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "result": "success",
+            "instruments": [
+                { "symbol": "PF_XBTUSD", "pair": "XBT:USD", "tickSize": 1, "contractSize": 1 }
+            ],
+            "serverTime": "2024-01-15T10:00:00.000Z"
+        })))
+        .expect(loads)
+        .mount(futures)
+        .await;
+}
+
+#[tokio::test]
+async fn test_registry_resolves_aliases_across_venues() {
+    let spot = MockServer::start().await;
+    let futures = MockServer::start().await;
+    mount_metadata(&spot, &futures, 1).await;
+
+    let registry = PairRegistry::new(SpotRestClient::builder().base_url(spot.uri()).build())
+        .with_futures(FuturesRestClient::builder().base_url(futures.uri()).build());
+
+    let pair = registry.resolve("pf_xbtusd").await.unwrap();
+    assert_eq!(pair.name, "BTC/USD");
+    assert_eq!(pair.rest(), Some("XXBTZUSD"));
+    assert_eq!(registry.resolve("XBTUSD").await.unwrap(), pair);
+    assert_eq!(registry.resolve_asset("XXBT").await.unwrap().name, "BTC");
+
+    let snapshot = registry.snapshot().await.unwrap();
+    assert_eq!(
+        snapshot.asset_pair(&pair).unwrap().tick_size,
+        Some("0.1".parse().unwrap())
+    );
+
+    let err = registry.resolve("NOPE").await.unwrap_err();
+    assert!(matches!(err, KrakenError::UnknownSymbol(alias) if alias == "NOPE"));
+}
+
+#[tokio::test]
+async fn test_registry_reloads_after_ttl() {
+    let spot = MockServer::start().await;
+    let futures = MockServer::start().await;
+    mount_metadata(&spot, &futures, 2).await;
+
+    let registry = PairRegistry::new(SpotRestClient::builder().base_url(spot.uri()).build())
+        .with_futures(FuturesRestClient::builder().base_url(futures.uri()).build())
+        .ttl(Duration::from_millis(50));
+
+    registry.resolve("BTC/USD").await.unwrap();
+    registry.resolve("XBT/USD").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    registry.resolve("XXBTZUSD").await.unwrap();
+}
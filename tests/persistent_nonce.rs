@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use kraken_api_client::auth::{NonceProvider, PersistentNonce};
+
+/// Set in child processes to the nonce file they should draw from.
+const CHILD_ENV: &str = "KRAKEN_PERSISTENT_NONCE_CHILD";
+const PER_WORKER: usize = 500;
+
+fn temp_path(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("kraken-nonce-it-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Draw nonces and check they increase within this worker.
+fn draw(provider: &PersistentNonce) -> Vec<u64> {
+    let nonces: Vec<u64> = (0..PER_WORKER).map(|_| provider.next_nonce()).collect();
+    assert!(nonces.windows(2).all(|pair| pair[0] < pair[1]));
+    nonces
+}
+
+fn assert_unique(batches: impl IntoIterator<Item = Vec<u64>>) {
+    let mut seen = HashSet::new();
+    for nonce in batches.into_iter().flatten() {
+        assert!(seen.insert(nonce), "nonce {} issued twice", nonce);
+    }
+}
+
+#[test]
+fn test_unique_across_threads() {
+    let path = temp_path("threads");
+    let shared = Arc::new(PersistentNonce::with_block_size(&path, 16).unwrap());
+
+    // Half the threads share one provider, half open their own on the file.
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let shared = shared.clone();
+            let path = path.clone();
+            thread::spawn(move || {
+                if i % 2 == 0 {
+                    draw(&shared)
+                } else {
+                    draw(&PersistentNonce::with_block_size(&path, 16).unwrap())
+                }
+            })
+        })
+        .collect();
+
+    assert_unique(handles.into_iter().map(|h| h.join().unwrap()));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_default_provider_orders_nonces_across_providers() {
+    let path = temp_path("ordered");
+    // Records nonces in the order they were issued, across all providers.
+    let issued = Arc::new(Mutex::new(Vec::new()));
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let path = path.clone();
+            let issued = issued.clone();
+            thread::spawn(move || {
+                let provider = PersistentNonce::open(&path).unwrap();
+                for _ in 0..100 {
+                    let mut issued = issued.lock().unwrap();
+                    issued.push(provider.next_nonce());
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let issued = issued.lock().unwrap();
+    assert!(issued.windows(2).all(|pair| pair[0] < pair[1]));
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Entry point for child processes spawned by `test_unique_across_processes`.
+#[test]
+fn child_process_draws_nonces() {
+    let Ok(path) = std::env::var(CHILD_ENV) else {
+        return;
+    };
+    let provider = PersistentNonce::with_block_size(path, 16).unwrap();
+    for nonce in draw(&provider) {
+        println!("nonce={}", nonce);
+    }
+}
+
+#[test]
+fn test_unique_across_processes() {
+    let path = temp_path("processes");
+    let exe = std::env::current_exe().unwrap();
+
+    // Spawn all children before waiting so they contend for the file.
+    let children: Vec<_> = (0..4)
+        .map(|_| {
+            Command::new(&exe)
+                .args([
+                    "child_process_draws_nonces",
+                    "--exact",
+                    "--nocapture",
+                    "--test-threads=1",
+                ])
+                .env(CHILD_ENV, &path)
+                .stdout(Stdio::piped())
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+
+    let batches: Vec<Vec<u64>> = children
+        .into_iter()
+        .map(|child| {
+            let output = child.wait_with_output().unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stdout)
+                .unwrap()
+                .lines()
+                // The harness may prefix the first line with the test name.
+                .filter_map(|line| line.split_once("nonce="))
+                .map(|(_, nonce)| nonce.parse().unwrap())
+                .collect()
+        })
+        .collect();
+
+    assert!(batches.iter().all(|batch| batch.len() == PER_WORKER));
+    assert_unique(batches);
+    std::fs::remove_file(&path).unwrap();
+}
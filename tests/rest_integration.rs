@@ -1,16 +1,20 @@
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use base64::{engine::general_purpose::STANDARD, Engine as _};
-use wiremock::matchers::{body_string_contains, method, path};
-use wiremock::{Mock, MockServer, ResponseTemplate};
+use wiremock::matchers::{body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
 
-use kraken_api_client::auth::StaticCredentials;
+use kraken_api_client::auth::{Credentials, RotatingCredentials, StaticCredentials};
+use kraken_api_client::rate_limit::{RateLimitedClient, SharedTradingLimiter, TradingRateLimiter};
 use kraken_api_client::spot::rest::private::{
-    DepositMethodsRequest, DepositStatusRequest, EarnAllocateRequest,
+    AddOrderRequest, CancelOrderRequest, DepositMethodsRequest, DepositStatusRequest, EarnAllocateRequest,
     EarnAllocationStatusRequest, EarnStrategiesRequest, TransferStatusRequest,
     WalletTransferRequest, WithdrawCancelRequest, WithdrawInfoRequest, WithdrawStatusRequest,
 };
-use kraken_api_client::spot::rest::SpotRestClient;
+use kraken_api_client::spot::rest::{KrakenClient, SpotRestClient};
+use kraken_api_client::types::VerificationTier;
+use kraken_api_client::{BuySell, OrderType};
 use rust_decimal::Decimal;
 
 fn build_client(server: &MockServer) -> SpotRestClient {
@@ -292,3 +296,131 @@ async fn test_earn_allocate_and_status() {
         .unwrap();
     assert!(status.pending);
 }
+
+#[tokio::test]
+async fn test_rotated_credentials_sign_next_request() {
+    let server = MockServer::start().await;
+    let balance_response = serde_json::json!({
+        "error": [],
+        "result": { "ZUSD": "100.0000" }
+    });
+
+    for key in ["old_key", "new_key"] {
+        Mock::given(method("POST"))
+            .and(path("/0/private/Balance"))
+            .and(header("API-Key", key))
+            .respond_with(ResponseTemplate::new(200).set_body_json(balance_response.clone()))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let secret = STANDARD.encode("test_secret");
+    let credentials = Arc::new(RotatingCredentials::new(Credentials::new(
+        "old_key",
+        secret.clone(),
+    )));
+    let client = SpotRestClient::builder()
+        .base_url(server.uri())
+        .credentials(credentials.clone())
+        .build();
+
+    client.get_account_balance().await.unwrap();
+    credentials.rotate(Credentials::new("new_key", secret));
+    client.get_account_balance().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_otp_is_sent_with_private_requests() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/0/private/Balance"))
+        .and(body_string_contains("otp=two+factor"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": { "ZUSD": "100.0000" }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let credentials = Credentials::new("test_key", STANDARD.encode("test_secret"))
+        .with_otp("two factor");
+    let client = SpotRestClient::builder()
+        .base_url(server.uri())
+        .credentials(Arc::new(StaticCredentials::from(credentials)))
+        .build();
+
+    client.get_account_balance().await.unwrap();
+}
+
+/// Answers AddOrder with a new transaction ID each time.
+struct NewOrderResponder(AtomicUsize);
+
+impl Respond for NewOrderResponder {
+    fn respond(&self, _request: &Request) -> ResponseTemplate {
+        let n = self.0.fetch_add(1, Ordering::SeqCst);
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": {
+                "descr": { "order": "buy 1.00000000 XBTUSD @ limit 40000.0" },
+                "txid": [format!("O{:05}-ABCDE-FGHIJK", n)]
+            }
+        }))
+    }
+}
+
+#[tokio::test]
+async fn test_rate_limited_client_forgets_closed_orders() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/0/private/AddOrder"))
+        .and(body_string_contains("pair=ETHUSD"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": ["EOrder:Insufficient funds"]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/0/private/AddOrder"))
+        .respond_with(NewOrderResponder(AtomicUsize::new(0)))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/0/private/CancelOrder"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": { "count": 1 }
+        })))
+        .mount(&server)
+        .await;
+
+    // A roomy budget keeps cancel penalties from slowing the test down.
+    let client = RateLimitedClient::with_tier(build_client(&server), VerificationTier::Pro)
+        .with_trading_limiter(SharedTradingLimiter::new(TradingRateLimiter::new(1000, 100.0)));
+    let order = |pair: &str| {
+        AddOrderRequest::new(pair, BuySell::Buy, OrderType::Limit, Decimal::ONE)
+            .price(Decimal::from(40000))
+    };
+
+    let mut txids = Vec::new();
+    for _ in 0..5 {
+        let response = client.add_order(&order("XBTUSD")).await.unwrap();
+        txids.extend(response.txid.unwrap());
+    }
+    assert_eq!(client.trading_limiter().tracked_orders(), 5);
+
+    // A rejected order is not left behind under its provisional key.
+    assert!(client.add_order(&order("ETHUSD")).await.is_err());
+    assert_eq!(client.trading_limiter().tracked_orders(), 5);
+
+    for txid in &txids {
+        client
+            .cancel_order(&CancelOrderRequest::new(txid.clone()))
+            .await
+            .unwrap();
+    }
+    assert_eq!(client.trading_limiter().tracked_orders(), 0);
+}
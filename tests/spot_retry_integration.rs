@@ -0,0 +1,359 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use rust_decimal::Decimal;
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+use kraken_api_client::auth::{IncreasingNonce, NonceProvider, StaticCredentials};
+use kraken_api_client::error::KrakenErrorCode;
+use kraken_api_client::spot::rest::private::{
+    AddOrderRequest, CancelOrderRequest, WalletTransferRequest,
+};
+use kraken_api_client::spot::rest::{RetryPolicy, SpotRestClient};
+use kraken_api_client::{BuySell, KrakenError, OrderType};
+
+fn build_client(server: &MockServer) -> SpotRestClient {
+    let secret = STANDARD.encode("test_secret");
+    let credentials = Arc::new(StaticCredentials::new("test_key", secret));
+    SpotRestClient::builder()
+        .base_url(server.uri())
+        .credentials(credentials)
+        .retry_policy(
+            RetryPolicy::new().backoff(Duration::from_millis(1), Duration::from_millis(5)),
+        )
+        .build()
+}
+
+async fn mount_nonce_rejection(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/0/private/Balance"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": ["EAPI:Invalid nonce"]
+        })))
+        .up_to_n_times(1)
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/0/private/Balance"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": { "ZUSD": "100.0000" }
+        })))
+        .mount(server)
+        .await;
+}
+
+fn unavailable() -> ResponseTemplate {
+    ResponseTemplate::new(503).set_body_string("Service Unavailable")
+}
+
+#[tokio::test]
+async fn test_public_get_retries_server_errors() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/0/public/Time"))
+        .respond_with(unavailable())
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/0/public/Time"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": { "unixtime": 1705312800, "rfc1123": "Mon, 15 Jan 24 10:00:00 +0000" }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let time = build_client(&server).get_server_time().await.unwrap();
+    assert_eq!(time.unixtime, 1705312800);
+}
+
+#[tokio::test]
+async fn test_funds_transfer_is_not_resent() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/0/private/WalletTransfer"))
+        .respond_with(unavailable())
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = WalletTransferRequest::new("USDT", "spot", "futures", Decimal::TEN);
+    let err = build_client(&server)
+        .wallet_transfer(&request)
+        .await
+        .unwrap_err();
+    assert!(err.is_retryable());
+}
+
+#[tokio::test]
+async fn test_server_error_keeps_kraken_error_body() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/0/private/WalletTransfer"))
+        .respond_with(ResponseTemplate::new(500).set_body_json(serde_json::json!({
+            "error": ["EGeneral:Invalid arguments"]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = WalletTransferRequest::new("USDT", "spot", "futures", Decimal::TEN);
+    let err = build_client(&server)
+        .wallet_transfer(&request)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(KrakenErrorCode::InvalidArguments));
+}
+
+#[tokio::test]
+async fn test_resent_cancel_of_unknown_order_succeeds() {
+    let server = MockServer::start().await;
+
+    // The cancel lands, but the response is lost.
+    Mock::given(method("POST"))
+        .and(path("/0/private/CancelOrder"))
+        .respond_with(unavailable())
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/0/private/CancelOrder"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": ["EOrder:Unknown order"]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = CancelOrderRequest::new("OABCDE-12345-FGHIJK");
+    let response = build_client(&server).cancel_order(&request).await.unwrap();
+    assert_eq!(response.count, 1);
+}
+
+#[tokio::test]
+async fn test_cancel_of_unknown_order_fails_on_first_attempt() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/0/private/CancelOrder"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": ["EOrder:Unknown order"]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = CancelOrderRequest::new("OABCDE-12345-FGHIJK");
+    let err = build_client(&server).cancel_order(&request).await.unwrap_err();
+    assert_eq!(err.code(), Some(KrakenErrorCode::UnknownOrder));
+}
+
+#[tokio::test]
+async fn test_add_order_without_reference_is_not_resent() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/0/private/AddOrder"))
+        .respond_with(unavailable())
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = AddOrderRequest::new("XBTUSD", BuySell::Buy, OrderType::Market, Decimal::ONE);
+    let err = build_client(&server).add_order(&request).await.unwrap_err();
+    assert!(matches!(err, KrakenError::Http(_)));
+}
+
+#[tokio::test]
+async fn test_add_order_with_only_userref_is_not_resent() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/0/private/AddOrder"))
+        .respond_with(unavailable())
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // A userref is not unique, so it cannot confirm whether the order landed.
+    let request =
+        AddOrderRequest::new("XBTUSD", BuySell::Buy, OrderType::Market, Decimal::ONE).userref(7);
+    let err = build_client(&server).add_order(&request).await.unwrap_err();
+    assert!(matches!(err, KrakenError::Http(_)));
+}
+
+#[tokio::test]
+async fn test_add_order_checks_before_resending() {
+    let server = MockServer::start().await;
+
+    // The order lands, but the response is lost.
+    Mock::given(method("POST"))
+        .and(path("/0/private/AddOrder"))
+        .respond_with(unavailable())
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/0/private/OpenOrders"))
+        .and(body_string_contains("cl_ord_id=grid-7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": { "open": {
+                "OABCDE-12345-FGHIJK": {
+                    "refid": null,
+                    "userref": 0,
+                    "cl_ord_id": "grid-7",
+                    "status": "open",
+                    "opentm": 1705312800.1,
+                    "starttm": 0,
+                    "expiretm": 0,
+                    "descr": {
+                        "pair": "XBTUSD",
+                        "type": "buy",
+                        "ordertype": "limit",
+                        "price": "40000.0",
+                        "price2": "0",
+                        "leverage": "none",
+                        "order": "buy 1.00000000 XBTUSD @ limit 40000.0",
+                        "close": ""
+                    },
+                    "vol": "1.00000000",
+                    "vol_exec": "0.00000000",
+                    "cost": "0.00000",
+                    "fee": "0.00000",
+                    "price": "0.00000",
+                    "misc": "",
+                    "oflags": "fciq"
+                }
+            } }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/0/private/ClosedOrders"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": { "closed": {}, "count": 0 }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = AddOrderRequest::new("XBTUSD", BuySell::Buy, OrderType::Limit, Decimal::ONE)
+        .price(Decimal::from(40000))
+        .cl_ord_id("grid-7");
+    let response = build_client(&server).add_order(&request).await.unwrap();
+
+    assert_eq!(response.txid, Some(vec!["OABCDE-12345-FGHIJK".to_string()]));
+    assert_eq!(
+        response.descr.order,
+        "buy 1.00000000 XBTUSD @ limit 40000.0"
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_nonce_is_returned_by_default() {
+    let server = MockServer::start().await;
+    mount_nonce_rejection(&server).await;
+
+    let err = build_client(&server)
+        .get_account_balance()
+        .await
+        .unwrap_err();
+    assert!(matches!(err, KrakenError::Api(e) if e.is_invalid_nonce()));
+}
+
+#[tokio::test]
+async fn test_invalid_nonce_recovery_resends_once() {
+    let server = MockServer::start().await;
+    mount_nonce_rejection(&server).await;
+
+    let secret = STANDARD.encode("test_secret");
+    let client = SpotRestClient::builder()
+        .base_url(server.uri())
+        .credentials(Arc::new(StaticCredentials::new("test_key", secret)))
+        .retry_policy(RetryPolicy::new().recover_invalid_nonce(true))
+        .build();
+
+    let balances = client.get_account_balance().await.unwrap();
+    assert_eq!(balances["ZUSD"], Decimal::from(100));
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    let nonce = |body: &[u8]| -> u64 {
+        let body = std::str::from_utf8(body).unwrap();
+        body.strip_prefix("nonce=").unwrap().parse().unwrap()
+    };
+    assert!(nonce(&requests[1].body) > nonce(&requests[0].body));
+}
+
+/// Kraken's per-key nonce check: accepts only nonces above the highest seen.
+struct SharedKeyNonces(Mutex<u64>);
+
+impl Respond for SharedKeyNonces {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body = std::str::from_utf8(&request.body).unwrap();
+        let nonce: u64 = body.strip_prefix("nonce=").unwrap().parse().unwrap();
+        let mut highest = self.0.lock().unwrap();
+        if nonce <= *highest {
+            return ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "error": ["EAPI:Invalid nonce"]
+            }));
+        }
+        *highest = nonce;
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": [],
+            "result": { "ZUSD": "100.0000" }
+        }))
+    }
+}
+
+/// Nonces from a clock running ahead of this machine's.
+struct SkewedNonce(IncreasingNonce, u64);
+
+impl NonceProvider for SkewedNonce {
+    fn next_nonce(&self) -> u64 {
+        self.0.next_nonce() + self.1
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_nonce_recovery_overtakes_client_sharing_key() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/0/private/Balance"))
+        .respond_with(SharedKeyNonces(Mutex::new(0)))
+        .mount(&server)
+        .await;
+
+    let secret = STANDARD.encode("test_secret");
+    let credentials = Arc::new(StaticCredentials::new("test_key", secret));
+    let other = SpotRestClient::builder()
+        .base_url(server.uri())
+        .credentials(credentials.clone())
+        .nonce_provider(Arc::new(SkewedNonce(IncreasingNonce::new(), 200_000)))
+        .build();
+    let client = SpotRestClient::builder()
+        .base_url(server.uri())
+        .credentials(credentials)
+        .retry_policy(RetryPolicy::new().recover_invalid_nonce(true))
+        .build();
+
+    other.get_account_balance().await.unwrap();
+    let balances = client.get_account_balance().await.unwrap();
+    assert_eq!(balances["ZUSD"], Decimal::from(100));
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 3);
+}